@@ -1,3 +1,13 @@
 fn main() {
+    // The CLI companion finds the app's settings without an app handle, so
+    // it gets the identifier from the same config the app is built with.
+    let config = std::fs::read_to_string("tauri.conf.json").expect("read tauri.conf.json");
+    let config: serde_json::Value = serde_json::from_str(&config).expect("parse tauri.conf.json");
+    let identifier = config["identifier"]
+        .as_str()
+        .expect("tauri.conf.json sets an identifier");
+    println!("cargo:rustc-env=GNOSIS_VPN_APP_IDENTIFIER={identifier}");
+    println!("cargo:rerun-if-changed=tauri.conf.json");
+
     tauri_build::build()
 }
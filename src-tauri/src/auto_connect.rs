@@ -0,0 +1,418 @@
+//! Automatic (re)connects: the one-off startup connect and the "always-on"
+//! reconnect after a connection drops without the user disconnecting.
+//!
+//! Both go through [`AutoConnect`], a small state machine fed with every
+//! [`ConnectionState`] the status loop sees. It decides when the next
+//! `Connect` is due; `commands.rs` sleeps, asks [`AutoConnect::take_due`]
+//! whether the attempt is still wanted, and sends it. An explicit user
+//! connect or disconnect always wins over a pending attempt.
+
+use serde::Serialize;
+
+use std::time::Duration;
+
+use crate::polling::Backoff;
+use crate::types::ConnectionState;
+
+pub const AUTO_CONNECT_EVENT: &str = "auto-connect";
+
+/// The startup connect gives up after this many attempts; always-on keeps
+/// trying (at the backoff cap) until the user disconnects.
+const STARTUP_MAX_ATTEMPTS: u32 = 5;
+
+fn backoff() -> Backoff {
+    Backoff::new(Duration::from_secs(2), Duration::from_secs(60))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum Trigger {
+    Startup,
+    Dropped,
+}
+
+/// Broadcast as `auto-connect` so the UI can show "reconnecting (attempt n)".
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind")]
+pub enum AutoConnectEvent {
+    /// Attempt `attempt` is scheduled `delay_ms` from now.
+    Attempt {
+        destination_id: String,
+        trigger: Trigger,
+        attempt: u32,
+        delay_ms: u64,
+    },
+    Succeeded {
+        destination_id: String,
+        attempts: u32,
+    },
+    GaveUp {
+        destination_id: String,
+        attempts: u32,
+    },
+    /// The user connected/disconnected, or always-on was switched off.
+    Cancelled,
+}
+
+/// A `Connect` the caller should send after `delay`, if
+/// [`AutoConnect::take_due`] still agrees by then.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Scheduled {
+    pub generation: u64,
+    pub delay: Duration,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Step {
+    pub event: Option<AutoConnectEvent>,
+    pub schedule: Option<Scheduled>,
+}
+
+#[derive(Debug)]
+struct Goal {
+    destination_id: String,
+    trigger: Trigger,
+    attempt: u32,
+    /// A `Connect` is scheduled or in flight and not yet reflected in status.
+    pending: bool,
+}
+
+#[derive(Debug)]
+pub struct AutoConnect {
+    goal: Option<Goal>,
+    backoff: Backoff,
+    /// Bumped whenever scheduled attempts become stale.
+    generation: u64,
+    /// Exit of the current connection; a drop reconnects to it.
+    last_connected: Option<String>,
+    disconnected: bool,
+    /// Set by an explicit disconnect so the `Disconnected` it causes is not
+    /// taken for a drop.
+    user_disconnecting: bool,
+}
+
+impl Default for AutoConnect {
+    fn default() -> Self {
+        Self {
+            goal: None,
+            backoff: backoff(),
+            generation: 0,
+            last_connected: None,
+            disconnected: true,
+            user_disconnecting: false,
+        }
+    }
+}
+
+impl AutoConnect {
+    /// Startup auto-connect picked `id`; the first attempt is immediate.
+    pub fn start(&mut self, id: String) -> Step {
+        self.set_goal(id, Trigger::Startup);
+        self.schedule()
+    }
+
+    pub fn on_state(&mut self, state: &ConnectionState, always_on: bool) -> Step {
+        if !always_on
+            && self
+                .goal
+                .as_ref()
+                .is_some_and(|goal| goal.trigger == Trigger::Dropped)
+        {
+            return self.cancel();
+        }
+        match state {
+            ConnectionState::Connected(id) => {
+                self.disconnected = false;
+                self.last_connected = Some(id.clone());
+                match self.goal.take() {
+                    Some(goal) => {
+                        self.generation += 1;
+                        self.backoff.reset();
+                        Step {
+                            event: Some(AutoConnectEvent::Succeeded {
+                                destination_id: id.clone(),
+                                attempts: goal.attempt,
+                            }),
+                            schedule: None,
+                        }
+                    }
+                    None => Step::default(),
+                }
+            }
+            ConnectionState::Connecting(id) | ConnectionState::Reconnecting(id) => {
+                self.disconnected = false;
+                match &mut self.goal {
+                    Some(goal) if goal.destination_id == *id => {
+                        goal.pending = false;
+                        Step::default()
+                    }
+                    // someone else picked a different exit
+                    Some(_) => self.cancel(),
+                    None => Step::default(),
+                }
+            }
+            ConnectionState::Disconnecting => Step::default(),
+            ConnectionState::Disconnected => {
+                self.disconnected = true;
+                if std::mem::take(&mut self.user_disconnecting) {
+                    self.last_connected = None;
+                    return Step::default();
+                }
+                match &self.goal {
+                    Some(goal) if !goal.pending => self.schedule(),
+                    Some(_) => Step::default(),
+                    None => match self.last_connected.take() {
+                        Some(id) if always_on => {
+                            self.set_goal(id, Trigger::Dropped);
+                            self.schedule()
+                        }
+                        _ => Step::default(),
+                    },
+                }
+            }
+        }
+    }
+
+    /// Called when a scheduled attempt's delay has passed; returns the exit
+    /// to connect to, or `None` if the attempt is stale.
+    pub fn take_due(&mut self, generation: u64) -> Option<String> {
+        if generation != self.generation {
+            return None;
+        }
+        let goal = self.goal.as_mut()?;
+        if !self.disconnected {
+            // a status in the meantime shows a connection under way
+            goal.pending = false;
+            return None;
+        }
+        Some(goal.destination_id.clone())
+    }
+
+    /// The `Connect` for `generation` came back. Either way the next status
+    /// tells the outcome: a `Disconnected` schedules the next attempt.
+    pub fn attempt_sent(&mut self, generation: u64) {
+        if generation != self.generation {
+            return;
+        }
+        if let Some(goal) = &mut self.goal {
+            goal.pending = false;
+        }
+    }
+
+    pub fn user_connected(&mut self) -> Step {
+        self.user_disconnecting = false;
+        self.cancel()
+    }
+
+    pub fn user_disconnected(&mut self) -> Step {
+        self.user_disconnecting = true;
+        self.cancel()
+    }
+
+    /// The user's disconnect never reached the daemon: a later
+    /// `Disconnected` is a drop again.
+    pub fn user_disconnect_failed(&mut self) -> Step {
+        self.user_disconnecting = false;
+        Step::default()
+    }
+
+    fn set_goal(&mut self, destination_id: String, trigger: Trigger) {
+        self.backoff.reset();
+        self.goal = Some(Goal {
+            destination_id,
+            trigger,
+            attempt: 0,
+            pending: false,
+        });
+    }
+
+    fn cancel(&mut self) -> Step {
+        self.generation += 1;
+        match self.goal.take() {
+            Some(_) => Step {
+                event: Some(AutoConnectEvent::Cancelled),
+                schedule: None,
+            },
+            None => Step::default(),
+        }
+    }
+
+    fn schedule(&mut self) -> Step {
+        let Some(goal) = &mut self.goal else {
+            return Step::default();
+        };
+        if goal.trigger == Trigger::Startup && goal.attempt >= STARTUP_MAX_ATTEMPTS {
+            let event = AutoConnectEvent::GaveUp {
+                destination_id: goal.destination_id.clone(),
+                attempts: goal.attempt,
+            };
+            self.goal = None;
+            self.generation += 1;
+            return Step {
+                event: Some(event),
+                schedule: None,
+            };
+        }
+        goal.attempt += 1;
+        goal.pending = true;
+        let delay = if goal.trigger == Trigger::Startup && goal.attempt == 1 {
+            Duration::ZERO
+        } else {
+            self.backoff.next_delay()
+        };
+        self.generation += 1;
+        Step {
+            event: Some(AutoConnectEvent::Attempt {
+                destination_id: goal.destination_id.clone(),
+                trigger: goal.trigger,
+                attempt: goal.attempt,
+                delay_ms: delay.as_millis() as u64,
+            }),
+            schedule: Some(Scheduled {
+                generation: self.generation,
+                delay,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn connected(id: &str) -> ConnectionState {
+        ConnectionState::Connected(id.to_string())
+    }
+
+    fn connecting(id: &str) -> ConnectionState {
+        ConnectionState::Connecting(id.to_string())
+    }
+
+    fn attempt_of(step: &Step) -> (u32, Trigger) {
+        match &step.event {
+            Some(AutoConnectEvent::Attempt {
+                attempt, trigger, ..
+            }) => (*attempt, *trigger),
+            other => panic!("expected an attempt, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn drop_reconnects_with_backoff_until_connected() {
+        let mut auto = AutoConnect::default();
+        auto.on_state(&connected("a"), true);
+
+        let step = auto.on_state(&ConnectionState::Disconnected, true);
+        assert_eq!(attempt_of(&step), (1, Trigger::Dropped));
+        let first = step.schedule.unwrap();
+        assert!(first.delay >= Duration::from_millis(1600));
+        assert_eq!(auto.take_due(first.generation).as_deref(), Some("a"));
+        auto.attempt_sent(first.generation);
+
+        // daemon tried and failed: the next attempt backs off further
+        auto.on_state(&connecting("a"), true);
+        let step = auto.on_state(&ConnectionState::Disconnected, true);
+        assert_eq!(attempt_of(&step), (2, Trigger::Dropped));
+        assert!(step.schedule.unwrap().delay > first.delay);
+
+        auto.on_state(&connecting("a"), true);
+        let step = auto.on_state(&connected("a"), true);
+        assert_eq!(
+            step.event,
+            Some(AutoConnectEvent::Succeeded {
+                destination_id: "a".to_string(),
+                attempts: 2,
+            })
+        );
+    }
+
+    #[test]
+    fn no_reconnect_without_always_on_or_after_user_disconnect() {
+        let mut auto = AutoConnect::default();
+        auto.on_state(&connected("a"), false);
+        assert_eq!(
+            auto.on_state(&ConnectionState::Disconnected, false),
+            Step::default()
+        );
+
+        auto.on_state(&connected("a"), true);
+        auto.user_disconnected();
+        // a stale poll still showing the connection must not re-arm it
+        auto.on_state(&connected("a"), true);
+        assert_eq!(
+            auto.on_state(&ConnectionState::Disconnected, true),
+            Step::default()
+        );
+    }
+
+    #[test]
+    fn failed_user_disconnect_keeps_reconnect_on_drop() {
+        let mut auto = AutoConnect::default();
+        auto.on_state(&connected("a"), true);
+        auto.user_disconnected();
+        auto.user_disconnect_failed();
+        let step = auto.on_state(&ConnectionState::Disconnected, true);
+        assert!(step.schedule.is_some());
+    }
+
+    #[test]
+    fn user_disconnect_cancels_pending_reconnect() {
+        let mut auto = AutoConnect::default();
+        auto.on_state(&connected("a"), true);
+        let scheduled = auto
+            .on_state(&ConnectionState::Disconnected, true)
+            .schedule
+            .unwrap();
+
+        let step = auto.user_disconnected();
+        assert_eq!(step.event, Some(AutoConnectEvent::Cancelled));
+        assert_eq!(auto.take_due(scheduled.generation), None);
+    }
+
+    #[test]
+    fn turning_always_on_off_cancels_reconnect() {
+        let mut auto = AutoConnect::default();
+        auto.on_state(&connected("a"), true);
+        auto.on_state(&ConnectionState::Disconnected, true);
+        let step = auto.on_state(&ConnectionState::Disconnected, false);
+        assert_eq!(step.event, Some(AutoConnectEvent::Cancelled));
+    }
+
+    #[test]
+    fn startup_connects_immediately_and_gives_up_eventually() {
+        let mut auto = AutoConnect::default();
+        let step = auto.start("a".to_string());
+        assert_eq!(attempt_of(&step), (1, Trigger::Startup));
+        let mut scheduled = step.schedule.unwrap();
+        assert_eq!(scheduled.delay, Duration::ZERO);
+
+        for n in 2..=STARTUP_MAX_ATTEMPTS {
+            assert!(auto.take_due(scheduled.generation).is_some());
+            auto.attempt_sent(scheduled.generation);
+            let step = auto.on_state(&ConnectionState::Disconnected, false);
+            assert_eq!(attempt_of(&step), (n, Trigger::Startup));
+            scheduled = step.schedule.unwrap();
+        }
+        auto.attempt_sent(scheduled.generation);
+        let step = auto.on_state(&ConnectionState::Disconnected, false);
+        assert_eq!(
+            step.event,
+            Some(AutoConnectEvent::GaveUp {
+                destination_id: "a".to_string(),
+                attempts: STARTUP_MAX_ATTEMPTS,
+            })
+        );
+    }
+
+    #[test]
+    fn attempt_is_dropped_once_a_connection_is_under_way() {
+        let mut auto = AutoConnect::default();
+        auto.on_state(&connected("a"), true);
+        let scheduled = auto
+            .on_state(&ConnectionState::Disconnected, true)
+            .schedule
+            .unwrap();
+        // the daemon reconnected on its own before the delay passed
+        auto.on_state(&ConnectionState::Reconnecting("a".to_string()), true);
+        assert_eq!(auto.take_due(scheduled.generation), None);
+    }
+}
@@ -0,0 +1,365 @@
+//! Launch at login (`launchAtLogin`).
+//!
+//! The OS entry is the source of truth: users can remove it from their
+//! desktop's startup settings, so the stored flag is reconciled against it
+//! at startup and whenever the Settings page asks (`refresh_launch_at_login`).
+//!
+//! - Linux: an XDG autostart entry in `$XDG_CONFIG_HOME/autostart`, requested
+//!   through the Background portal where one answers, written directly
+//!   otherwise. Any enabled entry there that runs our binary counts, so an
+//!   entry the portal wrote under another name is found and removed too.
+//! - macOS: a LaunchAgent in `~/Library/LaunchAgents` with `RunAtLoad`.
+//! - Elsewhere launching at login is not supported and reads as off.
+
+use tauri::{AppHandle, Manager};
+
+use std::path::PathBuf;
+
+use crate::settings::{SettingsPatch, SettingsStore};
+
+/// What the OS reports; `false` wherever it cannot be determined.
+pub fn is_enabled(app: &AppHandle) -> bool {
+    match (entry_dir(app), exe_path()) {
+        (Some(dir), Some(exe)) => platform::is_enabled(&dir, &app.config().identifier, &exe),
+        _ => false,
+    }
+}
+
+/// Stores the OS state if the flag disagrees with it and returns it.
+pub fn sync(app: &AppHandle) -> bool {
+    let enabled = is_enabled(app);
+    let store = app.state::<SettingsStore>();
+    if store.current().launch_at_login == enabled {
+        return enabled;
+    }
+    let patch = SettingsPatch {
+        launch_at_login: Some(enabled),
+        ..Default::default()
+    };
+    match store.update(patch) {
+        Ok(snapshot) => crate::settings::publish(app, &snapshot),
+        Err(e) => eprintln!("launch at login: failed to persist state: {e}"),
+    }
+    enabled
+}
+
+/// Creates or removes the OS entry for a changed `launchAtLogin`, then
+/// stores what the OS reports, so a failure flips the toggle back.
+pub fn apply(app: &AppHandle, enabled: bool) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let result = match (entry_dir(&app), exe_path()) {
+            (Some(dir), Some(exe)) => {
+                platform::set_enabled(&dir, &app.config().identifier, &exe, enabled).await
+            }
+            (None, _) => Err("no autostart directory".to_string()),
+            (_, None) => Err("cannot resolve the app executable".to_string()),
+        };
+        if let Err(e) = result {
+            eprintln!("launch at login: cannot turn it {}: {e}", on_off(enabled));
+        }
+        sync(&app);
+    });
+}
+
+#[tauri::command]
+pub fn refresh_launch_at_login(app: AppHandle) -> bool {
+    sync(&app)
+}
+
+fn on_off(enabled: bool) -> &'static str {
+    if enabled { "on" } else { "off" }
+}
+
+/// The AppImage runtime mounts the image at a new path on every run; the
+/// stable path is the image file itself.
+fn exe_path() -> Option<PathBuf> {
+    match std::env::var_os("APPIMAGE") {
+        Some(appimage) if cfg!(target_os = "linux") => Some(PathBuf::from(appimage)),
+        _ => std::env::current_exe().ok(),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn entry_dir(app: &AppHandle) -> Option<PathBuf> {
+    app.path()
+        .config_dir()
+        .ok()
+        .map(|dir| dir.join("autostart"))
+}
+
+#[cfg(target_os = "macos")]
+fn entry_dir(app: &AppHandle) -> Option<PathBuf> {
+    app.path()
+        .home_dir()
+        .ok()
+        .map(|dir| dir.join("Library").join("LaunchAgents"))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn entry_dir(_app: &AppHandle) -> Option<PathBuf> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use ashpd::desktop::background::Background;
+
+    use std::path::{Path, PathBuf};
+
+    const REASON: &str = "Start Gnosis VPN when you log in";
+
+    pub fn is_enabled(dir: &Path, _identifier: &str, exe: &Path) -> bool {
+        !our_entries(dir, exe, true).is_empty()
+    }
+
+    pub async fn set_enabled(
+        dir: &Path,
+        identifier: &str,
+        exe: &Path,
+        enabled: bool,
+    ) -> Result<(), String> {
+        if !enabled {
+            for path in our_entries(dir, exe, false) {
+                std::fs::remove_file(&path)
+                    .map_err(|e| format!("cannot remove {}: {e}", path.display()))?;
+            }
+            return Ok(());
+        }
+        match request_portal(exe).await {
+            Ok(true) if is_enabled(dir, identifier, exe) => return Ok(()),
+            // granted, but the portal may not write where we look
+            Ok(true) => {}
+            Ok(false) => return Err("not granted by the Background portal".to_string()),
+            // the user declined in the portal dialog; respect that
+            Err(ashpd::Error::Response(e)) => return Err(format!("portal request denied: {e}")),
+            Err(e) => {
+                eprintln!("launch at login: Background portal unavailable ({e}), writing the entry")
+            }
+        }
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("cannot create {}: {e}", dir.display()))?;
+        let path = dir.join(format!("{identifier}.desktop"));
+        std::fs::write(&path, desktop_entry(exe))
+            .map_err(|e| format!("cannot write {}: {e}", path.display()))
+    }
+
+    async fn request_portal(exe: &Path) -> Result<bool, ashpd::Error> {
+        let exe = exe.to_string_lossy();
+        let response = Background::request()
+            .reason(REASON)
+            .auto_start(true)
+            .command([exe.as_ref()])
+            .dbus_activatable(false)
+            .send()
+            .await?
+            .response()?;
+        Ok(response.auto_start())
+    }
+
+    /// `.desktop` files in `dir` that run `exe`, disabled ones too unless
+    /// `enabled_only` is set.
+    fn our_entries(dir: &Path, exe: &Path, enabled_only: bool) -> Vec<PathBuf> {
+        let Some(exe_name) = exe.file_name().and_then(|n| n.to_str()) else {
+            return Vec::new();
+        };
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return Vec::new();
+        };
+        entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "desktop"))
+            .filter(|path| {
+                let Ok(contents) = std::fs::read_to_string(path) else {
+                    return false;
+                };
+                match parse_entry(&contents, exe_name) {
+                    Some(enabled) => enabled || !enabled_only,
+                    None => false,
+                }
+            })
+            .collect()
+    }
+
+    fn desktop_entry(exe: &Path) -> String {
+        format!(
+            "[Desktop Entry]\n\
+             Type=Application\n\
+             Name=Gnosis VPN\n\
+             Exec={}\n\
+             X-GNOME-Autostart-enabled=true\n",
+            quote_exec_arg(&exe.to_string_lossy())
+        )
+    }
+
+    /// Quoting rules of the Desktop Entry `Exec` key: reserved characters
+    /// force double quotes, inside which `"`, `` ` ``, `$` and `\` are
+    /// backslash-escaped; the string value then escapes each `\` once more.
+    fn quote_exec_arg(arg: &str) -> String {
+        const RESERVED: &[char] = &[
+            ' ', '\t', '\n', '"', '\'', '\\', '>', '<', '~', '|', '&', ';', '$', '*', '?', '#',
+            '(', ')', '`',
+        ];
+        if !arg.contains(RESERVED) {
+            return arg.to_string();
+        }
+        let mut quoted = String::from("\"");
+        for c in arg.chars() {
+            match c {
+                '"' | '`' | '$' => quoted.push_str("\\\\"),
+                '\\' => quoted.push_str("\\\\\\"),
+                _ => {}
+            }
+            quoted.push(c);
+        }
+        quoted.push('"');
+        quoted
+    }
+
+    /// `Some(enabled)` if the entry's program is `exe_name`, `None` otherwise.
+    /// `Hidden=true` and `X-GNOME-Autostart-enabled=false` switch an entry off.
+    fn parse_entry(contents: &str, exe_name: &str) -> Option<bool> {
+        let mut in_main_group = false;
+        let mut runs_exe = false;
+        let mut enabled = true;
+        for line in contents.lines().map(str::trim) {
+            if line.starts_with('[') {
+                in_main_group = line == "[Desktop Entry]";
+                continue;
+            }
+            if !in_main_group {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            match (key.trim(), value.trim()) {
+                ("Exec", value) => runs_exe = exec_program_name(value) == Some(exe_name),
+                ("Hidden", "true") | ("X-GNOME-Autostart-enabled", "false") => enabled = false,
+                _ => {}
+            }
+        }
+        runs_exe.then_some(enabled)
+    }
+
+    /// File name of the first `Exec` argument, quoted or not.
+    fn exec_program_name(exec: &str) -> Option<&str> {
+        let program = match exec.strip_prefix('"') {
+            Some(rest) => &rest[..rest.find('"')?],
+            None => exec.split_whitespace().next()?,
+        };
+        program.rsplit('/').next().filter(|name| !name.is_empty())
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn recognises_our_entry_and_its_state() {
+            let entry = desktop_entry(Path::new("/usr/bin/gnosis_vpn-app"));
+            assert_eq!(parse_entry(&entry, "gnosis_vpn-app"), Some(true));
+            assert_eq!(parse_entry(&entry, "other-app"), None);
+
+            let hidden = format!("{entry}Hidden=true\n");
+            assert_eq!(parse_entry(&hidden, "gnosis_vpn-app"), Some(false));
+            let gnome_off = entry.replace(
+                "X-GNOME-Autostart-enabled=true",
+                "X-GNOME-Autostart-enabled=false",
+            );
+            assert_eq!(parse_entry(&gnome_off, "gnosis_vpn-app"), Some(false));
+
+            // keys outside the main group do not count
+            let action = "[Desktop Entry]\nExec=other\n[Desktop Action x]\nExec=gnosis_vpn-app\n";
+            assert_eq!(parse_entry(action, "gnosis_vpn-app"), None);
+        }
+
+        #[test]
+        fn quotes_paths_with_reserved_characters() {
+            let path = Path::new("/home/a b/Gnosis VPN.AppImage");
+            let entry = desktop_entry(path);
+            assert!(entry.contains("Exec=\"/home/a b/Gnosis VPN.AppImage\"\n"));
+            assert_eq!(parse_entry(&entry, "Gnosis VPN.AppImage"), Some(true));
+            assert_eq!(quote_exec_arg("/opt/$x"), "\"/opt/\\\\$x\"");
+            assert_eq!(quote_exec_arg("/usr/bin/app"), "/usr/bin/app");
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::path::Path;
+
+    pub fn is_enabled(dir: &Path, identifier: &str, _exe: &Path) -> bool {
+        dir.join(format!("{identifier}.plist")).is_file()
+    }
+
+    pub async fn set_enabled(
+        dir: &Path,
+        identifier: &str,
+        exe: &Path,
+        enabled: bool,
+    ) -> Result<(), String> {
+        let path = dir.join(format!("{identifier}.plist"));
+        if !enabled {
+            return match std::fs::remove_file(&path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                    Err(format!("cannot remove {}: {e}", path.display()))
+                }
+                _ => Ok(()),
+            };
+        }
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("cannot create {}: {e}", dir.display()))?;
+        std::fs::write(&path, launch_agent(identifier, exe))
+            .map_err(|e| format!("cannot write {}: {e}", path.display()))
+    }
+
+    fn launch_agent(identifier: &str, exe: &Path) -> String {
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{}</string>
+    <key>ProgramArguments</key>
+    <array>
+        <string>{}</string>
+    </array>
+    <key>RunAtLoad</key>
+    <true/>
+</dict>
+</plist>
+"#,
+            xml_escape(identifier),
+            xml_escape(&exe.to_string_lossy())
+        )
+    }
+
+    fn xml_escape(value: &str) -> String {
+        value
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+mod platform {
+    use std::path::Path;
+
+    pub fn is_enabled(_dir: &Path, _identifier: &str, _exe: &Path) -> bool {
+        false
+    }
+
+    pub async fn set_enabled(
+        _dir: &Path,
+        _identifier: &str,
+        _exe: &Path,
+        _enabled: bool,
+    ) -> Result<(), String> {
+        Err("not supported on this platform".to_string())
+    }
+}
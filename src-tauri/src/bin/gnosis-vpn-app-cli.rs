@@ -0,0 +1,3 @@
+fn main() -> std::process::ExitCode {
+    gnosis_vpn_app_lib::companion::main()
+}
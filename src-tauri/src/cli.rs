@@ -0,0 +1,174 @@
+//! Command-line arguments accepted by the app binary.
+//!
+//! Parsed by hand: the surface is a handful of flags and pulling in a full
+//! argument parser would also have to tolerate whatever the desktop launcher
+//! or the OS appends (e.g. macOS `-psn_…`), so unknown arguments are ignored.
+//!
+//! The same arguments reach a running app through the single-instance
+//! callback, so launchers, keybindings and scripts can control it:
+//!
+//! - `--connect <id>`, `--connect-best`, `--disconnect`
+//! - `--show <main|settings|logs|usage|updates>`
+//! - `--minimized`: start without showing the main window
+//! - `--quit`: disconnect and quit
+//! - a `gnosisvpn://` link (see `deeplink`)
+
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+use crate::deeplink;
+use crate::tray::WindowTarget;
+
+pub const SOCKET_PATH_FLAG: &str = "--socket-path";
+
+/// Something the launch arguments ask the app to do, in the order given.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LaunchAction {
+    Connect(String),
+    /// Connect to the exit automatic selection picks.
+    ConnectBest,
+    Disconnect,
+    Show(WindowTarget),
+    Quit,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LaunchArgs {
+    /// Root daemon socket, overriding the env var, settings and default.
+    /// Only read at startup.
+    pub socket_path: Option<PathBuf>,
+    pub actions: Vec<LaunchAction>,
+    /// Overrides `startMinimized` for this launch.
+    pub minimized: bool,
+}
+
+impl LaunchArgs {
+    pub fn from_env() -> Self {
+        Self::parse(std::env::args_os().skip(1))
+    }
+
+    /// Parses arguments without the program name.
+    pub fn parse<I>(args: I) -> Self
+    where
+        I: IntoIterator<Item = OsString>,
+    {
+        let mut parsed = Self::default();
+        let mut args = args.into_iter().peekable();
+        while let Some(arg) = args.next() {
+            let Some(arg_str) = arg.to_str() else {
+                continue;
+            };
+            let (flag, inline) = match arg_str.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => (flag, Some(value.to_string())),
+                _ => (arg_str, None),
+            };
+            // a flag's value is either inline (`--show=logs`) or the next
+            // argument, unless that is another flag
+            let mut value = || {
+                inline
+                    .clone()
+                    .or_else(|| {
+                        args.next_if(|v| !v.to_str().is_some_and(|v| v.starts_with("--")))
+                            .and_then(|v| v.into_string().ok())
+                    })
+                    .filter(|v| !v.is_empty() && !v.starts_with("--"))
+            };
+            match flag {
+                SOCKET_PATH_FLAG => parsed.socket_path = value().map(PathBuf::from),
+                "--connect" => parsed.actions.extend(value().map(LaunchAction::Connect)),
+                "--connect-best" => parsed.actions.push(LaunchAction::ConnectBest),
+                "--disconnect" => parsed.actions.push(LaunchAction::Disconnect),
+                "--show" => match value() {
+                    Some(name) => match WindowTarget::parse(&name) {
+                        Some(target) => parsed.actions.push(LaunchAction::Show(target)),
+                        None => eprintln!("Ignoring --show {name}: unknown window"),
+                    },
+                    None => eprintln!("Ignoring --show without a window"),
+                },
+                "--minimized" => parsed.minimized = true,
+                "--quit" => parsed.actions.push(LaunchAction::Quit),
+                link if link.starts_with(&format!("{}:", deeplink::SCHEME)) => {
+                    match deeplink::parse(link) {
+                        Ok(action) => parsed.actions.push(action),
+                        Err(e) => eprintln!("Ignoring link {link}: {e}"),
+                    }
+                }
+                _ => {}
+            }
+        }
+        parsed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> LaunchArgs {
+        LaunchArgs::parse(args.iter().map(OsString::from))
+    }
+
+    #[test]
+    fn parses_socket_path_in_both_forms() {
+        assert_eq!(
+            parse(&["--socket-path", "/tmp/a.sock"]).socket_path,
+            Some(PathBuf::from("/tmp/a.sock"))
+        );
+        assert_eq!(
+            parse(&["--socket-path=/tmp/b.sock"]).socket_path,
+            Some(PathBuf::from("/tmp/b.sock"))
+        );
+    }
+
+    #[test]
+    fn collects_actions_in_order() {
+        let args = parse(&[
+            "--disconnect",
+            "--connect",
+            "exit-1",
+            "--show=logs",
+            "--minimized",
+            "--connect-best",
+            "--quit",
+        ]);
+        assert_eq!(
+            args.actions,
+            [
+                LaunchAction::Disconnect,
+                LaunchAction::Connect("exit-1".to_string()),
+                LaunchAction::Show(WindowTarget::Logs),
+                LaunchAction::ConnectBest,
+                LaunchAction::Quit,
+            ]
+        );
+        assert!(args.minimized);
+    }
+
+    #[test]
+    fn takes_links_and_drops_invalid_actions() {
+        let args = parse(&[
+            "/usr/bin/gnosis_vpn-app",
+            "gnosisvpn://settings/usage",
+            "gnosisvpn://uninstall",
+            "--show",
+            "nowhere",
+            "--connect=",
+        ]);
+        assert_eq!(args.actions, [LaunchAction::Show(WindowTarget::Usage)]);
+    }
+
+    #[test]
+    fn flag_values_are_never_flags() {
+        let args = parse(&["--connect", "--minimized"]);
+        assert!(args.actions.is_empty());
+        assert!(args.minimized);
+        assert!(parse(&["--connect=--quit"]).actions.is_empty());
+    }
+
+    #[test]
+    fn ignores_unknown_and_empty_arguments() {
+        assert_eq!(parse(&["-psn_0_12345", "--verbose"]), LaunchArgs::default());
+        assert_eq!(parse(&["--socket-path="]).socket_path, None);
+        assert_eq!(parse(&["--socket-path"]).socket_path, None);
+    }
+}
@@ -1,6 +1,4 @@
 use gnosis_vpn_lib::command;
-use gnosis_vpn_lib::route_health::RouteHealthState;
-use gnosis_vpn_lib::socket::root as root_socket;
 
 use serde::Serialize;
 use tauri::{AppHandle, Emitter, Manager, State};
@@ -12,19 +10,30 @@ use std::io::{self, BufReader};
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
+use tokio::sync::Notify;
 use tokio::task::spawn_blocking;
 use tokio::time::{self, Instant};
 
+use crate::auto_connect::{AUTO_CONNECT_EVENT, AutoConnect, Step};
+use crate::cli::LaunchAction;
+use crate::daemon::{DaemonClient, DaemonSocket};
+use crate::error::{AppError, ErrorCode};
+use crate::failover::FAILOVER_EVENT;
 use crate::icons::{self, TrayIconState};
+use crate::latency::LatencyHistory;
+use crate::metrics;
+use crate::notifications;
+use crate::polling::{self, PollSink, StatusTracker};
+use crate::sessions::{self, SessionHistory};
 use crate::tray;
 use crate::types::{BalanceResponse, ConnectionState, StatusResponse};
 use crate::{AppStateCache, BalancePollingHandle, PollingExit, StatusPollingHandle};
 
 /// Semver requirements for compatible gnosis_vpn-client service versions,
 /// e.g. "0.93" (any 0.93.x) or ">=0.93.0" (0.93.0 and all later versions).
-const COMPATIBLE_VERSIONS: &[&str] = &["0.95"];
+pub(crate) const COMPATIBLE_VERSIONS: &[&str] = &["0.95"];
 
-fn is_version_compatible(version: &str) -> bool {
+pub(crate) fn is_version_compatible(version: &str) -> bool {
     let Ok(version) = semver::Version::parse(version.trim()) else {
         return false;
     };
@@ -45,9 +54,10 @@ pub fn get_platform() -> &'static str {
 #[tauri::command]
 pub async fn check_update(
     skip_vpn: bool,
-) -> Result<gnosis_vpn_lib::check_update::Manifest, String> {
+    socket: State<'_, DaemonSocket>,
+) -> Result<gnosis_vpn_lib::check_update::Manifest, AppError> {
     let client = reqwest::Client::new();
-    let socket_path = PathBuf::from(root_socket::DEFAULT_PATH);
+    let socket_path = socket.path();
     let path_ref = if skip_vpn {
         None
     } else {
@@ -56,104 +66,211 @@ pub async fn check_update(
 
     gnosis_vpn_lib::check_update::download(&client, path_ref)
         .await
-        .map_err(|e| match e {
-            gnosis_vpn_lib::check_update::Error::VpnNotConnected => "VpnNotConnected".to_string(),
-            gnosis_vpn_lib::check_update::Error::Integrity(msg) => format!("Integrity: {msg}"),
-            gnosis_vpn_lib::check_update::Error::Other(msg) => msg,
-        })
+        .map_err(AppError::from)
 }
 
-async fn query_info() -> Result<command::InfoResponse, String> {
-    let p = PathBuf::from(root_socket::DEFAULT_PATH);
-    let resp = root_socket::process_cmd(&p, &command::Command::Info)
-        .await
-        .map_err(|e| e.to_string())?;
-    match resp {
-        command::Response::Info(info) => Ok(info),
-        _ => Err("Unexpected response type".to_string()),
-    }
+#[tauri::command]
+pub async fn connect(app: AppHandle, id: String) -> Result<command::ConnectResponse, AppError> {
+    connect_to(&app, id).await
 }
 
-async fn start_client_worker(keep_alive: Duration) -> Result<(), String> {
-    let p = PathBuf::from(root_socket::DEFAULT_PATH);
-    let resp = root_socket::process_cmd(&p, &command::Command::StartClient(keep_alive))
-        .await
-        .map_err(|e| e.to_string())?;
-    match resp {
-        command::Response::StartClient(_) => Ok(()),
-        _ => Err("Unexpected response type".to_string()),
-    }
+#[tauri::command]
+pub async fn disconnect(app: AppHandle) -> Result<command::DisconnectResponse, AppError> {
+    disconnect_from(&app).await
 }
 
-#[tauri::command]
-pub async fn connect(
+/// A user's connect, from a webview or the control socket.
+pub(crate) async fn connect_to(
+    app: &AppHandle,
     id: String,
-    polling_state: State<'_, Mutex<StatusPollingHandle>>,
-) -> Result<command::ConnectResponse, String> {
-    let p = PathBuf::from(root_socket::DEFAULT_PATH);
+) -> Result<command::ConnectResponse, AppError> {
+    // the user's choice replaces any pending automatic attempt
+    update_auto_connect(app, AutoConnect::user_connected);
     let cmd = command::Command::Connect(id);
-    let resp = root_socket::process_cmd(&p, &cmd)
-        .await
-        .map_err(|e| e.to_string())?;
+    let resp = app.state::<DaemonSocket>().process_cmd(&cmd).await?;
     match resp {
         command::Response::Connect(resp) => {
-            if let Ok(guard) = polling_state.lock() {
-                guard.trigger.notify_one();
-            }
+            trigger_status_poll(app);
             Ok(resp)
         }
-        _ => Err("Unexpected response type".to_string()),
+        other => Err(AppError::unexpected(&other)),
     }
 }
 
-#[tauri::command]
-pub async fn disconnect(
-    polling_state: State<'_, Mutex<StatusPollingHandle>>,
-) -> Result<command::DisconnectResponse, String> {
-    let p = PathBuf::from(root_socket::DEFAULT_PATH);
-    let cmd = command::Command::Disconnect;
-    let resp = root_socket::process_cmd(&p, &cmd)
-        .await
-        .map_err(|e| e.to_string())?;
-    match resp {
-        command::Response::Disconnect(resp) => {
-            if let Ok(guard) = polling_state.lock() {
-                guard.trigger.notify_one();
+/// The exit a one-click connect picks: the startup selection.
+fn connect_target(status: &StatusResponse, settings: &crate::settings::Settings) -> Option<String> {
+    polling::pick_startup_target(
+        &status.destinations,
+        &status.latency,
+        &settings.preferred_exits,
+        settings.exit_node_sort_order,
+    )
+}
+
+/// The tray's Connect/Disconnect item: disconnects while a connection is
+/// up or being made, otherwise connects to the startup selection for the
+/// latest cached status.
+pub(crate) async fn toggle_connection(app: &AppHandle) -> Result<(), AppError> {
+    let status = app
+        .state::<AppStateCache>()
+        .status
+        .borrow()
+        .clone()
+        .and_then(|result| result.ok())
+        .flatten();
+    let Some(status) = status else {
+        return Err(AppError::new(
+            ErrorCode::NotAvailable,
+            "No status from the service yet",
+        ));
+    };
+    match ConnectionState::from(&status) {
+        ConnectionState::Connected(_)
+        | ConnectionState::Connecting(_)
+        | ConnectionState::Reconnecting(_) => disconnect_from(app).await.map(|_| ()),
+        ConnectionState::Disconnecting => Ok(()),
+        ConnectionState::Disconnected => {
+            let settings = app.state::<crate::settings::SettingsStore>().current();
+            let Some(id) = connect_target(&status, &settings) else {
+                return Err(AppError::new(
+                    ErrorCode::NotAvailable,
+                    "No exit is ready to connect",
+                ));
+            };
+            connect_to(app, id).await.map(|_| ())
+        }
+    }
+}
+
+/// How long a launch action that needs the exits (a fresh launch's
+/// `--connect`) waits for the first status.
+const LAUNCH_STATUS_WAIT: Duration = Duration::from_secs(30);
+
+/// Quitting must not wait out the full Disconnect timeout.
+const QUIT_DISCONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Carries out launch arguments and links, from this launch or forwarded
+/// by a second one, in order and in the background.
+pub(crate) fn run_launch_actions(app: &AppHandle, actions: Vec<LaunchAction>) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        for action in actions {
+            if let Err(e) = run_launch_action(&app, &action).await {
+                eprintln!("Launch action {action:?} failed: {e}");
             }
-            Ok(resp)
         }
-        _ => Err("Unexpected response type".to_string()),
+    });
+}
+
+async fn run_launch_action(app: &AppHandle, action: &LaunchAction) -> Result<(), AppError> {
+    match action {
+        LaunchAction::Show(target) => tray::show_window_target(app, *target),
+        LaunchAction::Disconnect => {
+            disconnect_from(app).await?;
+        }
+        LaunchAction::Connect(id) => {
+            let status = first_status(app).await?;
+            if !status
+                .destinations
+                .iter()
+                .any(|ds| ds.destination.id == *id)
+            {
+                return Err(AppError::new(ErrorCode::NotAvailable, "Unknown exit").with_details(id));
+            }
+            connect_to(app, id.clone()).await?;
+        }
+        LaunchAction::ConnectBest => {
+            let status = first_status(app).await?;
+            let settings = app.state::<crate::settings::SettingsStore>().current();
+            let Some(id) = connect_target(&status, &settings) else {
+                return Err(AppError::new(
+                    ErrorCode::NotAvailable,
+                    "No exit is ready to connect",
+                ));
+            };
+            connect_to(app, id).await?;
+        }
+        LaunchAction::Quit => disconnect_and_quit(app).await,
     }
+    Ok(())
 }
 
-async fn query_balance() -> (Duration, Result<Option<BalanceResponse>, String>) {
-    let p = PathBuf::from(root_socket::DEFAULT_PATH);
-    let resp = root_socket::process_cmd(&p, &command::Command::Balance).await;
-    match resp {
-        Ok(command::Response::Balance(Ok(balance_resp))) => {
-            (Duration::from_secs(60), Ok(Some(balance_resp.into())))
+/// The latest status, waiting for the first one after a fresh launch.
+async fn first_status(app: &AppHandle) -> Result<StatusResponse, AppError> {
+    let mut status = app.state::<AppStateCache>().status.subscribe();
+    let received = status.wait_for(|s| matches!(s, Some(Ok(Some(_)))));
+    let not_available = || AppError::new(ErrorCode::NotAvailable, "No status from the service yet");
+    let result = time::timeout(LAUNCH_STATUS_WAIT, received)
+        .await
+        .map_err(|_| not_available())?
+        .map_err(|_| not_available())?;
+    result
+        .clone()
+        .and_then(|r| r.ok())
+        .flatten()
+        .ok_or_else(not_available)
+}
+
+/// The tray's and `--quit`'s exit: disconnect, but only briefly, then quit.
+pub(crate) async fn disconnect_and_quit(app: &AppHandle) {
+    // no auto-reconnect may race the disconnect below
+    update_auto_connect(app, AutoConnect::user_disconnected);
+    let daemon = app.state::<DaemonSocket>();
+    let disconnect = daemon.process_cmd(&command::Command::Disconnect);
+    match time::timeout(QUIT_DISCONNECT_TIMEOUT, disconnect).await {
+        Ok(Err(e)) => eprintln!("Disconnect on quit failed: {e}"),
+        Err(_) => eprintln!("Disconnect on quit timed out, exiting anyway"),
+        Ok(Ok(_)) => {}
+    }
+    app.exit(0);
+}
+
+/// A user's disconnect, from a webview or the control socket.
+pub(crate) async fn disconnect_from(
+    app: &AppHandle,
+) -> Result<command::DisconnectResponse, AppError> {
+    // before the round trip: the next status may already show Disconnected
+    app.state::<SessionHistory>().mark_user_disconnect();
+    update_auto_connect(app, AutoConnect::user_disconnected);
+    let cmd = command::Command::Disconnect;
+    let result = match app.state::<DaemonSocket>().process_cmd(&cmd).await {
+        Ok(command::Response::Disconnect(resp)) => Ok(resp),
+        Ok(other) => Err(AppError::unexpected(&other)),
+        Err(e) => Err(e),
+    };
+    match result {
+        Ok(_) => trigger_status_poll(app),
+        Err(_) => {
+            // still connected: a later drop is not the user's doing
+            app.state::<SessionHistory>().clear_user_disconnect();
+            update_auto_connect(app, AutoConnect::user_disconnect_failed);
         }
-        Ok(command::Response::Balance(Err(_))) => (Duration::from_secs(5), Ok(None)),
-        Ok(command::Response::WorkerOffline) => (Duration::from_secs(5), Ok(None)),
-        Ok(unexpected) => (
-            Duration::from_secs(5),
-            Err(format!("Unexpected balance response: {:?}", unexpected)),
-        ),
-        Err(e) => (Duration::from_secs(5), Err(e.to_string())),
+    }
+    result
+}
+
+fn trigger_status_poll(app: &AppHandle) {
+    if let Ok(guard) = app.state::<Mutex<StatusPollingHandle>>().lock() {
+        guard.trigger.notify_one();
     }
 }
 
 #[cfg(target_os = "macos")]
 #[allow(unexpected_cfgs)]
 #[tauri::command]
-pub async fn set_app_icon(app: AppHandle, icon_name: String) -> Result<(), String> {
+pub async fn set_app_icon(app: AppHandle, icon_name: String) -> Result<(), AppError> {
     use dispatch::Queue;
     use std::sync::mpsc;
 
     let icon_data = app
         .state::<icons::IconCache>()
         .app_icon_bytes(&icon_name)
-        .ok_or_else(|| format!("Invalid icon name: {icon_name}"))?;
+        .ok_or_else(|| {
+            AppError::new(
+                ErrorCode::InvalidIcon,
+                format!("Invalid icon name: {icon_name}"),
+            )
+        })?;
 
     spawn_blocking(move || {
         let (tx, rx) = mpsc::channel();
@@ -198,16 +315,22 @@ pub async fn set_app_icon(app: AppHandle, icon_name: String) -> Result<(), Strin
             .map_err(|e| format!("Failed to receive result from main thread: {}", e))?
     })
     .await
-    .map_err(|e| format!("set_app_icon: blocking task panicked: {e}"))?
+    .map_err(|e| AppError::internal(format!("set_app_icon: blocking task panicked: {e}")))?
+    .map_err(AppError::internal)
 }
 
 #[cfg(target_os = "linux")]
 #[tauri::command]
-pub async fn set_app_icon(app: AppHandle, icon_name: String) -> Result<(), String> {
+pub async fn set_app_icon(app: AppHandle, icon_name: String) -> Result<(), AppError> {
     let image = app
         .state::<icons::IconCache>()
         .app_icon_image(&icon_name)
-        .ok_or_else(|| format!("Invalid icon name: {icon_name}"))?;
+        .ok_or_else(|| {
+            AppError::new(
+                ErrorCode::InvalidIcon,
+                format!("Invalid icon name: {icon_name}"),
+            )
+        })?;
 
     let mut errors = Vec::new();
 
@@ -226,26 +349,38 @@ pub async fn set_app_icon(app: AppHandle, icon_name: String) -> Result<(), Strin
     if errors.is_empty() {
         Ok(())
     } else {
-        Err(errors.join("; "))
+        Err(AppError::internal(errors.join("; ")))
     }
 }
 
 #[tauri::command]
-pub async fn compress_logs(log_path: String, dest_path: String) -> Result<(), String> {
+pub async fn compress_logs(log_path: String, dest_path: String) -> Result<(), AppError> {
+    let invalid_path = |message: &str, e: io::Error| {
+        AppError::new(ErrorCode::InvalidPath, message).with_details(e)
+    };
+    let io_error =
+        |message: &str, e: io::Error| AppError::new(ErrorCode::Io, message).with_details(e);
+
     let log_file = PathBuf::from(log_path)
         .canonicalize()
-        .map_err(|e| format!("Cannot resolve log file path: {e}"))?;
+        .map_err(|e| invalid_path("Cannot resolve log file path", e))?;
 
     let dest_path_buf = PathBuf::from(dest_path);
-    let dest_parent = dest_path_buf
-        .parent()
-        .ok_or_else(|| "Destination path must include a parent directory".to_string())?;
+    let dest_parent = dest_path_buf.parent().ok_or_else(|| {
+        AppError::new(
+            ErrorCode::InvalidPath,
+            "Destination path must include a parent directory",
+        )
+    })?;
     let dest_dir = dest_parent
         .canonicalize()
-        .map_err(|e| format!("Cannot resolve destination directory: {e}"))?;
-    let dest_file_name = dest_path_buf
-        .file_name()
-        .ok_or_else(|| "Destination path must include a file name".to_string())?;
+        .map_err(|e| invalid_path("Cannot resolve destination directory", e))?;
+    let dest_file_name = dest_path_buf.file_name().ok_or_else(|| {
+        AppError::new(
+            ErrorCode::InvalidPath,
+            "Destination path must include a file name",
+        )
+    })?;
     let dest_file_raw = dest_dir.join(dest_file_name);
     let dest_file = if dest_file_raw.extension().and_then(|e| e.to_str()) == Some("zst") {
         dest_file_raw.clone()
@@ -255,45 +390,33 @@ pub async fn compress_logs(log_path: String, dest_path: String) -> Result<(), St
 
     spawn_blocking(move || {
         let input_file =
-            File::open(log_file).map_err(|e| format!("Failed to open log file: {}", e))?;
+            File::open(log_file).map_err(|e| io_error("Failed to open log file", e))?;
         let output_file =
-            File::create(dest_file).map_err(|e| format!("Failed to create output file: {}", e))?;
+            File::create(dest_file).map_err(|e| io_error("Failed to create output file", e))?;
 
         let mut encoder = Encoder::new(&output_file, 5)
-            .map_err(|e| format!("Failed to create zstd encoder: {}", e))?;
+            .map_err(|e| io_error("Failed to create zstd encoder", e))?;
         io::copy(&mut BufReader::new(input_file), &mut encoder)
-            .map_err(|e| format!("Failed to compress log file: {}", e))?;
+            .map_err(|e| io_error("Failed to compress log file", e))?;
         encoder
             .finish()
-            .map_err(|e| format!("Failed to finalize compression: {}", e))?;
+            .map_err(|e| io_error("Failed to finalize compression", e))?;
         Ok(())
     })
     .await
-    .map_err(|e| format!("compress_logs: blocking task panicked: {e}"))?
-}
-
-pub async fn stop_client() -> Result<(), String> {
-    let p = PathBuf::from(root_socket::DEFAULT_PATH);
-    let cmd = command::Command::StopClient;
-    let resp = root_socket::process_cmd(&p, &cmd)
-        .await
-        .map_err(|e| e.to_string())?;
-    match resp {
-        command::Response::StopClient(_resp) => Ok(()),
-        _ => Err("Unexpected response type".to_string()),
-    }
+    .map_err(|e| AppError::internal(format!("compress_logs: blocking task panicked: {e}")))?
 }
 
 #[derive(Serialize)]
 pub struct CachedState {
-    pub status: Result<StatusResponse, String>,
-    pub balance: Result<BalanceResponse, String>,
+    pub status: Result<StatusResponse, AppError>,
+    pub balance: Result<BalanceResponse, AppError>,
     pub service_info: Option<command::InfoResponse>,
 }
 
-fn flatten_cached<T>(v: Option<Result<Option<T>, String>>) -> Result<T, String> {
+fn flatten_cached<T>(v: Option<Result<Option<T>, AppError>>) -> Result<T, AppError> {
     match v {
-        None | Some(Ok(None)) => Err("not available".to_string()),
+        None | Some(Ok(None)) => Err(AppError::new(ErrorCode::NotAvailable, "not available")),
         Some(Ok(Some(inner))) => Ok(inner),
         Some(Err(e)) => Err(e),
     }
@@ -301,6 +424,10 @@ fn flatten_cached<T>(v: Option<Result<Option<T>, String>>) -> Result<T, String>
 
 #[tauri::command]
 pub fn get_cached_state(cache: State<'_, AppStateCache>) -> CachedState {
+    cached_state(&cache)
+}
+
+pub(crate) fn cached_state(cache: &AppStateCache) -> CachedState {
     CachedState {
         status: flatten_cached(cache.status.borrow().clone()),
         balance: flatten_cached(cache.balance.borrow().clone()),
@@ -308,6 +435,211 @@ pub fn get_cached_state(cache: State<'_, AppStateCache>) -> CachedState {
     }
 }
 
+/// Feeds one published status into tray, dock, session history,
+/// auto-connect and failover.
+async fn apply_status(
+    app: &AppHandle,
+    tracker: &mut StatusTracker,
+    trigger: &Arc<Notify>,
+    result: &Result<Option<StatusResponse>, AppError>,
+) {
+    if result.is_err() {
+        app.state::<metrics::PollCounters>().status_error();
+    }
+    if let Ok(Some(status)) = result {
+        // Funding level needs the balance poll's data; use the latest cached response.
+        let cached_balance = app.state::<AppStateCache>().balance.borrow().clone();
+        let balance = cached_balance
+            .as_ref()
+            .and_then(|res| res.as_ref().ok())
+            .and_then(|opt| opt.as_ref());
+        let settings = app.state::<crate::settings::SettingsStore>().current();
+        let effects = tracker.on_status(status, balance, &settings);
+        let conn_state = &effects.conn_state;
+        let ended = sessions::record(app, &app.state::<SessionHistory>(), status);
+        notifications::on_status(
+            app,
+            status,
+            conn_state,
+            effects.funds_level,
+            &ended,
+            &settings.notifications,
+        );
+
+        let icon_state = app.state::<Arc<Mutex<icons::IconState>>>();
+        let new_dock_icon = match icon_state.lock() {
+            Ok(mut guard) => guard.apply_status(conn_state, effects.funds_level),
+            Err(e) => {
+                eprintln!("Failed to lock icon state: {}", e);
+                None
+            }
+        };
+
+        // during animation, the heartbeat logic owns app and tray icon changes
+        if !icons::is_animating_state(conn_state) {
+            icons::set_tray_icon_file(app, &app.state::<TrayIconState>(), effects.tray_icon);
+
+            if let Some(icon_name) = new_dock_icon {
+                if let Err(e) = set_app_icon(app.clone(), icon_name).await {
+                    eprintln!("Failed to update app icon: {}", e);
+                }
+            }
+        }
+
+        #[cfg(target_os = "linux")]
+        crate::dbus::publish(
+            app,
+            crate::dbus::VpnProperties::new(conn_state, effects.funds_level, &status.run_mode),
+        );
+
+        let status_item = app.state::<tray::TrayStatusItem>();
+        if let Ok(guard) = status_item.0.lock() {
+            let _ = guard.set_text(conn_state.to_string());
+        };
+
+        let quit_label = match conn_state {
+            ConnectionState::Connected(_)
+            | ConnectionState::Connecting(_)
+            | ConnectionState::Reconnecting(_) => "Disconnect and Quit",
+            _ => "Quit",
+        };
+        let quit_item = app.state::<tray::TrayQuitItem>();
+        if let Ok(guard) = quit_item.0.lock() {
+            let _ = guard.set_text(quit_label);
+        };
+
+        // the balance poll nudges this loop, so the funds line follows it too
+        let funds_item = app.state::<tray::TrayFundsItem>();
+        if let Ok(guard) = funds_item.0.lock() {
+            let _ = guard.set_text(tray::funds_label(effects.funds_level, balance));
+        };
+
+        let (connect_label, connect_enabled) = match conn_state {
+            ConnectionState::Connected(_)
+            | ConnectionState::Connecting(_)
+            | ConnectionState::Reconnecting(_) => ("Disconnect", true),
+            ConnectionState::Disconnecting => ("Disconnecting…", false),
+            ConnectionState::Disconnected => {
+                ("Connect", connect_target(status, &settings).is_some())
+            }
+        };
+        let connect_item = app.state::<tray::TrayConnectItem>();
+        if let Ok(guard) = connect_item.0.lock() {
+            let _ = guard.set_text(connect_label);
+            let _ = guard.set_enabled(connect_enabled);
+        };
+
+        tray::update_tray_text(app, &settings, status, conn_state, effects.funds_level);
+
+        tray::update_exit_menu(
+            app,
+            &tray::exit_entries(status, settings.exit_node_sort_order),
+        );
+
+        let steps = match app.state::<Mutex<AutoConnect>>().lock() {
+            Ok(mut auto) => {
+                let startup = effects.startup_target.map(|id| auto.start(id));
+                let observed = auto.on_state(conn_state, settings.always_on);
+                startup.into_iter().chain([observed]).collect()
+            }
+            Err(e) => {
+                eprintln!("Failed to lock auto-connect state: {e}");
+                Vec::new()
+            }
+        };
+        for step in steps {
+            run_auto_connect_step(app, trigger, step);
+        }
+
+        if let Some(failover) = effects.failover {
+            println!(
+                "Failing over from {} to {}: {}",
+                failover.from, failover.to, failover.reason
+            );
+            app.state::<SessionHistory>()
+                .mark_failover(failover.reason.clone());
+            let _ = app.emit(FAILOVER_EVENT, &failover);
+            let app_clone = app.clone();
+            let trigger_clone = trigger.clone();
+            tauri::async_runtime::spawn(async move {
+                let daemon = app_clone.state::<DaemonSocket>();
+                if let Err(e) = daemon
+                    .process_cmd(&command::Command::Connect(failover.to))
+                    .await
+                {
+                    eprintln!("failover connect failed: {e}");
+                }
+                trigger_clone.notify_one();
+            });
+        }
+    }
+}
+
+impl PollSink for AppHandle {
+    fn publish_status(&self, result: &Result<Option<StatusResponse>, AppError>) {
+        self.state::<AppStateCache>().publish_status(result);
+        let _ = self.emit("status", result);
+    }
+
+    fn publish_balance(&self, result: &Result<Option<BalanceResponse>, AppError>) {
+        self.state::<AppStateCache>().publish_balance(result);
+        let _ = self.emit("balance", result);
+    }
+}
+
+/// Runs `f` on the shared auto-connect state and carries out its result.
+pub(crate) fn update_auto_connect(app: &AppHandle, f: impl FnOnce(&mut AutoConnect) -> Step) {
+    let step = match app.state::<Mutex<AutoConnect>>().lock() {
+        Ok(mut auto) => f(&mut auto),
+        Err(e) => {
+            eprintln!("Failed to lock auto-connect state: {e}");
+            return;
+        }
+    };
+    let trigger = app
+        .state::<Mutex<StatusPollingHandle>>()
+        .lock()
+        .map(|guard| guard.trigger.clone())
+        .map_err(|e| e.to_string());
+    match trigger {
+        Ok(trigger) => run_auto_connect_step(app, &trigger, step),
+        Err(e) => eprintln!("Failed to lock polling state: {e}"),
+    }
+}
+
+/// Emits the step's event and, if an attempt is scheduled, sends the
+/// `Connect` once its delay has passed and it is still wanted.
+fn run_auto_connect_step(app: &AppHandle, trigger: &Arc<Notify>, step: Step) {
+    if let Some(event) = step.event {
+        let _ = app.emit(AUTO_CONNECT_EVENT, event);
+    }
+    let Some(scheduled) = step.schedule else {
+        return;
+    };
+    let app = app.clone();
+    let trigger = trigger.clone();
+    tauri::async_runtime::spawn(async move {
+        time::sleep(scheduled.delay).await;
+        let due = match app.state::<Mutex<AutoConnect>>().lock() {
+            Ok(mut auto) => auto.take_due(scheduled.generation),
+            Err(_) => None,
+        };
+        let Some(id) = due else {
+            return;
+        };
+        let daemon = app.state::<DaemonSocket>();
+        match daemon.process_cmd(&command::Command::Connect(id)).await {
+            Ok(command::Response::Connect(_)) => {}
+            Ok(other) => eprintln!("auto-connect: {}", AppError::unexpected(&other)),
+            Err(e) => eprintln!("auto-connect failed: {e}"),
+        }
+        if let Ok(mut auto) = app.state::<Mutex<AutoConnect>>().lock() {
+            auto.attempt_sent(scheduled.generation);
+        }
+        trigger.notify_one();
+    });
+}
+
 async fn spawn_polling_tasks(app_handle: AppHandle) -> Result<(), String> {
     let polling_state = app_handle.state::<Mutex<StatusPollingHandle>>();
     let bal_polling_state = app_handle.state::<Mutex<BalancePollingHandle>>();
@@ -338,9 +670,12 @@ async fn spawn_polling_tasks(app_handle: AppHandle) -> Result<(), String> {
     };
     let bal_trigger = trigger.clone();
 
+    let mut socket_rx = app_handle.state::<DaemonSocket>().subscribe();
+
     let app = app_handle.clone();
     let join_handle = tauri::async_runtime::spawn(async move {
-        let mut startup_connect_done = false;
+        let mut tracker = StatusTracker::default();
+        let mut backoff = polling::unresponsive_backoff();
         let tick_timeout = time::sleep(Duration::ZERO);
         tokio::pin!(tick_timeout);
         loop {
@@ -349,78 +684,23 @@ async fn spawn_polling_tasks(app_handle: AppHandle) -> Result<(), String> {
                     println!("Status tick received cancellation signal, exiting...");
                     break PollingExit::Cancelled;
                 }
+                Ok(()) = socket_rx.changed() => {
+                    println!("Daemon socket changed, restarting polling...");
+                    break PollingExit::NeedsReinit;
+                }
                 _ = trigger.notified() => {
                     tick_timeout.as_mut().reset(Instant::now());
                 }
                 _ = tick_timeout.as_mut() => {
-                    let (needs_reinit, status_delay, result) = query_status().await;
-                    tick_timeout.as_mut().reset(Instant::now() + status_delay);
-                    if let Ok(Some(ref status)) = result {
-                        let conn_state = status.into();
-
-                        // Funding level needs the balance poll's data; use the latest cached response.
-                        let cached_balance = app.state::<AppStateCache>().balance.borrow().clone();
-                        let balance = cached_balance
-                            .as_ref()
-                            .and_then(|res| res.as_ref().ok())
-                            .and_then(|opt| opt.as_ref());
-                        let level = icons::funds_level(&status.run_mode, balance);
-
-                        let icon_state = app.state::<Arc<Mutex<icons::IconState>>>();
-                        let new_dock_icon = match icon_state.lock() {
-                            Ok(mut guard) => guard.apply_status(&conn_state, level),
-                            Err(e) => {
-                                eprintln!("Failed to lock icon state: {}", e);
-                                None
-                            }
-                        };
-
-                        // during animation, the heartbeat logic owns app and tray icon changes
-                        if !icons::is_animating_state(&conn_state) {
-                            icons::update_tray_icon(&app, &app.state::<TrayIconState>(), &conn_state, level);
-
-                            if let Some(icon_name) = new_dock_icon {
-                                if let Err(e) = set_app_icon(app.clone(), icon_name).await {
-                                    eprintln!("Failed to update app icon: {}", e);
-                                }
-                            }
-                        }
-
-                        let status_item = app.state::<tray::TrayStatusItem>();
-                        if let Ok(guard) = status_item.0.lock() {
-                            let _ = guard.set_text(conn_state.to_string());
-                        };
-
-                        let quit_label = match conn_state {
-                            ConnectionState::Connected(_) | ConnectionState::Connecting(_) | ConnectionState::Reconnecting(_) => "Disconnect and Quit",
-                            _ => "Quit",
-                        };
-                        let quit_item = app.state::<tray::TrayQuitItem>();
-                        if let Ok(guard) = quit_item.0.lock() {
-                            let _ = guard.set_text(quit_label);
-                        };
-
-                        if !startup_connect_done
-                            && status.destinations.iter().any(|ds| is_positive_route_health(&ds.route_health))
-                        {
-                            startup_connect_done = true;
-                            let settings = app.state::<crate::settings::SettingsStore>().current();
-                            if settings.connect_on_startup {
-                                if let Some(id) = pick_startup_target(&status.destinations, &settings.preferred_location) {
-                                    let trigger_clone = trigger.clone();
-                                    tauri::async_runtime::spawn(async move {
-                                        let p = PathBuf::from(root_socket::DEFAULT_PATH);
-                                        if let Err(e) = root_socket::process_cmd(&p, &command::Command::Connect(id)).await {
-                                            eprintln!("connect on startup failed: {e}");
-                                        }
-                                        trigger_clone.notify_one();
-                                    });
-                                }
-                            }
-                        }
-                    }
-                    app.state::<AppStateCache>().status.send_replace(Some(result.clone()));
-                    let _ = app.emit("status", result);
+                    let (needs_reinit, delay, result) = polling::status_tick(
+                        &*app.state::<DaemonSocket>(),
+                        &mut backoff,
+                        &app.state::<LatencyHistory>(),
+                        &app,
+                    )
+                    .await;
+                    tick_timeout.as_mut().reset(Instant::now() + delay);
+                    apply_status(&app, &mut tracker, &trigger, &result).await;
                     if needs_reinit {
                         break PollingExit::NeedsReinit;
                     }
@@ -441,6 +721,7 @@ async fn spawn_polling_tasks(app_handle: AppHandle) -> Result<(), String> {
 
     let app_bal = app_handle.clone();
     let bal_join_handle = tauri::async_runtime::spawn(async move {
+        let mut backoff = polling::unresponsive_backoff();
         let tick_timeout = time::sleep(Duration::ZERO);
         tokio::pin!(tick_timeout);
         loop {
@@ -449,10 +730,13 @@ async fn spawn_polling_tasks(app_handle: AppHandle) -> Result<(), String> {
                     break;
                 }
                 _ = tick_timeout.as_mut() => {
-                    let (delay, result) = query_balance().await;
+                    let delay = polling::balance_tick(
+                        &*app_bal.state::<DaemonSocket>(),
+                        &mut backoff,
+                        &app_bal,
+                    )
+                    .await;
                     tick_timeout.as_mut().reset(Instant::now() + delay);
-                    app_bal.state::<AppStateCache>().balance.send_replace(Some(result.clone()));
-                    let _ = app_bal.emit("balance", result);
                     // Nudge the status loop so the tray funding level picks up
                     // the fresh balance without waiting for its next tick.
                     bal_trigger.notify_one();
@@ -471,51 +755,28 @@ async fn spawn_polling_tasks(app_handle: AppHandle) -> Result<(), String> {
 
 pub async fn run_initialization_loop(app: AppHandle) {
     const RETRY_DELAY: Duration = Duration::from_secs(5);
+    let mut backoff = polling::unresponsive_backoff();
     loop {
-        let info = match query_info().await {
+        // the socket is read per call, so one switched in settings takes effect here
+        let info = match polling::init_service(&*app.state::<DaemonSocket>()).await {
             Ok(i) => i,
             Err(e) => {
-                let _ = app.emit(
-                    "status",
-                    Err::<Option<StatusResponse>, String>(format!(
-                        "Failed to get service info: {e}"
-                    )),
-                );
-                time::sleep(RETRY_DELAY).await;
+                let delay = if e.is_unresponsive() {
+                    backoff.next_delay().max(RETRY_DELAY)
+                } else {
+                    RETRY_DELAY
+                };
+                let _ = app.emit("status", Err::<Option<StatusResponse>, AppError>(e));
+                time::sleep(delay).await;
                 continue;
             }
         };
-
-        if !is_version_compatible(&info.version) {
-            let supported = COMPATIBLE_VERSIONS.join(", ");
-            let _ = app.emit(
-                "status",
-                Err::<Option<StatusResponse>, String>(format!(
-                    "Incompatible service version: {}. Supported versions: {supported}. \
-                     If you just updated, please restart the app.",
-                    info.version
-                )),
-            );
-            time::sleep(RETRY_DELAY).await;
-            continue;
-        }
-
-        if let Err(e) = start_client_worker(Duration::from_secs(10)).await {
-            let _ = app.emit(
-                "status",
-                Err::<Option<StatusResponse>, String>(format!(
-                    "Failed to start client worker: {e}"
-                )),
-            );
-            time::sleep(RETRY_DELAY).await;
-            continue;
-        }
+        backoff.reset();
 
         if let Err(e) = spawn_polling_tasks(app.clone()).await {
-            let _ = app.emit(
-                "status",
-                Err::<Option<StatusResponse>, String>(format!("Failed to start polling: {e}")),
-            );
+            let error = AppError::new(ErrorCode::PollingStartFailed, "Failed to start polling")
+                .with_details(e);
+            let _ = app.emit("status", Err::<Option<StatusResponse>, AppError>(error));
             time::sleep(RETRY_DELAY).await;
             continue;
         }
@@ -524,6 +785,10 @@ pub async fn run_initialization_loop(app: AppHandle) {
         app.state::<AppStateCache>()
             .service_info
             .send_replace(Some(info));
+        notifications::check_update(
+            &app,
+            &app.state::<crate::settings::SettingsStore>().current(),
+        );
 
         let handle = app
             .state::<Mutex<StatusPollingHandle>>()
@@ -543,79 +808,6 @@ pub async fn run_initialization_loop(app: AppHandle) {
     }
 }
 
-async fn query_status() -> (bool, Duration, Result<Option<StatusResponse>, String>) {
-    let p = PathBuf::from(root_socket::DEFAULT_PATH);
-    let resp = root_socket::process_cmd(&p, &command::Command::Status).await;
-    match resp {
-        Ok(command::Response::Status(status_resp)) => {
-            let resp = StatusResponse {
-                run_mode: status_resp.run_mode.into(),
-                destinations: status_resp.destinations,
-                target_destination: status_resp.target_destination,
-                connected: status_resp.connected,
-                connecting: status_resp.connecting,
-                reconnecting: status_resp.reconnecting,
-                disconnecting: status_resp.disconnecting,
-            };
-
-            if matches!(resp.run_mode, crate::types::RunMode::NotRunning) {
-                return (true, Duration::from_secs(5), Ok(Some(resp)));
-            }
-
-            let is_in_transition = resp.connecting.is_some() || resp.reconnecting.is_some();
-            if is_in_transition {
-                (false, Duration::from_millis(222), Ok(Some(resp)))
-            } else {
-                (false, Duration::from_secs(2), Ok(Some(resp)))
-            }
-        }
-        Ok(command::Response::WorkerOffline) => {
-            // socket-level response: worker process not running
-            (true, Duration::from_secs(5), Ok(None))
-        }
-        // Internal response sent by the root process to itself; never forwarded to the app.
-        Ok(command::Response::ForceReconnectAcknowledged) => {
-            (false, Duration::from_secs(2), Ok(None))
-        }
-        Ok(unexpected) => (
-            false,
-            Duration::from_secs(2),
-            Err(format!("Unexpected response type: {:?}", unexpected).to_string()),
-        ),
-        Err(e) => (false, Duration::from_secs(2), Err(e.to_string())),
-    }
-}
-
-fn is_positive_route_health(rh: &Option<command::RouteHealthView>) -> bool {
-    matches!(
-        rh.as_ref().map(|v| &v.state),
-        Some(RouteHealthState::ReadyToConnect { .. })
-    )
-}
-
-fn startup_latency(rh: &Option<command::RouteHealthView>) -> Option<Duration> {
-    match rh.as_ref().map(|v| &v.state)? {
-        RouteHealthState::ReadyToConnect { exit } => Some(exit.ping_rtt),
-        _ => None,
-    }
-}
-
-fn pick_startup_target(
-    destinations: &[command::DestinationState],
-    preferred: &Option<String>,
-) -> Option<String> {
-    if let Some(id) = preferred {
-        return Some(id.clone());
-    }
-    destinations
-        .iter()
-        .filter_map(|ds| {
-            startup_latency(&ds.route_health).map(|ms| (ms, ds.destination.id.clone()))
-        })
-        .min_by_key(|(ms, _)| *ms)
-        .map(|(_, id)| id)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,455 @@
+//! Headless companion CLI (`gnosis-vpn-app-cli`) for scripting the VPN
+//! without the GUI.
+//!
+//! It talks to the same root daemon socket and reads the same settings file
+//! as the app, and prints the same `StatusResponse`/`BalanceResponse` shapes
+//! the webviews receive (`--json`). Arguments are parsed by hand like
+//! `cli.rs`; unlike the app, unknown arguments are errors here.
+
+use gnosis_vpn_lib::command::{self, ConnectResponse, DisconnectResponse};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use std::ffi::OsString;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use crate::cli::SOCKET_PATH_FLAG;
+use crate::daemon::{self, DaemonClient, DaemonSocket};
+use crate::error::AppError;
+use crate::exits::{health_label, latency_ms, sort_exits};
+use crate::icons;
+use crate::latency::LatencyHistory;
+use crate::polling;
+use crate::score;
+use crate::settings::{self, Settings, SettingsPatch, SettingsStore, SortOrder};
+use crate::types::{BalanceResponse, ConnectionState, StatusResponse, tokens};
+
+/// Tauri's `identifier`, read from tauri.conf.json by the build script; the
+/// app keeps its settings in `app_data_dir/settings.json`, derived from it.
+const APP_IDENTIFIER: &str = env!("GNOSIS_VPN_APP_IDENTIFIER");
+const SETTINGS_PATH_FLAG: &str = "--settings-path";
+
+const USAGE: &str = "\
+Usage: gnosis-vpn-app-cli [--json] [--socket-path <path>] [--settings-path <path>] <command>
+
+Commands:
+  status                          Connection state and exits
+  balance                         Node and safe funds
+  connect <id> | connect --best   Connect to an exit, or pick one like startup does
+  disconnect                      Disconnect
+  exits [--sort latency|alpha|score]
+                                  List exits, by default in the app's sort order
+  settings get [<key>]            Print settings or one value
+  settings set <key> <value>      Set a value (JSON, or a plain string)";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CliCommand {
+    Status,
+    Balance,
+    /// `None` picks the best exit (`--best`).
+    Connect(Option<String>),
+    Disconnect,
+    /// `None` follows the app's `exitNodeSortOrder`.
+    Exits(Option<SortOrder>),
+    SettingsGet(Option<String>),
+    SettingsSet(String, String),
+    Help,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CliArgs {
+    pub command: CliCommand,
+    pub json: bool,
+    pub socket_path: Option<PathBuf>,
+    pub settings_path: Option<PathBuf>,
+}
+
+impl CliArgs {
+    /// Parses arguments without the program name. Options may appear
+    /// anywhere around the command.
+    pub fn parse<I>(args: I) -> Result<Self, String>
+    where
+        I: IntoIterator<Item = OsString>,
+    {
+        let mut json = false;
+        let mut socket_path = None;
+        let mut settings_path = None;
+        let mut words = Vec::new();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let arg = arg
+                .into_string()
+                .map_err(|a| format!("argument is not valid UTF-8: {a:?}"))?;
+            if arg == "--json" {
+                json = true;
+            } else if arg == "-h" || arg == "--help" {
+                words = vec!["help".to_string()];
+                break;
+            } else if arg == SOCKET_PATH_FLAG || arg == SETTINGS_PATH_FLAG {
+                let value = args
+                    .next()
+                    .and_then(|v| v.into_string().ok())
+                    .filter(|v| !v.is_empty())
+                    .ok_or_else(|| format!("{arg} needs a path"))?;
+                if arg == SOCKET_PATH_FLAG {
+                    socket_path = Some(PathBuf::from(value));
+                } else {
+                    settings_path = Some(PathBuf::from(value));
+                }
+            } else {
+                words.push(arg);
+            }
+        }
+
+        let words: Vec<&str> = words.iter().map(String::as_str).collect();
+        let command = match words.as_slice() {
+            ["status"] => CliCommand::Status,
+            ["balance"] => CliCommand::Balance,
+            ["connect", "--best"] => CliCommand::Connect(None),
+            ["connect", id] if !id.starts_with('-') => CliCommand::Connect(Some(id.to_string())),
+            ["disconnect"] => CliCommand::Disconnect,
+            ["exits"] => CliCommand::Exits(None),
+            ["exits", "--sort", order] => CliCommand::Exits(Some(parse_sort_order(order)?)),
+            ["settings", "get"] => CliCommand::SettingsGet(None),
+            ["settings", "get", key] => CliCommand::SettingsGet(Some(key.to_string())),
+            ["settings", "set", key, value] => {
+                CliCommand::SettingsSet(key.to_string(), value.to_string())
+            }
+            ["help"] | [] => CliCommand::Help,
+            other => return Err(format!("unknown command: {}", other.join(" "))),
+        };
+        Ok(Self {
+            command,
+            json,
+            socket_path,
+            settings_path,
+        })
+    }
+}
+
+fn parse_sort_order(order: &str) -> Result<SortOrder, String> {
+    serde_json::from_value(Value::String(order.to_string()))
+        .map_err(|_| format!("unknown sort order: {order} (latency, alpha or score)"))
+}
+
+/// Entry point of the `gnosis-vpn-app-cli` binary.
+pub fn main() -> ExitCode {
+    let args = match CliArgs::parse(std::env::args_os().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{e}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    let runtime = match tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+    {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("cannot start runtime: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    match runtime.block_on(run(args)) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(args: CliArgs) -> Result<(), String> {
+    let settings_path = match args.settings_path {
+        Some(path) => path,
+        None => default_settings_path()?,
+    };
+    // only `settings set` writes; everything else leaves the app's file as is
+    let settings = settings::read(&settings_path);
+    let socket = DaemonSocket::quiet(
+        daemon::launch_override(args.socket_path, std::env::var_os(daemon::SOCKET_PATH_ENV)),
+        settings.daemon_socket_path.as_deref(),
+    );
+
+    match args.command {
+        CliCommand::Help => {
+            println!("{USAGE}");
+            Ok(())
+        }
+        CliCommand::Status => {
+            let status = fetch_status(&socket).await?;
+            if args.json {
+                return print_json(&status);
+            }
+            println!("{}", ConnectionState::from(&status));
+            println!("Exits: {} known", status.destinations.len());
+            Ok(())
+        }
+        CliCommand::Balance => {
+            let (_, result) = polling::query_balance(&socket).await;
+            let balance = result
+                .map_err(|e| e.to_string())?
+                .ok_or("Balance is not available yet: the service is still starting")?;
+            if args.json {
+                return print_json(&balance);
+            }
+            print_balance(&balance);
+            Ok(())
+        }
+        CliCommand::Connect(id) => {
+            let id = match id {
+                Some(id) => id,
+                None => {
+                    let status = fetch_status(&socket).await?;
+                    polling::pick_startup_target(
+                        &status.destinations,
+                        &status.latency,
+                        &settings.preferred_exits,
+                        settings.exit_node_sort_order,
+                    )
+                    .ok_or("No exit is ready to connect")?
+                }
+            };
+            let resp = match socket.process_cmd(&command::Command::Connect(id)).await {
+                Ok(command::Response::Connect(resp)) => resp,
+                Ok(other) => return Err(AppError::unexpected(&other).to_string()),
+                Err(e) => return Err(e.to_string()),
+            };
+            let failed = matches!(
+                resp,
+                ConnectResponse::UnableToConnect(..) | ConnectResponse::DestinationNotFound
+            );
+            if args.json {
+                print_json(&resp)?;
+            } else {
+                println!("{}", connect_message(&resp));
+            }
+            if failed {
+                return Err("Connect failed".to_string());
+            }
+            Ok(())
+        }
+        CliCommand::Disconnect => {
+            let resp = match socket.process_cmd(&command::Command::Disconnect).await {
+                Ok(command::Response::Disconnect(resp)) => resp,
+                Ok(other) => return Err(AppError::unexpected(&other).to_string()),
+                Err(e) => return Err(e.to_string()),
+            };
+            if args.json {
+                return print_json(&resp);
+            }
+            match resp {
+                DisconnectResponse::Disconnecting(dest) => {
+                    println!("Disconnecting from {}", dest.id)
+                }
+                DisconnectResponse::NotConnected => println!("Not connected"),
+            }
+            Ok(())
+        }
+        CliCommand::Exits(order) => {
+            let status = fetch_status(&socket).await?;
+            let order = order.unwrap_or(settings.exit_node_sort_order);
+            let exits = sort_exits(&status.destinations, &status.latency, &status.scores, order);
+            if args.json {
+                return print_json(&exits);
+            }
+            for ds in exits {
+                let id = &ds.destination.id;
+                let location = ds
+                    .destination
+                    .meta
+                    .get("location")
+                    .map_or("-", String::as_str);
+                let latency =
+                    latency_ms(ds, &status.latency).map_or("-".to_string(), |ms| format!("{ms}ms"));
+                let score = status
+                    .scores
+                    .get(id)
+                    .map_or("-".to_string(), |s| format!("{:.2}", s.score));
+                println!("{id}\t{location}\t{}\t{latency}\t{score}", health_label(ds));
+            }
+            Ok(())
+        }
+        CliCommand::SettingsGet(key) => {
+            let value = settings_value(&settings, key.as_deref())?;
+            if args.json {
+                return print_json(&value);
+            }
+            match value {
+                Value::String(s) => println!("{s}"),
+                other => print_json(&other)?,
+            }
+            Ok(())
+        }
+        CliCommand::SettingsSet(key, value) => {
+            let updated = SettingsStore::load(settings_path)
+                .update(settings_patch(&key, &value)?)
+                .map_err(|e| format!("Failed to save settings: {e}"))?;
+            if args.json {
+                return print_json(&updated);
+            }
+            // a running app notices the file change within seconds
+            println!("{key} = {}", settings_value(&updated, Some(&key))?);
+            Ok(())
+        }
+    }
+}
+
+/// One status with the derived fields the app adds (`status_tick`); a
+/// single poll has one latency sample per exit.
+async fn fetch_status(client: &impl DaemonClient) -> Result<StatusResponse, String> {
+    let (_, _, result) = polling::query_status(client).await;
+    let mut status = result
+        .map_err(|e| e.to_string())?
+        .ok_or("The VPN service worker is not running; start the app first")?;
+    status.latency = LatencyHistory::default().observe(&status.destinations);
+    status.scores = score::score_all(&status.destinations, &status.latency);
+    Ok(status)
+}
+
+fn print_json(value: &impl Serialize) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    println!("{json}");
+    Ok(())
+}
+
+fn print_balance(balance: &BalanceResponse) {
+    println!("Node: {}", token_amount(&balance.node, "xDAI"));
+    println!("Safe: {}", token_amount(&balance.safe, "wxHOPR"));
+    println!(
+        "Channels out: {}",
+        token_amount(&balance.channels_out, "wxHOPR")
+    );
+    if let Some(status) = &balance.funding_status {
+        println!("Funds: {}", icons::funding_status_level(status).name());
+    }
+}
+
+/// `3.2000 xDAI`; a value that is not a number is shown as the daemon sent it.
+fn token_amount(wei: &str, unit: &str) -> String {
+    match tokens(wei) {
+        Some(amount) => format!("{amount:.4} {unit}"),
+        None => format!("{wei} wei"),
+    }
+}
+
+fn connect_message(resp: &ConnectResponse) -> String {
+    match resp {
+        ConnectResponse::AlreadyConnected(dest) => format!("Already connected to {}", dest.id),
+        ConnectResponse::Connecting(dest) => format!("Connecting to {}", dest.id),
+        ConnectResponse::WaitingToConnect(dest, _) => {
+            format!("Waiting for {} to become ready", dest.id)
+        }
+        ConnectResponse::UnableToConnect(dest, state) => {
+            format!("Unable to connect to {}: {state:?}", dest.id)
+        }
+        ConnectResponse::DestinationNotFound => "No such exit".to_string(),
+    }
+}
+
+/// The whole settings object, or one camelCase key of it.
+fn settings_value(settings: &Settings, key: Option<&str>) -> Result<Value, String> {
+    let value = serde_json::to_value(settings).map_err(|e| e.to_string())?;
+    match key {
+        None => Ok(value),
+        Some(key) => value
+            .get(key)
+            .cloned()
+            .ok_or_else(|| format!("unknown setting: {key}")),
+    }
+}
+
+/// `settings set connectOnStartup true`: the value is JSON, or a plain
+/// string when it does not parse (`settings set exitNodeSortOrder score`).
+pub fn settings_patch(key: &str, value: &str) -> Result<SettingsPatch, String> {
+    if settings_value(&Settings::default(), Some(key)).is_err() {
+        return Err(format!("unknown setting: {key}"));
+    }
+    let value = serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.to_string()));
+    let mut patch = Map::new();
+    patch.insert(key.to_string(), value);
+    serde_json::from_value(Value::Object(patch))
+        .map_err(|e| format!("invalid value for {key}: {e}"))
+}
+
+/// `app_data_dir` as Tauri resolves it (`dirs::data_dir` plus the
+/// identifier), without an app handle.
+fn default_settings_path() -> Result<PathBuf, String> {
+    dirs::data_dir()
+        .map(|d| d.join(APP_IDENTIFIER).join("settings.json"))
+        .ok_or_else(|| format!("cannot locate the settings file; pass {SETTINGS_PATH_FLAG}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<CliArgs, String> {
+        CliArgs::parse(args.iter().map(OsString::from))
+    }
+
+    #[test]
+    fn parses_commands_with_options_anywhere() {
+        let args = parse(&[
+            "--json",
+            "connect",
+            "exit-1",
+            "--socket-path",
+            "/tmp/a.sock",
+        ])
+        .unwrap();
+        assert_eq!(
+            args.command,
+            CliCommand::Connect(Some("exit-1".to_string()))
+        );
+        assert!(args.json);
+        assert_eq!(args.socket_path, Some(PathBuf::from("/tmp/a.sock")));
+
+        assert_eq!(
+            parse(&["connect", "--best"]).unwrap().command,
+            CliCommand::Connect(None)
+        );
+        assert_eq!(
+            parse(&["exits", "--sort", "alpha"]).unwrap().command,
+            CliCommand::Exits(Some(SortOrder::Alpha))
+        );
+        assert_eq!(
+            parse(&["settings", "set", "alwaysOn", "true"])
+                .unwrap()
+                .command,
+            CliCommand::SettingsSet("alwaysOn".to_string(), "true".to_string())
+        );
+        assert_eq!(parse(&[]).unwrap().command, CliCommand::Help);
+    }
+
+    #[test]
+    fn rejects_unknown_commands_and_values() {
+        assert!(parse(&["frobnicate"]).is_err());
+        assert!(parse(&["connect"]).is_err());
+        assert!(parse(&["connect", "--fastest"]).is_err());
+        assert!(parse(&["exits", "--sort", "random"]).is_err());
+        assert!(parse(&["status", "--socket-path"]).is_err());
+    }
+
+    #[test]
+    fn token_amounts_are_scaled_from_wei() {
+        assert_eq!(token_amount("3200000000000000000", "xDAI"), "3.2000 xDAI");
+        assert_eq!(token_amount("0", "wxHOPR"), "0.0000 wxHOPR");
+        assert_eq!(token_amount("n/a", "xDAI"), "n/a wei");
+    }
+
+    #[test]
+    fn settings_patch_takes_json_or_plain_strings() {
+        let patch = settings_patch("exitNodeSortOrder", "score").unwrap();
+        assert_eq!(patch.exit_node_sort_order, Some(SortOrder::Score));
+        let patch = settings_patch("failoverAfterSecs", "90").unwrap();
+        assert_eq!(patch.failover_after_secs, Some(90));
+        let patch = settings_patch("daemonSocketPath", "null").unwrap();
+        assert_eq!(patch.daemon_socket_path, Some(None));
+
+        assert!(settings_patch("noSuchSetting", "1").is_err());
+        assert!(settings_patch("alwaysOn", "maybe").is_err());
+    }
+}
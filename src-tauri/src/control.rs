@@ -0,0 +1,375 @@
+//! Local control API: a per-user Unix socket speaking newline-delimited JSON.
+//!
+//! Desktop tools (shell prompts, status bars, test scripts) read the app's
+//! already-polled state here instead of querying the root daemon again.
+//! Every request line is answered by one line carrying the same `id`:
+//!
+//! ```text
+//! {"id":1,"method":"get_cached_state"}
+//! {"id":1,"result":{"status":{"Ok":…},"balance":{"Ok":…},"service_info":…}}
+//! {"id":2,"method":"connect","params":{"id":"exit-1"}}
+//! {"id":3,"method":"disconnect"}
+//! {"id":4,"method":"subscribe"}
+//! {"id":5,"method":"reboot"}
+//! {"id":5,"error":{"code":"InvalidRequest","message":"Invalid request","details":"…"}}
+//! ```
+//!
+//! After `subscribe` the connection also receives `{"event":…,"payload":…}`
+//! lines for `status`, `balance`, `service_info` and `settings-changed`,
+//! with the payloads the webviews get. There is no authentication: the
+//! socket is owner-only (0600) inside an owner-only (0700) directory.
+
+use gnosis_vpn_lib::command::InfoResponse;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::{mpsc, watch};
+use tokio::time;
+
+use std::fs::Permissions;
+use std::io;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::AppStateCache;
+use crate::commands;
+use crate::error::{AppError, ErrorCode};
+use crate::settings::{Settings, SettingsStore};
+use crate::types::{BalanceResponse, StatusResponse};
+
+const SOCKET_FILE: &str = "control.sock";
+/// Lines queued per connection before a slow reader holds up its own replies.
+const LINE_BUFFER: usize = 32;
+const ACCEPT_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[serde(tag = "method", content = "params", rename_all = "snake_case")]
+pub enum Call {
+    GetCachedState,
+    Connect { id: String },
+    Disconnect,
+    Subscribe,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Request {
+    /// Echoed back verbatim; any JSON value, `null` when absent.
+    #[serde(default)]
+    pub id: Value,
+    #[serde(flatten)]
+    pub call: Call,
+}
+
+#[derive(Serialize)]
+struct Event<'a, T> {
+    event: &'a str,
+    payload: &'a T,
+}
+
+/// Managed while the socket is up, so it can be removed on exit.
+pub struct ControlSocket {
+    path: PathBuf,
+}
+
+/// On error the request's `id`, if one could be read, for the reply.
+pub fn parse_request(line: &str) -> Result<Request, (Value, AppError)> {
+    let invalid = |e: serde_json::Error| {
+        AppError::new(ErrorCode::InvalidRequest, "Invalid request").with_details(e)
+    };
+    let raw: Value = serde_json::from_str(line).map_err(|e| (Value::Null, invalid(e)))?;
+    let id = raw.get("id").cloned().unwrap_or(Value::Null);
+    serde_json::from_value(raw).map_err(|e| (id, invalid(e)))
+}
+
+pub fn reply_line(id: &Value, result: Result<Value, AppError>) -> String {
+    match result {
+        Ok(result) => serde_json::json!({ "id": id, "result": result }),
+        Err(error) => serde_json::json!({ "id": id, "error": error }),
+    }
+    .to_string()
+}
+
+pub fn event_line(event: &str, payload: &impl Serialize) -> Option<String> {
+    serde_json::to_string(&Event { event, payload })
+        .inspect_err(|e| eprintln!("Control socket: cannot encode {event}: {e}"))
+        .ok()
+}
+
+fn to_result<T: Serialize>(result: Result<T, AppError>) -> Result<Value, AppError> {
+    result.and_then(|value| {
+        serde_json::to_value(value).map_err(|e| AppError::internal(e.to_string()))
+    })
+}
+
+/// The event feed of one subscribed connection.
+pub struct Subscription {
+    status: watch::Receiver<Option<Result<Option<StatusResponse>, AppError>>>,
+    balance: watch::Receiver<Option<Result<Option<BalanceResponse>, AppError>>>,
+    service_info: watch::Receiver<Option<InfoResponse>>,
+    settings: watch::Receiver<Settings>,
+}
+
+impl Subscription {
+    /// Only changes from now on are sent; `get_cached_state` has the rest.
+    pub fn new(cache: &AppStateCache, settings: &SettingsStore) -> Self {
+        Self {
+            status: cache.status.subscribe(),
+            balance: cache.balance.subscribe(),
+            service_info: cache.service_info.subscribe(),
+            settings: settings.subscribe(),
+        }
+    }
+
+    /// Sends event lines to `tx` until the connection or the app goes away.
+    pub async fn forward(mut self, tx: mpsc::Sender<String>) {
+        loop {
+            let line = tokio::select! {
+                Ok(()) = self.status.changed() => {
+                    let status = self.status.borrow_and_update();
+                    status.as_ref().and_then(|r| event_line("status", r))
+                }
+                Ok(()) = self.balance.changed() => {
+                    let balance = self.balance.borrow_and_update();
+                    balance.as_ref().and_then(|r| event_line("balance", r))
+                }
+                Ok(()) = self.service_info.changed() => {
+                    let info = self.service_info.borrow_and_update();
+                    info.as_ref().and_then(|i| event_line("service_info", i))
+                }
+                Ok(()) = self.settings.changed() => {
+                    let settings = self.settings.borrow_and_update();
+                    event_line("settings-changed", &*settings)
+                }
+                else => return,
+            };
+            if let Some(line) = line {
+                if tx.send(line).await.is_err() {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// `$XDG_RUNTIME_DIR/<identifier>/control.sock` where there is a runtime
+/// dir (Linux), `app_data_dir/run/control.sock` otherwise.
+pub fn socket_path(app: &AppHandle) -> tauri::Result<PathBuf> {
+    let dir = match app.path().runtime_dir() {
+        Ok(dir) => dir.join(&app.config().identifier),
+        Err(_) => app.path().app_data_dir()?.join("run"),
+    };
+    Ok(dir.join(SOCKET_FILE))
+}
+
+fn bind(path: &Path) -> io::Result<std::os::unix::net::UnixListener> {
+    let dir = path
+        .parent()
+        .ok_or_else(|| io::Error::other("socket path has no parent directory"))?;
+    std::fs::create_dir_all(dir)?;
+    // before binding: the socket must never be reachable by anyone else
+    std::fs::set_permissions(dir, Permissions::from_mode(0o700))?;
+    // left behind by a crashed run; single-instance rules out a live one
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+        _ => {}
+    }
+    let listener = std::os::unix::net::UnixListener::bind(path)?;
+    std::fs::set_permissions(path, Permissions::from_mode(0o600))?;
+    listener.set_nonblocking(true)?;
+    Ok(listener)
+}
+
+/// Binds the socket and serves it for the app's lifetime. The app works
+/// without it, so failures are only logged.
+pub fn start(app: &AppHandle) {
+    let path = match socket_path(app) {
+        Ok(path) => path,
+        Err(e) => {
+            eprintln!("Control socket disabled: {e}");
+            return;
+        }
+    };
+    let listener = match bind(&path) {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!(
+                "Control socket disabled: cannot bind {}: {e}",
+                path.display()
+            );
+            return;
+        }
+    };
+    println!("Control socket listening on {}", path.display());
+    app.manage(ControlSocket { path });
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let listener = match UnixListener::from_std(listener) {
+            Ok(listener) => listener,
+            Err(e) => {
+                eprintln!("Control socket disabled: {e}");
+                return;
+            }
+        };
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    tauri::async_runtime::spawn(serve(app.clone(), stream));
+                }
+                Err(e) => {
+                    eprintln!("Control socket: accept failed: {e}");
+                    time::sleep(ACCEPT_RETRY_DELAY).await;
+                }
+            }
+        }
+    });
+}
+
+pub fn remove_socket(app: &AppHandle) {
+    if let Some(socket) = app.try_state::<ControlSocket>() {
+        let _ = std::fs::remove_file(&socket.path);
+    }
+}
+
+/// One connection: requests are handled in order; replies and events share
+/// one writer so lines never interleave.
+async fn serve(app: AppHandle, stream: UnixStream) {
+    let (read, mut write) = stream.into_split();
+    let (tx, mut rx) = mpsc::channel::<String>(LINE_BUFFER);
+
+    let writer = async move {
+        while let Some(mut line) = rx.recv().await {
+            line.push('\n');
+            if write.write_all(line.as_bytes()).await.is_err() {
+                break;
+            }
+        }
+    };
+
+    let reader = async move {
+        let mut subscription = None;
+        let mut lines = BufReader::new(read).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let reply = match parse_request(&line) {
+                Err((id, error)) => reply_line(&id, Err(error)),
+                Ok(Request { id, call }) => {
+                    let result = match call {
+                        Call::GetCachedState => {
+                            to_result(Ok(commands::cached_state(&app.state::<AppStateCache>())))
+                        }
+                        Call::Connect { id } => to_result(commands::connect_to(&app, id).await),
+                        Call::Disconnect => to_result(commands::disconnect_from(&app).await),
+                        Call::Subscribe => {
+                            if subscription.is_none() {
+                                let feed = Subscription::new(
+                                    &app.state::<AppStateCache>(),
+                                    &app.state::<SettingsStore>(),
+                                );
+                                subscription =
+                                    Some(tauri::async_runtime::spawn(feed.forward(tx.clone())));
+                            }
+                            Ok(Value::Null)
+                        }
+                    };
+                    reply_line(&id, result)
+                }
+            };
+            if tx.send(reply).await.is_err() {
+                break;
+            }
+        }
+        // the feed holds a sender; the writer drains and ends once both are gone
+        if let Some(subscription) = subscription {
+            subscription.abort();
+        }
+    };
+
+    tokio::join!(writer, reader);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parses_requests_with_and_without_params() {
+        let request =
+            parse_request(r#"{"id":7,"method":"connect","params":{"id":"exit-1"}}"#).unwrap();
+        assert_eq!(request.id, json!(7));
+        assert_eq!(
+            request.call,
+            Call::Connect {
+                id: "exit-1".to_string()
+            }
+        );
+        let request = parse_request(r#"{"method":"subscribe"}"#).unwrap();
+        assert_eq!(request.id, Value::Null);
+        assert_eq!(request.call, Call::Subscribe);
+    }
+
+    #[test]
+    fn invalid_requests_keep_their_id() {
+        let (id, error) = parse_request(r#"{"id":"a","method":"reboot"}"#).unwrap_err();
+        assert_eq!(id, json!("a"));
+        assert_eq!(error.code, ErrorCode::InvalidRequest);
+        let (id, _) = parse_request("not json").unwrap_err();
+        assert_eq!(id, Value::Null);
+        assert!(parse_request(r#"{"id":1,"method":"connect"}"#).is_err());
+    }
+
+    #[test]
+    fn replies_carry_result_or_error() {
+        assert_eq!(
+            reply_line(&json!(1), Ok(Value::Null)),
+            r#"{"id":1,"result":null}"#
+        );
+        let error = AppError::new(ErrorCode::NotAvailable, "not available");
+        let line: Value = serde_json::from_str(&reply_line(&json!(2), Err(error))).unwrap();
+        assert_eq!(line["error"]["code"], "NotAvailable");
+    }
+
+    #[tokio::test]
+    async fn subscription_forwards_changes_as_events() {
+        let (status, _) = watch::channel(Some(Ok(None)));
+        let (balance, _) = watch::channel(None);
+        let (service_info, _) = watch::channel(None);
+        let cache = AppStateCache {
+            status,
+            balance,
+            service_info,
+        };
+        let settings = SettingsStore::load(std::env::temp_dir().join(format!(
+            "gnosis_vpn_control_test_{}/settings.json",
+            std::process::id()
+        )));
+
+        let (tx, mut rx) = mpsc::channel(4);
+        let feed = tokio::spawn(Subscription::new(&cache, &settings).forward(tx));
+
+        cache.balance.send_replace(Some(Err(AppError::new(
+            ErrorCode::DaemonUnreachable,
+            "unreachable",
+        ))));
+        let line: Value = serde_json::from_str(&rx.recv().await.unwrap()).unwrap();
+        assert_eq!(line["event"], "balance");
+        assert_eq!(line["payload"]["Err"]["code"], "DaemonUnreachable");
+
+        cache.status.send_replace(Some(Ok(None)));
+        assert_eq!(
+            rx.recv().await.unwrap(),
+            r#"{"event":"status","payload":{"Ok":null}}"#
+        );
+
+        drop(rx);
+        cache.status.send_replace(None);
+        cache.status.send_replace(Some(Ok(None)));
+        feed.await.unwrap();
+    }
+}
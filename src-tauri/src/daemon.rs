@@ -0,0 +1,267 @@
+//! Which root daemon socket the app talks to.
+//!
+//! Resolved once at startup with the precedence `--socket-path` flag,
+//! `GNOSISVPN_SOCKET_PATH`, the `daemonSocketPath` setting, then the library
+//! default. Flag and env var pin the socket for the process lifetime; without
+//! them a settings change moves it at runtime and the status loop restarts
+//! against the new path.
+//!
+//! All daemon round trips go through [`DaemonClient`] so the polling logic
+//! can be driven by a scripted daemon in tests. The socket client bounds
+//! calls by their command's [`CallPolicy`], so a hung daemon surfaces as
+//! `DaemonTimeout` instead of stalling the caller. `Connect` and `Disconnect`
+//! are left unbounded: the daemon may still carry one out after a timeout,
+//! and the app would report a failure that did not happen.
+
+use gnosis_vpn_lib::command::{Command, Response};
+use gnosis_vpn_lib::socket::root as root_socket;
+
+use tokio::sync::watch;
+use tokio::time;
+
+use std::future::Future;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::error::AppError;
+
+pub const SOCKET_PATH_ENV: &str = "GNOSISVPN_SOCKET_PATH";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SocketSource {
+    Flag,
+    Env,
+    Settings,
+    Default,
+}
+
+/// One request/response exchange with the root daemon. Errors are
+/// transport failures (`DaemonUnreachable`); daemon-level errors arrive as
+/// `Response` variants.
+pub trait DaemonClient: Send + Sync {
+    fn process_cmd(&self, cmd: &Command)
+    -> impl Future<Output = Result<Response, AppError>> + Send;
+}
+
+/// Timeout and retry budget for one kind of daemon command.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CallPolicy {
+    /// `None` for commands that are unsafe to repeat.
+    pub timeout: Option<Duration>,
+    /// Extra attempts after a failed one. Only commands that are safe to
+    /// repeat get any: a `Connect` that timed out may still be in progress.
+    pub retries: u32,
+}
+
+impl CallPolicy {
+    const RETRY_DELAY: Duration = Duration::from_millis(250);
+
+    pub fn for_command(cmd: &Command) -> Self {
+        let (timeout_ms, retries) = match cmd {
+            // polled every tick; a slow answer is as good as none
+            Command::Status => (3_000, 1),
+            Command::Info => (3_000, 1),
+            // the daemon queries chain state for balances
+            Command::Balance => (10_000, 1),
+            Command::Connect(_) | Command::Disconnect => {
+                return Self {
+                    timeout: None,
+                    retries: 0,
+                };
+            }
+            Command::StartClient(_) | Command::StopClient => (5_000, 0),
+            _ => (10_000, 0),
+        };
+        Self {
+            timeout: Some(Duration::from_millis(timeout_ms)),
+            retries,
+        }
+    }
+}
+
+/// Launch-time pin from the flag or env var, if any.
+pub fn launch_override(
+    flag: Option<PathBuf>,
+    env: Option<std::ffi::OsString>,
+) -> Option<(PathBuf, SocketSource)> {
+    if let Some(path) = flag {
+        return Some((path, SocketSource::Flag));
+    }
+    env.filter(|v| !v.is_empty())
+        .map(|v| (PathBuf::from(v), SocketSource::Env))
+}
+
+pub fn resolve(
+    pinned: Option<&(PathBuf, SocketSource)>,
+    setting: Option<&str>,
+) -> (PathBuf, SocketSource) {
+    if let Some((path, source)) = pinned {
+        return (path.clone(), *source);
+    }
+    match setting.map(str::trim).filter(|s| !s.is_empty()) {
+        Some(path) => (PathBuf::from(path), SocketSource::Settings),
+        None => (
+            PathBuf::from(root_socket::DEFAULT_PATH),
+            SocketSource::Default,
+        ),
+    }
+}
+
+/// Managed state holding the active socket. Every daemon call reads the
+/// current path, and the status loop subscribes to restart on a change.
+pub struct DaemonSocket {
+    pinned: Option<(PathBuf, SocketSource)>,
+    current: watch::Sender<PathBuf>,
+}
+
+impl DaemonSocket {
+    pub fn new(pinned: Option<(PathBuf, SocketSource)>, setting: Option<&str>) -> Self {
+        let (path, source) = resolve(pinned.as_ref(), setting);
+        println!("Using daemon socket {} ({source:?})", path.display());
+        Self::resolved(pinned, path)
+    }
+
+    /// `new` without the log line, for the companion CLI whose stdout is
+    /// its output.
+    pub fn quiet(pinned: Option<(PathBuf, SocketSource)>, setting: Option<&str>) -> Self {
+        let (path, _) = resolve(pinned.as_ref(), setting);
+        Self::resolved(pinned, path)
+    }
+
+    fn resolved(pinned: Option<(PathBuf, SocketSource)>, path: PathBuf) -> Self {
+        Self {
+            pinned,
+            current: watch::Sender::new(path),
+        }
+    }
+
+    pub fn path(&self) -> PathBuf {
+        self.current.borrow().clone()
+    }
+
+    pub fn subscribe(&self) -> watch::Receiver<PathBuf> {
+        self.current.subscribe()
+    }
+
+    /// Re-resolves after a settings change. Returns true if the socket moved;
+    /// a pinned socket never does.
+    pub fn apply_setting(&self, setting: Option<&str>) -> bool {
+        let (next, _) = resolve(self.pinned.as_ref(), setting);
+        self.current.send_if_modified(|current| {
+            if *current == next {
+                return false;
+            }
+            println!(
+                "Daemon socket changed: {} -> {}",
+                current.display(),
+                next.display()
+            );
+            *current = next;
+            true
+        })
+    }
+}
+
+impl DaemonClient for DaemonSocket {
+    async fn process_cmd(&self, cmd: &Command) -> Result<Response, AppError> {
+        let policy = CallPolicy::for_command(cmd);
+        let mut attempt = 0;
+        loop {
+            let path = self.path();
+            let call = root_socket::process_cmd(&path, cmd);
+            let result = match policy.timeout {
+                Some(limit) => match time::timeout(limit, call).await {
+                    Ok(res) => res.map_err(AppError::daemon),
+                    Err(_) => Err(AppError::timeout(cmd, limit)),
+                },
+                None => call.await.map_err(AppError::daemon),
+            };
+            match result {
+                Err(e) if attempt < policy.retries => {
+                    attempt += 1;
+                    eprintln!(
+                        "{cmd:?} failed, retrying ({attempt}/{}): {e}",
+                        policy.retries
+                    );
+                    time::sleep(CallPolicy::RETRY_DELAY).await;
+                }
+                other => return other,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn state_changing_commands_are_never_retried() {
+        for cmd in [
+            Command::Connect("exit".to_string()),
+            Command::Disconnect,
+            Command::StartClient(Duration::from_secs(10)),
+            Command::StopClient,
+        ] {
+            assert_eq!(CallPolicy::for_command(&cmd).retries, 0, "{cmd:?}");
+        }
+        assert!(CallPolicy::for_command(&Command::Status).retries > 0);
+    }
+
+    #[test]
+    fn connect_and_disconnect_are_not_timed_out() {
+        assert!(CallPolicy::for_command(&Command::Status).timeout.is_some());
+        for cmd in [Command::Connect("exit".to_string()), Command::Disconnect] {
+            assert_eq!(CallPolicy::for_command(&cmd).timeout, None, "{cmd:?}");
+        }
+    }
+
+    #[test]
+    fn flag_beats_env() {
+        let pinned = launch_override(Some(PathBuf::from("/flag.sock")), Some("/env.sock".into()));
+        assert_eq!(
+            pinned,
+            Some((PathBuf::from("/flag.sock"), SocketSource::Flag))
+        );
+        assert_eq!(
+            launch_override(None, Some("/env.sock".into())),
+            Some((PathBuf::from("/env.sock"), SocketSource::Env))
+        );
+        assert_eq!(launch_override(None, Some("".into())), None);
+    }
+
+    #[test]
+    fn setting_applies_only_without_pin() {
+        let (path, source) = resolve(None, Some("/staging.sock"));
+        assert_eq!(path, PathBuf::from("/staging.sock"));
+        assert_eq!(source, SocketSource::Settings);
+
+        let pinned = (PathBuf::from("/env.sock"), SocketSource::Env);
+        let (path, source) = resolve(Some(&pinned), Some("/staging.sock"));
+        assert_eq!(path, PathBuf::from("/env.sock"));
+        assert_eq!(source, SocketSource::Env);
+
+        let (path, source) = resolve(None, Some("  "));
+        assert_eq!(path, PathBuf::from(root_socket::DEFAULT_PATH));
+        assert_eq!(source, SocketSource::Default);
+    }
+
+    #[test]
+    fn apply_setting_reports_moves_and_respects_pin() {
+        let socket = DaemonSocket::new(None, None);
+        let mut rx = socket.subscribe();
+        assert!(socket.apply_setting(Some("/staging.sock")));
+        assert!(rx.has_changed().unwrap());
+        assert_eq!(*rx.borrow_and_update(), PathBuf::from("/staging.sock"));
+        assert!(!socket.apply_setting(Some("/staging.sock")));
+        assert!(socket.apply_setting(None));
+        assert_eq!(socket.path(), PathBuf::from(root_socket::DEFAULT_PATH));
+
+        let pinned = DaemonSocket::new(
+            Some((PathBuf::from("/flag.sock"), SocketSource::Flag)),
+            None,
+        );
+        assert!(!pinned.apply_setting(Some("/staging.sock")));
+        assert_eq!(pinned.path(), PathBuf::from("/flag.sock"));
+    }
+}
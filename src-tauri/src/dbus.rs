@@ -0,0 +1,284 @@
+//! Session-bus service `org.gnosis.VPN` at `/org/gnosis/VPN`, for Linux
+//! desktop integrations (GNOME extensions, waybar, KDE widgets).
+//!
+//! Properties (all strings, empty until the first status):
+//! `ConnectionState`, `Destination`, `FundsLevel`, `RunMode`. Methods:
+//! `Connect(s id)`, `Disconnect()`, `ShowWindow()`. Property changes are
+//! signalled with the standard `PropertiesChanged`.
+//!
+//! The bus side only knows two channels — properties in, calls out — so
+//! it can be served on a private bus in tests without a running app.
+
+use tauri::{AppHandle, Manager};
+use tokio::sync::{mpsc, oneshot, watch};
+use zbus::{Connection, fdo};
+
+use crate::icons::FundsLevel;
+use crate::types::{ConnectionState, RunMode};
+
+pub const BUS_NAME: &str = "org.gnosis.VPN";
+pub const OBJECT_PATH: &str = "/org/gnosis/VPN";
+
+const CALL_BUFFER: usize = 8;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct VpnProperties {
+    /// `Connected`, `Connecting`, `Reconnecting`, `Disconnecting` or `Disconnected`.
+    pub connection_state: String,
+    /// The exit being connected to, connected or reconnected; empty otherwise.
+    pub destination: String,
+    /// `Sufficient`, `Low` or `Empty`.
+    pub funds_level: String,
+    /// The `RunMode` variant, e.g. `Running` or `PreparingSafe`.
+    pub run_mode: String,
+}
+
+impl VpnProperties {
+    pub fn new(conn_state: &ConnectionState, funds_level: FundsLevel, run_mode: &RunMode) -> Self {
+        let (connection_state, destination) = match conn_state {
+            ConnectionState::Connected(id) => ("Connected", id.as_str()),
+            ConnectionState::Connecting(id) => ("Connecting", id.as_str()),
+            ConnectionState::Reconnecting(id) => ("Reconnecting", id.as_str()),
+            ConnectionState::Disconnecting => ("Disconnecting", ""),
+            ConnectionState::Disconnected => ("Disconnected", ""),
+        };
+        let funds_level = match funds_level {
+            FundsLevel::Sufficient => "Sufficient",
+            FundsLevel::Low => "Low",
+            FundsLevel::Empty => "Empty",
+        };
+        let run_mode = match run_mode {
+            RunMode::PreparingSafe { .. } => "PreparingSafe",
+            RunMode::DeployingSafe { .. } => "DeployingSafe",
+            RunMode::Warmup { .. } => "Warmup",
+            RunMode::Running { .. } => "Running",
+            RunMode::Shutdown => "Shutdown",
+            RunMode::Restarting => "Restarting",
+            RunMode::NotRunning => "NotRunning",
+        };
+        Self {
+            connection_state: connection_state.to_string(),
+            destination: destination.to_string(),
+            funds_level: funds_level.to_string(),
+            run_mode: run_mode.to_string(),
+        }
+    }
+}
+
+/// A method call waiting for the app. Errors are returned to the caller
+/// as `org.freedesktop.DBus.Error.Failed`.
+#[derive(Debug)]
+pub enum DbusCall {
+    Connect(String, oneshot::Sender<Result<(), String>>),
+    Disconnect(oneshot::Sender<Result<(), String>>),
+    ShowWindow,
+}
+
+struct VpnInterface {
+    props: watch::Receiver<VpnProperties>,
+    calls: mpsc::Sender<DbusCall>,
+}
+
+#[zbus::interface(name = "org.gnosis.VPN")]
+impl VpnInterface {
+    async fn connect(&self, id: String) -> fdo::Result<()> {
+        self.call(|reply| DbusCall::Connect(id, reply)).await
+    }
+
+    async fn disconnect(&self) -> fdo::Result<()> {
+        self.call(DbusCall::Disconnect).await
+    }
+
+    async fn show_window(&self) -> fdo::Result<()> {
+        self.calls
+            .send(DbusCall::ShowWindow)
+            .await
+            .map_err(|_| shutting_down())
+    }
+
+    #[zbus(property)]
+    fn connection_state(&self) -> String {
+        self.props.borrow().connection_state.clone()
+    }
+
+    #[zbus(property)]
+    fn destination(&self) -> String {
+        self.props.borrow().destination.clone()
+    }
+
+    #[zbus(property)]
+    fn funds_level(&self) -> String {
+        self.props.borrow().funds_level.clone()
+    }
+
+    #[zbus(property)]
+    fn run_mode(&self) -> String {
+        self.props.borrow().run_mode.clone()
+    }
+}
+
+impl VpnInterface {
+    async fn call(
+        &self,
+        make: impl FnOnce(oneshot::Sender<Result<(), String>>) -> DbusCall,
+    ) -> fdo::Result<()> {
+        let (reply, result) = oneshot::channel();
+        self.calls
+            .send(make(reply))
+            .await
+            .map_err(|_| shutting_down())?;
+        result
+            .await
+            .map_err(|_| shutting_down())?
+            .map_err(fdo::Error::Failed)
+    }
+}
+
+fn shutting_down() -> fdo::Error {
+    fdo::Error::Failed("The app is shutting down".to_string())
+}
+
+/// Exports the object and claims the bus name.
+pub async fn register(
+    conn: &Connection,
+    props: watch::Receiver<VpnProperties>,
+    calls: mpsc::Sender<DbusCall>,
+) -> zbus::Result<()> {
+    conn.object_server()
+        .at(OBJECT_PATH, VpnInterface { props, calls })
+        .await?;
+    conn.request_name(BUS_NAME).await?;
+    Ok(())
+}
+
+/// Emits `PropertiesChanged` for every property that differs from the
+/// previous snapshot, until the sender is gone.
+pub async fn publish_changes(
+    conn: &Connection,
+    mut props: watch::Receiver<VpnProperties>,
+) -> zbus::Result<()> {
+    let iface_ref = conn
+        .object_server()
+        .interface::<_, VpnInterface>(OBJECT_PATH)
+        .await?;
+    let mut last = props.borrow_and_update().clone();
+    while props.changed().await.is_ok() {
+        let next = props.borrow_and_update().clone();
+        let iface = iface_ref.get().await;
+        let emitter = iface_ref.signal_emitter();
+        if next.connection_state != last.connection_state {
+            iface.connection_state_changed(emitter).await?;
+        }
+        if next.destination != last.destination {
+            iface.destination_changed(emitter).await?;
+        }
+        if next.funds_level != last.funds_level {
+            iface.funds_level_changed(emitter).await?;
+        }
+        if next.run_mode != last.run_mode {
+            iface.run_mode_changed(emitter).await?;
+        }
+        last = next;
+    }
+    Ok(())
+}
+
+/// Managed state: the status loop writes the properties here, whether or
+/// not the bus is up yet.
+pub struct DbusProperties(watch::Sender<VpnProperties>);
+
+pub fn publish(app: &AppHandle, next: VpnProperties) {
+    if let Some(props) = app.try_state::<DbusProperties>() {
+        props.0.send_if_modified(|current| {
+            let changed = *current != next;
+            *current = next;
+            changed
+        });
+    }
+}
+
+/// Registers the service on the session bus. Desktops without a session
+/// bus simply go without; the app does not depend on it.
+pub fn start(app: &AppHandle) {
+    let (props_tx, props_rx) = watch::channel(VpnProperties::default());
+    app.manage(DbusProperties(props_tx));
+    let (calls_tx, mut calls_rx) = mpsc::channel(CALL_BUFFER);
+
+    tauri::async_runtime::spawn(async move {
+        let conn = match Connection::session().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                eprintln!("D-Bus service disabled: no session bus: {e}");
+                return;
+            }
+        };
+        if let Err(e) = register(&conn, props_rx.clone(), calls_tx).await {
+            eprintln!("D-Bus service disabled: cannot register {BUS_NAME}: {e}");
+            return;
+        }
+        println!("D-Bus service registered as {BUS_NAME}");
+        if let Err(e) = publish_changes(&conn, props_rx).await {
+            eprintln!("D-Bus property updates stopped: {e}");
+        }
+    });
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        while let Some(call) = calls_rx.recv().await {
+            let app = app.clone();
+            // connects can take seconds; do not hold up a ShowWindow behind one
+            tauri::async_runtime::spawn(async move { handle_call(&app, call).await });
+        }
+    });
+}
+
+async fn handle_call(app: &AppHandle, call: DbusCall) {
+    use gnosis_vpn_lib::command::ConnectResponse;
+
+    match call {
+        DbusCall::Connect(id, reply) => {
+            let result = match crate::commands::connect_to(app, id).await {
+                Ok(ConnectResponse::DestinationNotFound) => Err("No such exit".to_string()),
+                Ok(ConnectResponse::UnableToConnect(dest, state)) => {
+                    Err(format!("Unable to connect to {}: {state:?}", dest.id))
+                }
+                Ok(_) => Ok(()),
+                Err(e) => Err(e.to_string()),
+            };
+            let _ = reply.send(result);
+        }
+        DbusCall::Disconnect(reply) => {
+            let result = crate::commands::disconnect_from(app)
+                .await
+                .map(|_| ())
+                .map_err(|e| e.to_string());
+            let _ = reply.send(result);
+        }
+        DbusCall::ShowWindow => crate::tray::show_main_window(app),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn properties_follow_connection_state() {
+        let props = VpnProperties::new(
+            &ConnectionState::Reconnecting("exit-1".to_string()),
+            FundsLevel::Low,
+            &RunMode::Restarting,
+        );
+        assert_eq!(props.connection_state, "Reconnecting");
+        assert_eq!(props.destination, "exit-1");
+        assert_eq!(props.funds_level, "Low");
+        assert_eq!(props.run_mode, "Restarting");
+
+        let props = VpnProperties::new(
+            &ConnectionState::Disconnecting,
+            FundsLevel::Sufficient,
+            &RunMode::NotRunning,
+        );
+        assert_eq!(props.destination, "");
+    }
+}
@@ -0,0 +1,105 @@
+//! `gnosisvpn://` links, so docs and chat messages can drive the app:
+//!
+//! - `gnosisvpn://connect/<id>` connects to a known exit
+//! - `gnosisvpn://disconnect`
+//! - `gnosisvpn://show` and `gnosisvpn://settings[/<logs|usage|updates>]`
+//!   open a window or settings page
+//!
+//! On Linux the `.desktop` entry registers the scheme
+//! (`x-scheme-handler/gnosisvpn`) and the desktop launches the app with the
+//! link as an argument, so links arrive with the other launch arguments
+//! (see `cli`) in the first instance and the single-instance callback.
+//! Anything else in the link is rejected.
+
+use percent_encoding::percent_decode_str;
+use tauri::Url;
+
+use crate::cli::LaunchAction;
+use crate::tray::WindowTarget;
+
+pub const SCHEME: &str = "gnosisvpn";
+
+/// The exit id is percent-decoded here and checked against the known exits
+/// when the action runs.
+pub fn parse(link: &str) -> Result<LaunchAction, String> {
+    let url = Url::parse(link).map_err(|e| format!("not a URL: {e}"))?;
+    if url.scheme() != SCHEME {
+        return Err(format!("not a {SCHEME}:// link"));
+    }
+    if url.query().is_some() || url.fragment().is_some() {
+        return Err("unexpected query or fragment".to_string());
+    }
+    let path: Vec<&str> = url
+        .path_segments()
+        .map(|segments| segments.filter(|s| !s.is_empty()).collect())
+        .unwrap_or_default();
+    match (url.host_str().unwrap_or_default(), path.as_slice()) {
+        ("connect", [id]) => match percent_decode_str(id).decode_utf8() {
+            Ok(id) => Ok(LaunchAction::Connect(id.into_owned())),
+            Err(_) => Err("exit id is not valid UTF-8".to_string()),
+        },
+        ("disconnect", []) => Ok(LaunchAction::Disconnect),
+        ("show", []) => Ok(LaunchAction::Show(WindowTarget::Main)),
+        ("settings", []) => Ok(LaunchAction::Show(WindowTarget::Settings)),
+        ("settings", [page]) => match WindowTarget::parse(page) {
+            Some(target) if target != WindowTarget::Main => Ok(LaunchAction::Show(target)),
+            _ => Err(format!("unknown settings page '{page}'")),
+        },
+        _ => Err("unknown link".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_known_links() {
+        assert_eq!(
+            parse("gnosisvpn://connect/exit-1"),
+            Ok(LaunchAction::Connect("exit-1".to_string()))
+        );
+        assert_eq!(
+            parse("gnosisvpn://connect/exit%20de%2F1"),
+            Ok(LaunchAction::Connect("exit de/1".to_string()))
+        );
+        assert_eq!(
+            parse("gnosisvpn://disconnect"),
+            Ok(LaunchAction::Disconnect)
+        );
+        assert_eq!(
+            parse("gnosisvpn://disconnect/"),
+            Ok(LaunchAction::Disconnect)
+        );
+        assert_eq!(
+            parse("gnosisvpn://show"),
+            Ok(LaunchAction::Show(WindowTarget::Main))
+        );
+        assert_eq!(
+            parse("gnosisvpn://settings"),
+            Ok(LaunchAction::Show(WindowTarget::Settings))
+        );
+        assert_eq!(
+            parse("gnosisvpn://settings/logs"),
+            Ok(LaunchAction::Show(WindowTarget::Logs))
+        );
+    }
+
+    #[test]
+    fn rejects_everything_else() {
+        for link in [
+            "https://connect/exit-1",
+            "gnosisvpn://connect",
+            "gnosisvpn://connect/exit-1/extra",
+            "gnosisvpn://disconnect/now",
+            "gnosisvpn://settings/main",
+            "gnosisvpn://settings/secrets",
+            "gnosisvpn://connect/exit-1?force=1",
+            "gnosisvpn://connect/%FF",
+            "gnosisvpn://uninstall",
+            "gnosisvpn:",
+        ] {
+            assert!(parse(link).is_err(), "{link}");
+        }
+    }
+}
@@ -0,0 +1,155 @@
+//! Error payload shared by every Tauri command and the `status`/`balance`
+//! error events, e.g.
+//! `{"code":"VpnNotConnected","message":"…","details":null}`.
+//!
+//! `code` is the stable part the frontend branches on; `message` is for
+//! humans and may change freely. The TS mirror is `src/services/appError.ts`.
+
+use serde::Serialize;
+
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum ErrorCode {
+    /// The root daemon socket could not be reached or the exchange failed.
+    DaemonUnreachable,
+    /// The daemon accepted the request but did not answer in time.
+    DaemonTimeout,
+    /// The daemon answered with a response the app did not ask for.
+    UnexpectedResponse,
+    IncompatibleService,
+    WorkerStartFailed,
+    PollingStartFailed,
+    /// Nothing has been received from the daemon yet.
+    NotAvailable,
+    VpnNotConnected,
+    UpdateIntegrity,
+    UpdateCheckFailed,
+    InstallInProgress,
+    InvalidChannel,
+    UnsupportedPlatform,
+    SpawnFailed,
+    InvalidPath,
+    Io,
+    SettingsPersistFailed,
+    /// An imported settings file is not a JSON object.
+    InvalidSettingsFile,
+    InvalidIcon,
+    /// A control-socket request that is not valid JSON or names no known method.
+    InvalidRequest,
+    /// Lock poisoning, panicked blocking tasks and other "should not happen" cases.
+    Internal,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct AppError {
+    pub code: ErrorCode,
+    pub message: String,
+    pub details: Option<String>,
+}
+
+impl AppError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            details: None,
+        }
+    }
+
+    pub fn with_details(mut self, details: impl ToString) -> Self {
+        self.details = Some(details.to_string());
+        self
+    }
+
+    pub fn daemon(details: impl ToString) -> Self {
+        Self::new(ErrorCode::DaemonUnreachable, "Cannot reach the VPN service")
+            .with_details(details)
+    }
+
+    pub fn timeout(cmd: &impl fmt::Debug, after: std::time::Duration) -> Self {
+        Self::new(
+            ErrorCode::DaemonTimeout,
+            "The VPN service is not responding",
+        )
+        .with_details(format!("{cmd:?} timed out after {after:?}"))
+    }
+
+    /// Transport-level failure: the daemon is down, hung or restarting.
+    pub fn is_unresponsive(&self) -> bool {
+        matches!(
+            self.code,
+            ErrorCode::DaemonUnreachable | ErrorCode::DaemonTimeout
+        )
+    }
+
+    pub fn unexpected(response: &impl fmt::Debug) -> Self {
+        Self::new(ErrorCode::UnexpectedResponse, "Unexpected response type")
+            .with_details(format!("{response:?}"))
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Internal, message)
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.details {
+            Some(details) => write!(f, "{}: {details}", self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl From<gnosis_vpn_lib::check_update::Error> for AppError {
+    fn from(e: gnosis_vpn_lib::check_update::Error) -> Self {
+        use gnosis_vpn_lib::check_update::Error;
+        match e {
+            Error::VpnNotConnected => AppError::new(
+                ErrorCode::VpnNotConnected,
+                "Connect to the VPN to check for updates",
+            ),
+            Error::Integrity(msg) => AppError::new(
+                ErrorCode::UpdateIntegrity,
+                "Update manifest failed the integrity check",
+            )
+            .with_details(msg),
+            Error::Other(msg) => {
+                AppError::new(ErrorCode::UpdateCheckFailed, "Update check failed").with_details(msg)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serializes_code_message_and_details() {
+        let v = serde_json::to_value(AppError::daemon("connection refused")).unwrap();
+        assert_eq!(
+            v,
+            serde_json::json!({
+                "code": "DaemonUnreachable",
+                "message": "Cannot reach the VPN service",
+                "details": "connection refused",
+            })
+        );
+        let v = serde_json::to_value(AppError::new(ErrorCode::InstallInProgress, "busy")).unwrap();
+        assert_eq!(v["code"], "InstallInProgress");
+        assert!(v["details"].is_null());
+    }
+
+    #[test]
+    fn display_appends_details() {
+        assert_eq!(AppError::internal("boom").to_string(), "boom");
+        assert_eq!(
+            AppError::daemon("refused").to_string(),
+            "Cannot reach the VPN service: refused"
+        );
+    }
+}
@@ -0,0 +1,65 @@
+//! Exit list formatting shared by the tray menu and the companion CLI.
+
+use gnosis_vpn_lib::command::DestinationState;
+use gnosis_vpn_lib::route_health::RouteHealthState;
+
+use std::collections::BTreeMap;
+
+use crate::latency::LatencyStats;
+use crate::score::ExitScore;
+use crate::settings::SortOrder;
+
+pub fn health_label(ds: &DestinationState) -> &'static str {
+    match ds.route_health.as_ref().map(|v| &v.state) {
+        Some(RouteHealthState::ReadyToConnect { .. }) => "ready",
+        Some(RouteHealthState::Connecting { .. }) => "connecting",
+        Some(RouteHealthState::Routable) => "routable",
+        Some(RouteHealthState::NeedsChannel) => "needs channel",
+        Some(RouteHealthState::NeedsPeering { .. }) => "needs peering",
+        Some(RouteHealthState::Unrecoverable { .. }) => "unrecoverable",
+        None => "unknown",
+    }
+}
+
+/// Smoothed latency once sampled, else the last ping; `None` for exits
+/// without a health check.
+pub fn latency_ms(ds: &DestinationState, latency: &BTreeMap<String, LatencyStats>) -> Option<u64> {
+    match ds.route_health.as_ref().map(|v| &v.state)? {
+        RouteHealthState::ReadyToConnect { exit } | RouteHealthState::Connecting { exit, .. } => {
+            Some(
+                latency
+                    .get(&ds.destination.id)
+                    .map_or(exit.ping_rtt.as_millis() as u64, |l| l.smoothed_ms),
+            )
+        }
+        _ => None,
+    }
+}
+
+/// Same tiers as the exit list: exits with a health check first, ranked by
+/// `order`, then everything else by id.
+pub fn sort_exits<'a>(
+    destinations: &'a [DestinationState],
+    latency: &BTreeMap<String, LatencyStats>,
+    scores: &BTreeMap<String, ExitScore>,
+    order: SortOrder,
+) -> Vec<&'a DestinationState> {
+    let mut exits: Vec<&DestinationState> = destinations.iter().collect();
+    exits.sort_by(|a, b| {
+        let (la, lb) = (latency_ms(a, latency), latency_ms(b, latency));
+        let tier = lb.is_some().cmp(&la.is_some());
+        let by_order = match order {
+            SortOrder::Latency => la.cmp(&lb),
+            SortOrder::Alpha => std::cmp::Ordering::Equal,
+            SortOrder::Score => {
+                match (scores.get(&a.destination.id), scores.get(&b.destination.id)) {
+                    (Some(sa), Some(sb)) => ExitScore::rank(sa, sb),
+                    (sa, sb) => sb.is_some().cmp(&sa.is_some()),
+                }
+            }
+        };
+        tier.then(by_order)
+            .then_with(|| a.destination.id.cmp(&b.destination.id))
+    });
+    exits
+}
@@ -0,0 +1,265 @@
+//! Opt-in exit failover: when the connected exit stays unhealthy for
+//! `failoverAfterSecs`, switch to the best other `ReadyToConnect` exit.
+//!
+//! "Unhealthy" is read from the connected destination's route health: an
+//! `Unrecoverable` state or failing health checks (`consecutive_failures`).
+//! A pinned exit (see `settings::pinned_exit`) is never left; the switch
+//! target follows the preference list like startup selection does.
+
+use gnosis_vpn_lib::command::{DestinationState, RouteHealthView};
+use gnosis_vpn_lib::route_health::RouteHealthState;
+use serde::Serialize;
+
+use std::time::Duration;
+use tokio::time::Instant;
+
+use crate::polling::pick_startup_target;
+use crate::settings::{Settings, pinned_exit};
+use crate::types::{ConnectionState, StatusResponse};
+
+pub const FAILOVER_EVENT: &str = "failover";
+
+/// A switch the status loop should carry out; broadcast as `failover`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Failover {
+    pub from: String,
+    pub to: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Default)]
+pub struct FailoverMonitor {
+    /// Connected exit and when it was first seen unhealthy.
+    unhealthy_since: Option<(String, Instant)>,
+}
+
+impl FailoverMonitor {
+    pub fn on_status(
+        &mut self,
+        status: &StatusResponse,
+        settings: &Settings,
+        now: Instant,
+    ) -> Option<Failover> {
+        let ConnectionState::Connected(id) = ConnectionState::from(status) else {
+            self.unhealthy_since = None;
+            return None;
+        };
+        let pinned = pinned_exit(&settings.preferred_exits) == Some(id.as_str());
+        if !settings.exit_failover || pinned {
+            self.unhealthy_since = None;
+            return None;
+        }
+        let problem = status
+            .destinations
+            .iter()
+            .find(|ds| ds.destination.id == id)
+            .and_then(|ds| ds.route_health.as_ref())
+            .and_then(health_problem);
+        let Some(problem) = problem else {
+            self.unhealthy_since = None;
+            return None;
+        };
+
+        let since = match &self.unhealthy_since {
+            Some((unhealthy_id, since)) if *unhealthy_id == id => *since,
+            _ => {
+                self.unhealthy_since = Some((id.clone(), now));
+                now
+            }
+        };
+        let unhealthy_for = now.duration_since(since);
+        if unhealthy_for < Duration::from_secs(settings.failover_after_secs) {
+            return None;
+        }
+
+        let others: Vec<DestinationState> = status
+            .destinations
+            .iter()
+            .filter(|ds| ds.destination.id != id)
+            .cloned()
+            .collect();
+        // nothing to switch to: keep watching, the next tick may offer one
+        let to = pick_startup_target(
+            &others,
+            &status.latency,
+            &settings.preferred_exits,
+            settings.exit_node_sort_order,
+        )?;
+
+        self.unhealthy_since = None;
+        Some(Failover {
+            from: id,
+            to,
+            reason: format!("{problem} for {}s", unhealthy_for.as_secs()),
+        })
+    }
+}
+
+fn health_problem(view: &RouteHealthView) -> Option<String> {
+    if let RouteHealthState::Unrecoverable { reason } = &view.state {
+        return Some(format!("exit unrecoverable ({reason:?})"));
+    }
+    if view.consecutive_failures == 0 {
+        return None;
+    }
+    let mut problem = format!("{} failed health checks", view.consecutive_failures);
+    if let Some(error) = &view.last_error {
+        problem.push_str(&format!(" ({error})"));
+    }
+    Some(problem)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gnosis_vpn_lib::command::ConnectedInfo;
+
+    use std::collections::BTreeMap;
+    use std::time::SystemTime;
+
+    use crate::settings::ExitPreference;
+    use crate::test_fixtures::{destination_state, ready};
+    use crate::types::RunMode;
+
+    fn failing(id: &str, failures: u32) -> DestinationState {
+        let mut ds = destination_state(id, None, RouteHealthState::Routable);
+        let view = ds.route_health.as_mut().unwrap();
+        view.consecutive_failures = failures;
+        view.last_error = Some("ping timeout".to_string());
+        ds
+    }
+
+    fn connected_to(id: &str, destinations: Vec<DestinationState>) -> StatusResponse {
+        StatusResponse {
+            run_mode: RunMode::NotRunning,
+            destinations,
+            target_destination: Some(id.to_string()),
+            connected: Some(ConnectedInfo {
+                destination_id: id.to_string(),
+                since: SystemTime::UNIX_EPOCH,
+            }),
+            connecting: None,
+            reconnecting: None,
+            disconnecting: vec![],
+            latency: BTreeMap::new(),
+            scores: BTreeMap::new(),
+        }
+    }
+
+    fn enabled() -> Settings {
+        Settings {
+            exit_failover: true,
+            failover_after_secs: 30,
+            ..Settings::default()
+        }
+    }
+
+    fn failing_status() -> StatusResponse {
+        connected_to(
+            "bad",
+            vec![failing("bad", 3), ready("slow", 80), ready("fast", 20)],
+        )
+    }
+
+    #[test]
+    fn switches_to_fastest_exit_after_threshold() {
+        let mut monitor = FailoverMonitor::default();
+        let start = Instant::now();
+        let status = failing_status();
+
+        assert_eq!(monitor.on_status(&status, &enabled(), start), None);
+        assert_eq!(
+            monitor.on_status(&status, &enabled(), start + Duration::from_secs(29)),
+            None
+        );
+        let failover = monitor
+            .on_status(&status, &enabled(), start + Duration::from_secs(31))
+            .unwrap();
+        assert_eq!(failover.from, "bad");
+        assert_eq!(failover.to, "fast");
+        assert_eq!(
+            failover.reason,
+            "3 failed health checks (ping timeout) for 31s"
+        );
+    }
+
+    #[test]
+    fn recovery_restarts_the_clock() {
+        let mut monitor = FailoverMonitor::default();
+        let start = Instant::now();
+        monitor.on_status(&failing_status(), &enabled(), start);
+
+        let healthy = connected_to("bad", vec![ready("bad", 10), ready("fast", 20)]);
+        monitor.on_status(&healthy, &enabled(), start + Duration::from_secs(20));
+        assert_eq!(
+            monitor.on_status(
+                &failing_status(),
+                &enabled(),
+                start + Duration::from_secs(40)
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn respects_opt_in_and_pinned_exit() {
+        let start = Instant::now();
+        let later = start + Duration::from_secs(60);
+
+        let mut monitor = FailoverMonitor::default();
+        let off = Settings::default();
+        monitor.on_status(&failing_status(), &off, start);
+        assert_eq!(monitor.on_status(&failing_status(), &off, later), None);
+
+        let mut monitor = FailoverMonitor::default();
+        let pinned = Settings {
+            preferred_exits: vec![ExitPreference::Id("bad".to_string())],
+            ..enabled()
+        };
+        monitor.on_status(&failing_status(), &pinned, start);
+        assert_eq!(monitor.on_status(&failing_status(), &pinned, later), None);
+    }
+
+    #[test]
+    fn prefers_the_preferred_exit_when_ready() {
+        let mut monitor = FailoverMonitor::default();
+        let start = Instant::now();
+        let settings = Settings {
+            preferred_exits: vec![ExitPreference::Id("slow".to_string())],
+            ..enabled()
+        };
+        monitor.on_status(&failing_status(), &settings, start);
+        let failover = monitor
+            .on_status(
+                &failing_status(),
+                &settings,
+                start + Duration::from_secs(30),
+            )
+            .unwrap();
+        assert_eq!(failover.to, "slow");
+    }
+
+    #[test]
+    fn unrecoverable_exit_is_unhealthy_without_failures() {
+        let mut monitor = FailoverMonitor::default();
+        let start = Instant::now();
+        let status = connected_to(
+            "bad",
+            vec![
+                destination_state(
+                    "bad",
+                    None,
+                    RouteHealthState::Unrecoverable {
+                        reason: gnosis_vpn_lib::route_health::UnrecoverableReason::NotAllowed,
+                    },
+                ),
+                ready("fast", 20),
+            ],
+        );
+        monitor.on_status(&status, &enabled(), start);
+        let failover = monitor
+            .on_status(&status, &enabled(), start + Duration::from_secs(30))
+            .unwrap();
+        assert_eq!(failover.reason, "exit unrecoverable (NotAllowed) for 30s");
+    }
+}
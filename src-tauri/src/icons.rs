@@ -10,7 +10,7 @@ use tauri::{AppHandle, Manager, tray::TrayIcon};
 use tokio::time::sleep;
 
 use crate::commands::set_app_icon;
-use crate::types::{BalanceResponse, ConnectionState, RunMode};
+use crate::types::{BalanceResponse, ConnectionState, RunMode, TauriFundingStatus};
 
 // App icon constants
 pub const APP_ICON_CONNECTED: &str = "app-icon-connected.png";
@@ -212,6 +212,17 @@ pub enum FundsLevel {
     Empty,
 }
 
+impl FundsLevel {
+    /// Lowercase name for the tray and the CLI.
+    pub fn name(self) -> &'static str {
+        match self {
+            FundsLevel::Sufficient => "sufficient",
+            FundsLevel::Low => "low",
+            FundsLevel::Empty => "empty",
+        }
+    }
+}
+
 impl From<gnosis_vpn_lib::balance::FundingLevel> for FundsLevel {
     fn from(level: gnosis_vpn_lib::balance::FundingLevel) -> Self {
         match level {
@@ -234,6 +245,11 @@ pub fn funds_level(run_mode: &RunMode, balance: Option<&BalanceResponse>) -> Fun
     else {
         return FundsLevel::Sufficient;
     };
+    funding_status_level(status)
+}
+
+/// The worse of the traffic and gas levels.
+pub fn funding_status_level(status: &TauriFundingStatus) -> FundsLevel {
     worst(status.traffic.into(), status.gas.into())
 }
 
@@ -0,0 +1,202 @@
+//! Per-destination history of exit health checks.
+//!
+//! Every status carries the latest `ExitHealth` of ready exits; one sample
+//! is kept per health check (by `checked_at`, polls repeat it) in a bounded
+//! ring buffer. Smoothed latency and jitter are exponentially weighted so a
+//! single slow ping does not reorder the exit list or flip startup selection.
+
+use gnosis_vpn_lib::command::DestinationState;
+use gnosis_vpn_lib::route_health::{ExitHealth, LoadAvg, RouteHealthState, Slots};
+use serde::Serialize;
+use tauri::State;
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, UNIX_EPOCH};
+
+/// Health checks kept per destination.
+const CAPACITY: usize = 30;
+/// Weight of the newest sample in the moving averages.
+const ALPHA: f64 = 0.25;
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Sample {
+    pub checked_at_ms: u64,
+    pub ping_rtt_ms: u64,
+    pub slots: Slots,
+    pub load_avg: LoadAvg,
+}
+
+impl From<&ExitHealth> for Sample {
+    fn from(exit: &ExitHealth) -> Self {
+        Sample {
+            checked_at_ms: exit
+                .checked_at
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or_default(),
+            ping_rtt_ms: exit.ping_rtt.as_millis() as u64,
+            slots: exit.health.slots.clone(),
+            load_avg: exit.health.load_avg.clone(),
+        }
+    }
+}
+
+/// Summary sent with every `status` as `latency[destination_id]`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct LatencyStats {
+    pub samples: usize,
+    pub last_ms: u64,
+    pub smoothed_ms: u64,
+    /// Smoothed absolute difference between consecutive samples.
+    pub jitter_ms: u64,
+}
+
+impl LatencyStats {
+    pub fn smoothed(&self) -> Duration {
+        Duration::from_millis(self.smoothed_ms)
+    }
+
+    fn from_samples(samples: &VecDeque<Sample>) -> Option<Self> {
+        let mut rtts = samples.iter().map(|s| s.ping_rtt_ms as f64);
+        let first = rtts.next()?;
+        let (mut smoothed, mut jitter, mut last) = (first, 0.0, first);
+        for rtt in rtts {
+            smoothed += ALPHA * (rtt - smoothed);
+            jitter += ALPHA * ((rtt - last).abs() - jitter);
+            last = rtt;
+        }
+        Some(LatencyStats {
+            samples: samples.len(),
+            last_ms: last as u64,
+            smoothed_ms: smoothed.round() as u64,
+            jitter_ms: jitter.round() as u64,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct DestinationLatency {
+    pub samples: Vec<Sample>,
+    pub stats: LatencyStats,
+}
+
+#[derive(Debug, Default)]
+pub struct LatencyHistory {
+    inner: Mutex<HashMap<String, VecDeque<Sample>>>,
+}
+
+impl LatencyHistory {
+    /// Records new health checks from one status and returns the stats of
+    /// every destination with history. Destinations the daemon no longer
+    /// lists are forgotten.
+    pub fn observe(&self, destinations: &[DestinationState]) -> BTreeMap<String, LatencyStats> {
+        let mut history = self.lock();
+        history.retain(|id, _| destinations.iter().any(|ds| ds.destination.id == *id));
+        for ds in destinations {
+            let exit = match ds.route_health.as_ref().map(|v| &v.state) {
+                Some(RouteHealthState::ReadyToConnect { exit })
+                | Some(RouteHealthState::Connecting { exit, .. }) => exit,
+                _ => continue,
+            };
+            let sample = Sample::from(exit);
+            let samples = history.entry(ds.destination.id.clone()).or_default();
+            if samples
+                .back()
+                .is_some_and(|last| last.checked_at_ms == sample.checked_at_ms)
+            {
+                continue;
+            }
+            if samples.len() == CAPACITY {
+                samples.pop_front();
+            }
+            samples.push_back(sample);
+        }
+        history
+            .iter()
+            .filter_map(|(id, samples)| {
+                LatencyStats::from_samples(samples).map(|stats| (id.clone(), stats))
+            })
+            .collect()
+    }
+
+    pub fn snapshot(&self) -> BTreeMap<String, DestinationLatency> {
+        self.lock()
+            .iter()
+            .filter_map(|(id, samples)| {
+                let stats = LatencyStats::from_samples(samples)?;
+                Some((
+                    id.clone(),
+                    DestinationLatency {
+                        samples: samples.iter().cloned().collect(),
+                        stats,
+                    },
+                ))
+            })
+            .collect()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, VecDeque<Sample>>> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[tauri::command]
+pub fn get_latency_history(
+    history: State<'_, LatencyHistory>,
+) -> BTreeMap<String, DestinationLatency> {
+    history.snapshot()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures;
+
+    fn ready(id: &str, checked_at_secs: u64, rtt_ms: u64) -> DestinationState {
+        let mut exit = test_fixtures::exit_health(rtt_ms);
+        exit.checked_at = UNIX_EPOCH + Duration::from_secs(checked_at_secs);
+        test_fixtures::destination_state(id, None, RouteHealthState::ReadyToConnect { exit })
+    }
+
+    #[test]
+    fn repeated_health_check_is_recorded_once() {
+        let history = LatencyHistory::default();
+        history.observe(&[ready("a", 10, 40)]);
+        let stats = history.observe(&[ready("a", 10, 40)]);
+        assert_eq!(stats["a"].samples, 1);
+        assert_eq!(stats["a"].smoothed_ms, 40);
+        assert_eq!(stats["a"].jitter_ms, 0);
+    }
+
+    #[test]
+    fn single_spike_is_damped() {
+        let history = LatencyHistory::default();
+        for t in 0..8 {
+            history.observe(&[ready("a", t, 40)]);
+        }
+        let stats = history.observe(&[ready("a", 8, 400)]);
+        assert_eq!(stats["a"].last_ms, 400);
+        assert_eq!(stats["a"].smoothed_ms, 130);
+        assert_eq!(stats["a"].jitter_ms, 90);
+    }
+
+    #[test]
+    fn buffer_is_bounded_and_forgets_vanished_destinations() {
+        let history = LatencyHistory::default();
+        for t in 0..(CAPACITY as u64 + 5) {
+            history.observe(&[ready("a", t, 40), ready("b", t, 50)]);
+        }
+        let snapshot = history.snapshot();
+        assert_eq!(snapshot["a"].samples.len(), CAPACITY);
+        assert_eq!(
+            snapshot["a"].samples[0].checked_at_ms,
+            5 * 1000,
+            "oldest samples are dropped first"
+        );
+
+        let stats = history.observe(&[ready("b", 100, 50)]);
+        assert!(!stats.contains_key("a"));
+        assert!(history.snapshot().get("a").is_none());
+    }
+}
@@ -8,33 +8,61 @@ use tokio::sync::Notify;
 use tokio::sync::watch;
 use tokio_util::sync::CancellationToken;
 
-use std::path::PathBuf;
+use std::ffi::OsString;
 use std::sync::{Arc, Mutex};
 
+pub mod auto_connect;
+pub mod autostart;
+mod cli;
 mod commands;
-mod icons;
+pub mod companion;
+#[cfg(unix)]
+pub mod control;
+pub mod daemon;
+#[cfg(target_os = "linux")]
+pub mod dbus;
+pub mod deeplink;
+pub mod error;
+pub mod exits;
+pub mod failover;
+pub mod icons;
+pub mod latency;
+pub mod metrics;
+pub mod notifications;
 mod platform;
+pub mod polling;
+pub mod score;
+pub mod sessions;
 pub mod settings;
+#[cfg(test)]
+mod test_fixtures;
 mod theme;
 pub mod tray;
 pub mod types;
 pub mod update_install;
 
+use cli::LaunchArgs;
 use commands::{
     check_update, compress_logs, connect, disconnect, get_cached_state, get_platform,
-    run_initialization_loop, set_app_icon, stop_client,
+    run_initialization_loop, set_app_icon,
 };
+use daemon::{DaemonClient, DaemonSocket};
+use error::AppError;
+use gnosis_vpn_lib::command;
 use gnosis_vpn_lib::command::InfoResponse;
-use gnosis_vpn_lib::{command, socket::root as root_socket};
 use icons::{IconState, TrayIconState, determine_tray_icon, start_icon_heartbeat};
+use latency::{LatencyHistory, get_latency_history};
 use platform::{Platform, PlatformInterface};
-use settings::{SettingsStore, get_settings, update_settings};
+use sessions::{EndReason, SessionHistory, export_session_history, get_session_history};
+use settings::{
+    SettingsStore, export_settings, get_settings, import_settings, reset_settings, update_settings,
+};
 #[cfg(target_os = "linux")]
 use theme::spawn_linux_theme_monitor;
 #[cfg_attr(target_os = "macos", allow(unused_imports))]
 use theme::{InitialTheme, get_initial_theme, system_theme};
 use tray::{
-    create_tray_menu, handle_tray_event, show_settings, show_settings_and_check,
+    create_tray_menu, handle_tray_event, show_main_window, show_settings, show_settings_and_check,
     toggle_main_window_visibility,
 };
 use types::ConnectionState;
@@ -60,8 +88,8 @@ pub struct BalancePollingHandle {
 }
 
 pub struct AppStateCache {
-    pub status: watch::Sender<Option<Result<Option<StatusResponse>, String>>>,
-    pub balance: watch::Sender<Option<Result<Option<BalanceResponse>, String>>>,
+    pub status: watch::Sender<Option<Result<Option<StatusResponse>, AppError>>>,
+    pub balance: watch::Sender<Option<Result<Option<BalanceResponse>, AppError>>>,
     pub service_info: watch::Sender<Option<InfoResponse>>,
 }
 
@@ -181,15 +209,14 @@ pub fn run() {
     }
 
     tauri::Builder::default()
-        .plugin(tauri_plugin_single_instance::init(|app, _argv, _cwd| {
-            // A second instance was launched — bring the existing window to focus
-            #[cfg(target_os = "macos")]
-            {
-                let _ = app.set_activation_policy(tauri::ActivationPolicy::Regular);
-            }
-            if let Some(window) = app.get_webview_window("main") {
-                let _ = window.show();
-                let _ = window.set_focus();
+        .plugin(tauri_plugin_single_instance::init(|app, argv, _cwd| {
+            // A second instance was launched — carry out what its arguments
+            // ask for, or bring the existing window to focus
+            let args = LaunchArgs::parse(argv.into_iter().skip(1).map(OsString::from));
+            if !args.actions.is_empty() {
+                commands::run_launch_actions(app, args.actions);
+            } else if !args.minimized {
+                show_main_window(app);
             }
         }))
         .plugin(tauri_plugin_opener::init())
@@ -198,6 +225,19 @@ pub fn run() {
             // Load settings (settings.json) before any UI decisions
             let settings_path = app.path().app_data_dir()?.join("settings.json");
             app.manage(SettingsStore::load(settings_path));
+            let sessions_path = app.path().app_data_dir()?.join("sessions.json");
+            app.manage(SessionHistory::load(sessions_path));
+            app.manage(LatencyHistory::default());
+            app.manage(Mutex::new(auto_connect::AutoConnect::default()));
+
+            // Resolve the daemon socket once; every daemon call reads it from state
+            let launch_args = LaunchArgs::from_env();
+            let socket_pin = daemon::launch_override(
+                launch_args.socket_path.clone(),
+                std::env::var_os(daemon::SOCKET_PATH_ENV),
+            );
+            let socket_setting = app.state::<SettingsStore>().current().daemon_socket_path;
+            app.manage(DaemonSocket::new(socket_pin, socket_setting.as_deref()));
 
             // First step: OS theme for app windows (all OS) and tray icons (non-macOS only)
             let theme = system_theme();
@@ -229,11 +269,7 @@ pub fn run() {
                     "quit" => {
                         let app_clone = app.clone();
                         tauri::async_runtime::spawn(async move {
-                            let socket = PathBuf::from(root_socket::DEFAULT_PATH);
-                            let _ =
-                                root_socket::process_cmd(&socket, &command::Command::Disconnect)
-                                    .await;
-                            app_clone.exit(0);
+                            commands::disconnect_and_quit(&app_clone).await;
                         });
                     }
                     "show" => {
@@ -243,7 +279,25 @@ pub fn run() {
                     "logs" => show_settings(app, "logs"),
                     "usage" => show_settings(app, "usage"),
                     "check_update" => show_settings_and_check(app),
-                    _ => {}
+                    "toggle_connection" => {
+                        let app_clone = app.clone();
+                        tauri::async_runtime::spawn(async move {
+                            if let Err(e) = commands::toggle_connection(&app_clone).await {
+                                eprintln!("Tray connect/disconnect failed: {e}");
+                            }
+                        });
+                    }
+                    id => {
+                        if let Some(exit_id) = id.strip_prefix(tray::CONNECT_TO_PREFIX) {
+                            let app_clone = app.clone();
+                            let exit_id = exit_id.to_string();
+                            tauri::async_runtime::spawn(async move {
+                                if let Err(e) = commands::connect_to(&app_clone, exit_id).await {
+                                    eprintln!("Tray connect failed: {e}");
+                                }
+                            });
+                        }
+                    }
                 })
                 .on_tray_icon_event(|tray, event| {
                     handle_tray_event(tray.app_handle(), event);
@@ -294,30 +348,29 @@ pub fn run() {
                     })
                     .and_then(|p| p.to_str().map(String::from));
                 tauri::async_runtime::spawn(async move {
-                    let socket = PathBuf::from(root_socket::DEFAULT_PATH);
                     let fallback = "Version: Something is wrong".to_string();
-                    let pkg: String =
-                        match root_socket::process_cmd(&socket, &command::Command::Info).await {
-                            Ok(command::Response::Info(info)) => {
-                                eprintln!(
-                                    "[about-panel] daemon Info.package_version = {:?}",
-                                    info.package_version
-                                );
-                                println!(
-                                    "[about-panel] daemon Info: {:?}",
-                                    info.package_version.as_deref().unwrap_or("<none>")
-                                );
-                                info.package_version.unwrap_or_else(|| fallback.clone())
-                            }
-                            Ok(other) => {
-                                eprintln!("[about-panel] unexpected daemon response: {:?}", other);
-                                fallback.clone()
-                            }
-                            Err(e) => {
-                                eprintln!("[about-panel] daemon call failed: {:?}", e);
-                                fallback.clone()
-                            }
-                        };
+                    let daemon = app_handle.state::<DaemonSocket>();
+                    let pkg: String = match daemon.process_cmd(&command::Command::Info).await {
+                        Ok(command::Response::Info(info)) => {
+                            eprintln!(
+                                "[about-panel] daemon Info.package_version = {:?}",
+                                info.package_version
+                            );
+                            println!(
+                                "[about-panel] daemon Info: {:?}",
+                                info.package_version.as_deref().unwrap_or("<none>")
+                            );
+                            info.package_version.unwrap_or_else(|| fallback.clone())
+                        }
+                        Ok(other) => {
+                            eprintln!("[about-panel] unexpected daemon response: {:?}", other);
+                            fallback.clone()
+                        }
+                        Err(e) => {
+                            eprintln!("[about-panel] daemon call failed: {:?}", e);
+                            fallback.clone()
+                        }
+                    };
                     install_macos_about_panel_override(&app_handle, pkg, icon_path);
                 });
             }
@@ -353,7 +406,8 @@ pub fn run() {
 
             // Decide initial window visibility based on settings
             if let Some(window) = app.get_webview_window("main") {
-                let start_minimized = app.state::<SettingsStore>().current().start_minimized;
+                let start_minimized =
+                    launch_args.minimized || app.state::<SettingsStore>().current().start_minimized;
                 #[cfg(target_os = "macos")]
                 {
                     let policy = if start_minimized {
@@ -395,11 +449,29 @@ pub fn run() {
                 service_info: service_info_tx,
             });
 
+            app.manage(metrics::PollCounters::default());
+            app.manage(metrics::MetricsServer::default());
+            app.manage(Mutex::new(notifications::Notifier::default()));
+            metrics::apply_setting(app.handle(), &app.state::<SettingsStore>().current());
+            // the entry may have been removed or added outside the app
+            autostart::sync(app.handle());
+            // publishes to the state managed above
+            settings::spawn_watcher(app.handle().clone());
+
+            // after the cache and settings it serves
+            #[cfg(unix)]
+            control::start(app.handle());
+            #[cfg(target_os = "linux")]
+            dbus::start(app.handle());
+
             let app_handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
                 run_initialization_loop(app_handle).await;
             });
 
+            // after the window visibility is decided, so `--show` wins
+            commands::run_launch_actions(app.handle(), launch_args.actions);
+
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -412,10 +484,17 @@ pub fn run() {
             get_cached_state,
             get_settings,
             update_settings,
+            export_settings,
+            import_settings,
+            reset_settings,
             get_platform,
             install_update,
             get_install_status,
-            get_toolkit_version
+            get_toolkit_version,
+            get_session_history,
+            get_latency_history,
+            export_session_history,
+            autostart::refresh_launch_at_login
         ])
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
@@ -445,14 +524,24 @@ pub fn run() {
                     let _ = tauri::async_runtime::block_on(handle);
                 }
 
+                // polling has stopped, so nothing else will end the open session
+                app_handle
+                    .state::<SessionHistory>()
+                    .end_active(EndReason::AppExit);
+
                 if let Ok(mut guard) = app_handle.state::<HeartbeatHandle>().0.lock() {
                     if let Some(handle) = guard.take() {
                         handle.abort();
                     }
                 }
 
+                #[cfg(unix)]
+                control::remove_socket(app_handle);
+
                 // inform the client about the shutdown
-                if let Err(reason) = tauri::async_runtime::block_on(async { stop_client().await }) {
+                let daemon = app_handle.state::<DaemonSocket>();
+                if let Err(reason) = tauri::async_runtime::block_on(polling::stop_client(&*daemon))
+                {
                     eprintln!("Error stopping client on exit: {reason}");
                 }
             }
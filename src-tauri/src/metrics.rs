@@ -0,0 +1,419 @@
+//! Optional OpenMetrics endpoint on `127.0.0.1:<metricsPort>/metrics`.
+//!
+//! Off by default (`metricsEndpoint`). Every scrape renders the cached
+//! status and balance the polling loops already hold, so monitoring never
+//! adds daemon traffic. Only loopback is bound; there is no authentication.
+
+use gnosis_vpn_lib::balance::FundingLevel;
+use gnosis_vpn_lib::route_health::RouteHealthState;
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::time;
+use tokio_util::sync::CancellationToken;
+
+use std::fmt::Write;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use crate::AppStateCache;
+use crate::settings::Settings;
+use crate::types::{BalanceResponse, ConnectionState, StatusResponse, tokens};
+
+const CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+/// Scrapers send a request line and a few headers; anything longer is not one.
+const MAX_REQUEST_BYTES: usize = 8 * 1024;
+
+/// Counters the cache cannot answer: it only holds the latest result.
+#[derive(Debug, Default)]
+pub struct PollCounters {
+    status_errors: AtomicU64,
+}
+
+impl PollCounters {
+    pub fn status_error(&self) {
+        self.status_errors.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Managed state: the port being served and how to stop it.
+#[derive(Default)]
+pub struct MetricsServer(Mutex<Option<(u16, CancellationToken)>>);
+
+/// Starts, stops or moves the endpoint to match the settings.
+pub fn apply_setting(app: &AppHandle, settings: &Settings) {
+    let server = app.state::<MetricsServer>();
+    let mut running = server.0.lock().unwrap_or_else(|e| e.into_inner());
+    let wanted = settings.metrics_endpoint.then_some(settings.metrics_port);
+    if running.as_ref().map(|(port, _)| *port) == wanted {
+        return;
+    }
+    if let Some((port, cancel)) = running.take() {
+        println!("Metrics endpoint on port {port} stopped");
+        cancel.cancel();
+    }
+    let Some(port) = wanted else {
+        return;
+    };
+    let cancel = CancellationToken::new();
+    *running = Some((port, cancel.clone()));
+    tauri::async_runtime::spawn(serve(app.clone(), port, cancel));
+}
+
+async fn serve(app: AppHandle, port: u16, cancel: CancellationToken) {
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    let listener = match TcpListener::bind(addr).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("Metrics endpoint disabled: cannot bind {addr}: {e}");
+            // forget the port so the next settings change retries it
+            let server = app.state::<MetricsServer>();
+            let mut running = server.0.lock().unwrap_or_else(|e| e.into_inner());
+            if !cancel.is_cancelled() {
+                *running = None;
+            }
+            return;
+        }
+    };
+    println!("Metrics endpoint listening on http://{addr}/metrics");
+    loop {
+        tokio::select! {
+            _ = cancel.cancelled() => return,
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => {
+                    let app = app.clone();
+                    tauri::async_runtime::spawn(async move {
+                        if let Err(e) = time::timeout(REQUEST_TIMEOUT, respond(&app, stream)).await {
+                            eprintln!("Metrics request timed out: {e}");
+                        }
+                    });
+                }
+                Err(e) => eprintln!("Metrics endpoint: accept failed: {e}"),
+            },
+        }
+    }
+}
+
+async fn respond(app: &AppHandle, mut stream: TcpStream) {
+    let mut request = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+        match stream.read(&mut buf).await {
+            Ok(0) | Err(_) => return,
+            Ok(n) => request.extend_from_slice(&buf[..n]),
+        }
+        if request.len() > MAX_REQUEST_BYTES {
+            return;
+        }
+    }
+    let request_line = request.split(|b| *b == b'\r').next().unwrap_or_default();
+    let response = match request_line.split(|b| *b == b' ').collect::<Vec<_>>()[..] {
+        [b"GET", b"/metrics", _] => {
+            let body = render_cached(app);
+            format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: {CONTENT_TYPE}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+        }
+        _ => "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string(),
+    };
+    let _ = stream.write_all(response.as_bytes()).await;
+}
+
+fn render_cached(app: &AppHandle) -> String {
+    let cache = app.state::<AppStateCache>();
+    let status = cache.status.borrow().clone();
+    let balance = cache.balance.borrow().clone();
+    render(
+        status.and_then(Result::ok).flatten().as_ref(),
+        balance.and_then(Result::ok).flatten().as_ref(),
+        app.state::<PollCounters>()
+            .status_errors
+            .load(Ordering::Relaxed),
+    )
+}
+
+/// The exposition text. Families without data (no status yet, no balance
+/// yet) are left out rather than reported as zero.
+pub fn render(
+    status: Option<&StatusResponse>,
+    balance: Option<&BalanceResponse>,
+    status_errors: u64,
+) -> String {
+    let mut out = String::new();
+
+    if let Some(status) = status {
+        let conn_state = ConnectionState::from(status);
+        family(
+            &mut out,
+            "gnosisvpn_connected",
+            "gauge",
+            "1 while a VPN connection is up.",
+        );
+        let connected = matches!(conn_state, ConnectionState::Connected(_));
+        sample(&mut out, "gnosisvpn_connected", &[], u8::from(connected));
+
+        let target = match &conn_state {
+            ConnectionState::Connected(id) => Some((id.as_str(), "connected")),
+            ConnectionState::Connecting(id) => Some((id.as_str(), "connecting")),
+            ConnectionState::Reconnecting(id) => Some((id.as_str(), "reconnecting")),
+            ConnectionState::Disconnecting | ConnectionState::Disconnected => None,
+        };
+        family(
+            &mut out,
+            "gnosisvpn_destination",
+            "info",
+            "The exit in use.",
+        );
+        if let Some((id, state)) = target {
+            sample(
+                &mut out,
+                "gnosisvpn_destination_info",
+                &[("destination", id), ("state", state)],
+                1,
+            );
+        }
+
+        let exits: Vec<_> = status
+            .destinations
+            .iter()
+            .filter_map(|ds| {
+                let view = ds.route_health.as_ref()?;
+                let exit = match &view.state {
+                    RouteHealthState::ReadyToConnect { exit }
+                    | RouteHealthState::Connecting { exit, .. } => Some(exit),
+                    _ => None,
+                };
+                Some((ds.destination.id.as_str(), view, exit))
+            })
+            .collect();
+        family(
+            &mut out,
+            "gnosisvpn_exit_ping_rtt_seconds",
+            "gauge",
+            "Latest health-check round trip of a ready exit.",
+        );
+        for &(id, _, exit) in &exits {
+            if let Some(exit) = exit {
+                sample(
+                    &mut out,
+                    "gnosisvpn_exit_ping_rtt_seconds",
+                    &[("destination", id)],
+                    exit.ping_rtt.as_secs_f64(),
+                );
+            }
+        }
+        family(
+            &mut out,
+            "gnosisvpn_exit_slots",
+            "gauge",
+            "Session slots of a ready exit.",
+        );
+        for &(id, _, exit) in &exits {
+            if let Some(exit) = exit {
+                let slots = &exit.health.slots;
+                for (kind, value) in [
+                    ("available", slots.available as f64),
+                    ("connected", slots.connected as f64),
+                ] {
+                    sample(
+                        &mut out,
+                        "gnosisvpn_exit_slots",
+                        &[("destination", id), ("kind", kind)],
+                        value,
+                    );
+                }
+            }
+        }
+        family(
+            &mut out,
+            "gnosisvpn_exit_consecutive_failures",
+            "gauge",
+            "Route health checks failed in a row.",
+        );
+        for &(id, view, _) in &exits {
+            sample(
+                &mut out,
+                "gnosisvpn_exit_consecutive_failures",
+                &[("destination", id)],
+                view.consecutive_failures,
+            );
+        }
+    }
+
+    if let Some(balance) = balance {
+        for (name, help, amount) in [
+            ("gnosisvpn_node_xdai", "xDAI on the node.", &balance.node),
+            (
+                "gnosisvpn_safe_wxhopr",
+                "wxHOPR in the safe.",
+                &balance.safe,
+            ),
+            (
+                "gnosisvpn_channels_out_wxhopr",
+                "wxHOPR in outgoing channels.",
+                &balance.channels_out,
+            ),
+        ] {
+            if let Some(amount) = tokens(amount) {
+                family(&mut out, name, "gauge", help);
+                sample(&mut out, name, &[], amount);
+            }
+        }
+        if let Some(funding) = &balance.funding_status {
+            family(
+                &mut out,
+                "gnosisvpn_funding_level",
+                "gauge",
+                "1 for the current level of each funding kind.",
+            );
+            for (kind, current) in [("traffic", funding.traffic), ("gas", funding.gas)] {
+                for (level, label) in [
+                    (FundingLevel::Good, "good"),
+                    (FundingLevel::Low, "low"),
+                    (FundingLevel::Empty, "empty"),
+                ] {
+                    let is_current =
+                        std::mem::discriminant(&current) == std::mem::discriminant(&level);
+                    sample(
+                        &mut out,
+                        "gnosisvpn_funding_level",
+                        &[("kind", kind), ("level", label)],
+                        u8::from(is_current),
+                    );
+                }
+            }
+        }
+    }
+
+    family(
+        &mut out,
+        "gnosisvpn_status_poll_errors",
+        "counter",
+        "Status polls that failed since the app started.",
+    );
+    sample(
+        &mut out,
+        "gnosisvpn_status_poll_errors_total",
+        &[],
+        status_errors,
+    );
+
+    out.push_str("# EOF\n");
+    out
+}
+
+fn family(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+    let _ = writeln!(out, "# HELP {name} {help}");
+}
+
+fn sample(out: &mut String, name: &str, labels: &[(&str, &str)], value: impl std::fmt::Display) {
+    out.push_str(name);
+    if !labels.is_empty() {
+        let labels: Vec<String> = labels
+            .iter()
+            .map(|(key, value)| format!("{key}=\"{}\"", escape(value)))
+            .collect();
+        let _ = write!(out, "{{{}}}", labels.join(","));
+    }
+    let _ = writeln!(out, " {value}");
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{self, address};
+    use crate::types::{RunMode, TauriFundingStatus};
+    use gnosis_vpn_lib::command::{self, ConnectedInfo, DestinationState};
+
+    use std::collections::BTreeMap;
+    use std::time::SystemTime;
+
+    fn ready(id: &str) -> DestinationState {
+        let mut ds = test_fixtures::ready(id, 42);
+        ds.route_health.as_mut().unwrap().consecutive_failures = 2;
+        ds
+    }
+
+    fn connected_status() -> StatusResponse {
+        StatusResponse {
+            run_mode: RunMode::NotRunning,
+            destinations: vec![ready("exit-1")],
+            target_destination: Some("exit-1".to_string()),
+            connected: Some(ConnectedInfo {
+                destination_id: "exit-1".to_string(),
+                since: SystemTime::UNIX_EPOCH,
+            }),
+            connecting: None,
+            reconnecting: None,
+            disconnecting: vec![],
+            latency: BTreeMap::new(),
+            scores: BTreeMap::new(),
+        }
+    }
+
+    fn balance() -> BalanceResponse {
+        BalanceResponse {
+            node: "1500000000000000000".to_string(),
+            safe: "3200000000000000000".to_string(),
+            channels_out: "0".to_string(),
+            info: command::Info {
+                node_address: address(),
+                node_peer_id: "16Uiu2HAmTest".to_string(),
+                safe_address: address(),
+            },
+            funding_status: Some(TauriFundingStatus {
+                traffic: FundingLevel::Low,
+                gas: FundingLevel::Good,
+                wxhopr_deficit: None,
+                xdai_deficit: None,
+            }),
+            ideal_balance: None,
+            capacity_allocations: None,
+        }
+    }
+
+    #[test]
+    fn renders_connection_exits_and_funds() {
+        let text = render(Some(&connected_status()), Some(&balance()), 3);
+        for line in [
+            "gnosisvpn_connected 1",
+            "gnosisvpn_destination_info{destination=\"exit-1\",state=\"connected\"} 1",
+            "gnosisvpn_exit_ping_rtt_seconds{destination=\"exit-1\"} 0.042",
+            "gnosisvpn_exit_slots{destination=\"exit-1\",kind=\"available\"} 10",
+            "gnosisvpn_exit_consecutive_failures{destination=\"exit-1\"} 2",
+            "gnosisvpn_node_xdai 1.5",
+            "gnosisvpn_safe_wxhopr 3.2",
+            "gnosisvpn_funding_level{kind=\"traffic\",level=\"low\"} 1",
+            "gnosisvpn_funding_level{kind=\"gas\",level=\"low\"} 0",
+            "gnosisvpn_status_poll_errors_total 3",
+        ] {
+            assert!(text.lines().any(|l| l == line), "missing {line} in\n{text}");
+        }
+        assert!(text.ends_with("# EOF\n"));
+    }
+
+    #[test]
+    fn leaves_out_what_has_not_been_polled() {
+        let text = render(None, None, 0);
+        assert!(!text.contains("gnosisvpn_connected"));
+        assert!(!text.contains("gnosisvpn_node_xdai"));
+        assert!(text.contains("gnosisvpn_status_poll_errors_total 0"));
+    }
+
+    #[test]
+    fn escapes_label_values() {
+        assert_eq!(escape("a\"b\\c\n"), "a\\\"b\\\\c\\n");
+    }
+}
@@ -0,0 +1,665 @@
+//! Native desktop notifications for events worth hearing about while the
+//! window is closed: connected, connection dropped, funds low or empty,
+//! `PreparingSafe` funded, and update available.
+//!
+//! `Notifier` turns the status loop's observations into notifications and
+//! decides which are shown: each category has a toggle, quiet hours drop
+//! everything, and the same category and subject is shown at most once per
+//! `DEDUP_WINDOW` so a flapping connection or balance does not spam.
+//! Delivery uses `org.freedesktop.Notifications` on Linux and `osascript`
+//! on macOS, so no extra notification crate is needed.
+
+use chrono::Timelike;
+use tauri::{AppHandle, Manager};
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::AppStateCache;
+use crate::icons::FundsLevel;
+use crate::sessions::{EndReason, Session};
+use crate::settings::{NotificationSettings, Settings, UpdateChannel};
+use crate::types::{ConnectionState, RunMode, StatusResponse};
+
+const APP_NAME: &str = "Gnosis VPN";
+const DEDUP_WINDOW: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Category {
+    Connected,
+    Disconnected,
+    FundsLow,
+    SafeFunded,
+    UpdateAvailable,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Notification {
+    pub category: Category,
+    /// What de-duplication keys on besides the category, e.g. the exit id.
+    subject: String,
+    pub title: String,
+    pub body: String,
+}
+
+/// The previous tick's state; transitions from it are what gets notified.
+#[derive(Debug)]
+struct Observed {
+    connected: Option<String>,
+    funds_level: FundsLevel,
+    preparing_safe: bool,
+}
+
+/// Managed as `Mutex<Notifier>`.
+#[derive(Debug, Default)]
+pub struct Notifier {
+    /// `None` until the first status: what is already true at launch is
+    /// not news.
+    observed: Option<Observed>,
+    /// Each available version is announced once per run.
+    update_notified: Option<String>,
+    /// An update `admit` turned down, e.g. during quiet hours; offered
+    /// again on every status until it is shown.
+    pending_update: Option<Notification>,
+    sent: HashMap<(Category, String), Instant>,
+}
+
+impl Notifier {
+    /// `ended` are the sessions the same status finished (see `sessions`);
+    /// their end reason tells a drop from the user's own disconnect.
+    pub fn on_status(
+        &mut self,
+        status: &StatusResponse,
+        conn_state: &ConnectionState,
+        funds_level: FundsLevel,
+        ended: &[Session],
+    ) -> Vec<Notification> {
+        let connected = match conn_state {
+            ConnectionState::Connected(id) | ConnectionState::Reconnecting(id) => Some(id.clone()),
+            _ => None,
+        };
+        let preparing_safe = matches!(status.run_mode, RunMode::PreparingSafe { .. });
+        let current = Observed {
+            connected,
+            funds_level,
+            preparing_safe,
+        };
+        let Some(previous) = self.observed.replace(current) else {
+            return Vec::new();
+        };
+
+        let mut pending = Vec::new();
+        for session in ended {
+            let dropped = matches!(
+                session.end_reason,
+                EndReason::Dropped | EndReason::ServiceStopped
+            );
+            if dropped && session.connected_at.is_some() {
+                pending.push(Notification {
+                    category: Category::Disconnected,
+                    subject: session.destination_id.clone(),
+                    title: "Connection lost".to_string(),
+                    body: format!(
+                        "The connection to {} dropped.",
+                        label(&session.destination_id, session.meta.get("location"))
+                    ),
+                });
+            }
+        }
+        let newly_connected = match conn_state {
+            ConnectionState::Connected(id) if previous.connected.as_ref() != Some(id) => Some(id),
+            _ => None,
+        };
+        if let Some(id) = newly_connected {
+            let location = status
+                .destinations
+                .iter()
+                .find(|ds| ds.destination.id == *id)
+                .and_then(|ds| ds.destination.meta.get("location"));
+            pending.push(Notification {
+                category: Category::Connected,
+                subject: id.clone(),
+                title: "Connected".to_string(),
+                body: format!("Traffic now exits via {}.", label(id, location)),
+            });
+        }
+        if severity(funds_level) > severity(previous.funds_level) {
+            let (title, body, subject) = match funds_level {
+                FundsLevel::Empty => (
+                    "Out of funds",
+                    "The VPN cannot route traffic until your node is topped up.",
+                    "Empty",
+                ),
+                _ => (
+                    "Funds running low",
+                    "Top up your node soon to keep the VPN running.",
+                    "Low",
+                ),
+            };
+            pending.push(Notification {
+                category: Category::FundsLow,
+                subject: subject.to_string(),
+                title: title.to_string(),
+                body: body.to_string(),
+            });
+        }
+        let safe_deployment = matches!(
+            status.run_mode,
+            RunMode::DeployingSafe { .. } | RunMode::Warmup { .. } | RunMode::Running { .. }
+        );
+        if previous.preparing_safe && safe_deployment {
+            pending.push(Notification {
+                category: Category::SafeFunded,
+                subject: String::new(),
+                title: "Funds received".to_string(),
+                body: "Your node is funded and continues its setup.".to_string(),
+            });
+        }
+        pending
+    }
+
+    /// `available` is the version `available_update` reports, if any. The
+    /// version only counts as announced once `admit` lets it through.
+    pub fn on_update(&mut self, available: Option<&str>) -> Option<Notification> {
+        self.pending_update = available
+            .filter(|version| self.update_notified.as_deref() != Some(*version))
+            .map(|version| Notification {
+                category: Category::UpdateAvailable,
+                subject: version.to_string(),
+                title: "Update available".to_string(),
+                body: format!("{APP_NAME} {version} is ready to install."),
+            });
+        self.pending_update.clone()
+    }
+
+    /// The update notification still waiting to be shown, if any.
+    pub fn pending_update(&self) -> Option<Notification> {
+        self.pending_update.clone()
+    }
+
+    /// Whether `notification` should be shown now; records it if so.
+    pub fn admit(
+        &mut self,
+        notification: &Notification,
+        settings: &NotificationSettings,
+        now: Instant,
+        minute_of_day: u16,
+    ) -> bool {
+        let enabled = match notification.category {
+            Category::Connected => settings.connected,
+            Category::Disconnected => settings.disconnected,
+            Category::FundsLow => settings.funds_low,
+            Category::SafeFunded => settings.safe_funded,
+            Category::UpdateAvailable => settings.update_available,
+        };
+        let quiet = settings
+            .quiet_hours
+            .as_ref()
+            .is_some_and(|hours| hours.contains(minute_of_day));
+        if !enabled || quiet {
+            return false;
+        }
+        self.sent
+            .retain(|_, sent_at| now.duration_since(*sent_at) < DEDUP_WINDOW);
+        let key = (notification.category, notification.subject.clone());
+        if self.sent.contains_key(&key) {
+            return false;
+        }
+        self.sent.insert(key, now);
+        if notification.category == Category::UpdateAvailable {
+            self.update_notified = Some(notification.subject.clone());
+            self.pending_update = None;
+        }
+        true
+    }
+}
+
+fn severity(level: FundsLevel) -> u8 {
+    match level {
+        FundsLevel::Sufficient => 0,
+        FundsLevel::Low => 1,
+        FundsLevel::Empty => 2,
+    }
+}
+
+fn label(id: &str, location: Option<&String>) -> String {
+    match location {
+        Some(location) => format!("{id} ({location})"),
+        None => id.to_string(),
+    }
+}
+
+/// The newer version the update checker's manifest offers on the
+/// effective channel, unless dismissed — `evaluateUpdate` in
+/// `src/utils/updateAvailability.ts`, which drives the in-app banner.
+pub fn available_update(package_version: &str, settings: &Settings) -> Option<String> {
+    let manifest = serde_json::to_value(settings.update_manifest.as_ref()?).ok()?;
+    let installed = detect_channel(package_version);
+    let channel = settings.channel.unwrap_or(installed);
+    let key = match channel {
+        UpdateChannel::Stable => "stable",
+        UpdateChannel::Snapshot => "snapshot",
+    };
+    let latest = manifest["channels"][key]["version"].as_str()?;
+    let newer = installed != channel || is_older(package_version, latest);
+    let dismissed = settings.dismissed_update_version.as_deref() == Some(latest);
+    (newer && !dismissed).then(|| latest.to_string())
+}
+
+fn detect_channel(version: &str) -> UpdateChannel {
+    if version.contains('-') || version.contains('+') {
+        UpdateChannel::Snapshot
+    } else {
+        UpdateChannel::Stable
+    }
+}
+
+/// `compareVersions(a, b) < 0` in `src/utils/version.ts`: `+build.N`
+/// orders numerically and pre-release tags are ignored. A version semver
+/// cannot parse is never older.
+fn is_older(installed: &str, latest: &str) -> bool {
+    let parse = |version: &str| {
+        // date-based snapshots like `2026.08.20+build.013814` have leading
+        // zeros in the core, which semver rejects
+        let (core, tags) = version.split_at(version.find(['-', '+']).unwrap_or(version.len()));
+        let core = core
+            .split('.')
+            .map(|n| match n.trim_start_matches('0') {
+                "" => "0",
+                trimmed => trimmed,
+            })
+            .collect::<Vec<_>>()
+            .join(".");
+        semver::Version::parse(&format!("{core}{tags}"))
+            .ok()
+            .map(|mut version| {
+                version.pre = semver::Prerelease::EMPTY;
+                version
+            })
+    };
+    match (parse(installed), parse(latest)) {
+        (Some(installed), Some(latest)) => installed < latest,
+        _ => false,
+    }
+}
+
+/// Called from the status loop for every status it applies.
+pub fn on_status(
+    app: &AppHandle,
+    status: &StatusResponse,
+    conn_state: &ConnectionState,
+    funds_level: FundsLevel,
+    ended: &[Session],
+    settings: &NotificationSettings,
+) {
+    let Ok(mut notifier) = app.state::<Mutex<Notifier>>().lock() else {
+        return;
+    };
+    let mut pending = notifier.on_status(status, conn_state, funds_level, ended);
+    pending.extend(notifier.pending_update());
+    for notification in pending {
+        if notifier.admit(
+            &notification,
+            settings,
+            Instant::now(),
+            local_minute_of_day(),
+        ) {
+            show(notification);
+        }
+    }
+}
+
+/// Called whenever the manifest, the channel, the dismissed version or the
+/// daemon's package version may have changed.
+pub fn check_update(app: &AppHandle, settings: &Settings) {
+    let package_version = app
+        .state::<AppStateCache>()
+        .service_info
+        .borrow()
+        .as_ref()
+        .and_then(|info| info.package_version.clone());
+    let Some(package_version) = package_version else {
+        return;
+    };
+    let available = available_update(&package_version, settings);
+    let Ok(mut notifier) = app.state::<Mutex<Notifier>>().lock() else {
+        return;
+    };
+    let Some(notification) = notifier.on_update(available.as_deref()) else {
+        return;
+    };
+    let minute_of_day = local_minute_of_day();
+    if notifier.admit(
+        &notification,
+        &settings.notifications,
+        Instant::now(),
+        minute_of_day,
+    ) {
+        show(notification);
+    }
+}
+
+fn local_minute_of_day() -> u16 {
+    let now = chrono::Local::now();
+    (now.hour() * 60 + now.minute()) as u16
+}
+
+fn show(notification: Notification) {
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = deliver(&notification.title, &notification.body).await {
+            eprintln!("notifications: cannot show '{}': {e}", notification.title);
+        }
+    });
+}
+
+#[cfg(target_os = "linux")]
+async fn deliver(title: &str, body: &str) -> Result<(), String> {
+    use zbus::zvariant::Value;
+
+    let connection = zbus::Connection::session()
+        .await
+        .map_err(|e| e.to_string())?;
+    let actions: Vec<&str> = Vec::new();
+    let hints: HashMap<&str, Value<'_>> = HashMap::new();
+    connection
+        .call_method(
+            Some("org.freedesktop.Notifications"),
+            "/org/freedesktop/Notifications",
+            Some("org.freedesktop.Notifications"),
+            "Notify",
+            // app name, replaces id, icon, summary, body, actions, hints, timeout
+            &(APP_NAME, 0u32, "", title, body, actions, hints, -1i32),
+        )
+        .await
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(target_os = "macos")]
+async fn deliver(title: &str, body: &str) -> Result<(), String> {
+    let script = format!(
+        "display notification {} with title {} subtitle {}",
+        applescript_string(body),
+        applescript_string(APP_NAME),
+        applescript_string(title)
+    );
+    let output = tokio::process::Command::new("osascript")
+        .args(["-e", &script])
+        .output()
+        .await
+        .map_err(|e| e.to_string())?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn applescript_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+async fn deliver(_title: &str, _body: &str) -> Result<(), String> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::QuietHours;
+    use std::collections::BTreeMap;
+
+    fn status(run_mode: RunMode) -> StatusResponse {
+        StatusResponse {
+            run_mode,
+            destinations: vec![],
+            target_destination: None,
+            connected: None,
+            connecting: None,
+            reconnecting: None,
+            disconnecting: vec![],
+            latency: BTreeMap::new(),
+            scores: BTreeMap::new(),
+        }
+    }
+
+    fn running() -> RunMode {
+        RunMode::Running {
+            funding_status: None,
+            hopr_status: None,
+        }
+    }
+
+    fn preparing_safe() -> RunMode {
+        RunMode::PreparingSafe {
+            node_address: "0xnode".to_string(),
+            node_xdai: "0".to_string(),
+            node_wxhopr: "0".to_string(),
+            funding_tool: None,
+            error: None,
+            balance_recommendation: None,
+        }
+    }
+
+    fn session(id: &str, end_reason: EndReason) -> Session {
+        Session {
+            destination_id: id.to_string(),
+            meta: BTreeMap::from([("location".to_string(), "EU".to_string())]),
+            started_at: 0,
+            connected_at: Some(1_000),
+            ended_at: 2_000,
+            time_to_connect_ms: Some(1_000),
+            reconnect_count: 0,
+            end_reason,
+            end_detail: None,
+        }
+    }
+
+    fn categories(notifications: &[Notification]) -> Vec<Category> {
+        notifications.iter().map(|n| n.category).collect()
+    }
+
+    #[test]
+    fn first_status_is_a_baseline() {
+        let mut notifier = Notifier::default();
+        let connected = ConnectionState::Connected("exit-1".to_string());
+        let pending = notifier.on_status(&status(running()), &connected, FundsLevel::Empty, &[]);
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn notifies_connection_transitions_but_not_reconnects() {
+        let mut notifier = Notifier::default();
+        let running = status(running());
+        let connected = ConnectionState::Connected("exit-1".to_string());
+        let reconnecting = ConnectionState::Reconnecting("exit-1".to_string());
+        let sufficient = FundsLevel::Sufficient;
+
+        notifier.on_status(&running, &ConnectionState::Disconnected, sufficient, &[]);
+        let pending = notifier.on_status(&running, &connected, sufficient, &[]);
+        assert_eq!(categories(&pending), [Category::Connected]);
+        assert_eq!(pending[0].body, "Traffic now exits via exit-1.");
+
+        notifier.on_status(&running, &reconnecting, sufficient, &[]);
+        assert!(
+            notifier
+                .on_status(&running, &connected, sufficient, &[])
+                .is_empty()
+        );
+
+        let dropped = [session("exit-1", EndReason::Dropped)];
+        let pending = notifier.on_status(
+            &running,
+            &ConnectionState::Disconnected,
+            sufficient,
+            &dropped,
+        );
+        assert_eq!(categories(&pending), [Category::Disconnected]);
+        assert_eq!(pending[0].body, "The connection to exit-1 (EU) dropped.");
+
+        let by_user = [session("exit-1", EndReason::UserDisconnect)];
+        notifier.on_status(&running, &connected, sufficient, &[]);
+        let pending = notifier.on_status(
+            &running,
+            &ConnectionState::Disconnected,
+            sufficient,
+            &by_user,
+        );
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn notifies_falling_funds_and_safe_funding() {
+        let mut notifier = Notifier::default();
+        let disconnected = ConnectionState::Disconnected;
+
+        notifier.on_status(
+            &status(preparing_safe()),
+            &disconnected,
+            FundsLevel::Sufficient,
+            &[],
+        );
+        let deploying = status(RunMode::DeployingSafe {
+            node_address: "0xnode".to_string(),
+        });
+        let pending = notifier.on_status(&deploying, &disconnected, FundsLevel::Sufficient, &[]);
+        assert_eq!(categories(&pending), [Category::SafeFunded]);
+
+        let running = status(running());
+        let pending = notifier.on_status(&running, &disconnected, FundsLevel::Low, &[]);
+        assert_eq!(categories(&pending), [Category::FundsLow]);
+        assert_eq!(pending[0].title, "Funds running low");
+        let pending = notifier.on_status(&running, &disconnected, FundsLevel::Empty, &[]);
+        assert_eq!(pending[0].title, "Out of funds");
+        // rising funds are not news
+        assert!(
+            notifier
+                .on_status(&running, &disconnected, FundsLevel::Low, &[])
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn admit_applies_toggles_quiet_hours_and_dedup() {
+        let mut notifier = Notifier::default();
+        let notification = Notification {
+            category: Category::Connected,
+            subject: "exit-1".to_string(),
+            title: "Connected".to_string(),
+            body: String::new(),
+        };
+        let start = Instant::now();
+        let noon = 12 * 60;
+
+        let off = NotificationSettings {
+            connected: false,
+            ..NotificationSettings::default()
+        };
+        assert!(!notifier.admit(&notification, &off, start, noon));
+
+        let night = NotificationSettings {
+            quiet_hours: Some(QuietHours {
+                start: "22:00".to_string(),
+                end: "07:00".to_string(),
+            }),
+            ..NotificationSettings::default()
+        };
+        assert!(!notifier.admit(&notification, &night, start, 23 * 60));
+
+        assert!(notifier.admit(&notification, &night, start, noon));
+        // flapping within the window stays quiet
+        assert!(!notifier.admit(&notification, &night, start + Duration::from_secs(60), noon));
+        let other_exit = Notification {
+            subject: "exit-2".to_string(),
+            ..notification.clone()
+        };
+        assert!(notifier.admit(&other_exit, &night, start + Duration::from_secs(60), noon));
+        assert!(notifier.admit(&notification, &night, start + DEDUP_WINDOW, noon));
+    }
+
+    #[test]
+    fn update_follows_the_frontend_evaluation() {
+        let manifest = serde_json::from_value(serde_json::json!({
+            "schema_version": 1,
+            "generated_at": "2026-07-06T00:00:00Z",
+            "channels": {
+                "stable": {
+                    "version": "0.29.0",
+                    "published_at": "2026-07-01T12:00:00Z",
+                    "download_url": "https://download.gnosisvpn.io/app/0.29.0.AppImage",
+                    "size_bytes": 123456789,
+                    "sha256": "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+                    "artifact_signature": "sig",
+                    "release_notes": "notes",
+                    "min_os_version": "10.15",
+                    "min_app_version": "0.28.0"
+                },
+                "snapshot": null
+            }
+        }))
+        .expect("valid manifest");
+        let mut settings = Settings {
+            update_manifest: Some(manifest),
+            ..Settings::default()
+        };
+
+        assert_eq!(
+            available_update("0.28.5", &settings),
+            Some("0.29.0".to_string())
+        );
+        assert_eq!(available_update("0.29.0", &settings), None);
+        // a snapshot build switching to stable is offered stable
+        settings.channel = Some(UpdateChannel::Stable);
+        assert_eq!(
+            available_update("0.30.0+build.4", &settings),
+            Some("0.29.0".to_string())
+        );
+        // no snapshot release in the manifest
+        settings.channel = None;
+        assert_eq!(available_update("0.28.5+build.1", &settings), None);
+
+        settings.dismissed_update_version = Some("0.29.0".to_string());
+        assert_eq!(available_update("0.28.5", &settings), None);
+    }
+
+    #[test]
+    fn update_suppressed_by_quiet_hours_is_announced_later() {
+        let mut notifier = Notifier::default();
+        let night = NotificationSettings {
+            quiet_hours: Some(QuietHours {
+                start: "22:00".to_string(),
+                end: "07:00".to_string(),
+            }),
+            ..NotificationSettings::default()
+        };
+        let start = Instant::now();
+
+        let update = notifier.on_update(Some("0.29.0")).expect("new version");
+        assert!(!notifier.admit(&update, &night, start, 23 * 60));
+        // still news on the next check and on the next status tick
+        assert_eq!(notifier.on_update(Some("0.29.0")), Some(update.clone()));
+        assert_eq!(notifier.pending_update(), Some(update.clone()));
+
+        assert!(notifier.admit(&update, &night, start, 12 * 60));
+        assert_eq!(notifier.pending_update(), None);
+        assert!(notifier.on_update(Some("0.29.0")).is_none());
+        assert!(notifier.on_update(None).is_none());
+    }
+
+    #[test]
+    fn orders_versions_like_the_frontend() {
+        assert!(is_older("0.28.5", "0.29.0"));
+        assert!(!is_older("0.29.0", "0.29.0"));
+        assert!(is_older("0.29.0+build.3", "0.29.0+build.12"));
+        assert!(!is_older("0.29.0+build.1", "0.29.0"));
+        assert!(!is_older("0.29.0-rc.1", "0.29.0"));
+        assert!(is_older(
+            "2026.08.20+build.013814",
+            "2026.08.21+build.000102"
+        ));
+        assert!(!is_older("not a version", "0.29.0"));
+    }
+}
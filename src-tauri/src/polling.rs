@@ -0,0 +1,420 @@
+//! Daemon round trips and per-tick decisions of the status/balance loops.
+//!
+//! Kept free of `AppHandle` so the loops' logic — reinit detection, startup
+//! auto-connect, icon choice — runs against any [`DaemonClient`], including
+//! the scripted fake socket in `tests/daemon_polling.rs`. Each tick publishes
+//! its result through a [`PollSink`]; `commands.rs` owns the loops themselves
+//! and applies the results to tray, dock and auto-connect.
+
+use gnosis_vpn_lib::command;
+use gnosis_vpn_lib::route_health::RouteHealthState;
+
+use tokio::time;
+
+use std::collections::BTreeMap;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+use crate::AppStateCache;
+use crate::commands::{COMPATIBLE_VERSIONS, is_version_compatible};
+use crate::daemon::DaemonClient;
+use crate::error::{AppError, ErrorCode};
+use crate::failover::{Failover, FailoverMonitor};
+use crate::icons::{self, FundsLevel};
+use crate::latency::{LatencyHistory, LatencyStats};
+use crate::score::{self, ExitScore};
+use crate::settings::{ExitPreference, Settings, SortOrder};
+use crate::types::{BalanceResponse, ConnectionState, RunMode, StatusResponse};
+
+/// Keep-alive passed to `StartClient`; the worker shuts down if the app stops polling.
+pub const CLIENT_KEEP_ALIVE: Duration = Duration::from_secs(10);
+
+pub async fn query_info(client: &impl DaemonClient) -> Result<command::InfoResponse, AppError> {
+    match client.process_cmd(&command::Command::Info).await? {
+        command::Response::Info(info) => Ok(info),
+        other => Err(AppError::unexpected(&other)),
+    }
+}
+
+pub async fn start_client_worker(
+    client: &impl DaemonClient,
+    keep_alive: Duration,
+) -> Result<(), AppError> {
+    match client
+        .process_cmd(&command::Command::StartClient(keep_alive))
+        .await?
+    {
+        command::Response::StartClient(_) => Ok(()),
+        other => Err(AppError::unexpected(&other)),
+    }
+}
+
+pub async fn stop_client(client: &impl DaemonClient) -> Result<(), AppError> {
+    match client.process_cmd(&command::Command::StopClient).await? {
+        command::Response::StopClient(_resp) => Ok(()),
+        other => Err(AppError::unexpected(&other)),
+    }
+}
+
+/// Exponential backoff with jitter for loops talking to an unresponsive
+/// daemon, so a hung or restarting service isn't hammered every tick.
+#[derive(Debug)]
+pub struct Backoff {
+    base: Duration,
+    max: Duration,
+    failures: u32,
+}
+
+impl Backoff {
+    pub const fn new(base: Duration, max: Duration) -> Self {
+        Self {
+            base,
+            max,
+            failures: 0,
+        }
+    }
+
+    /// Delay before the next attempt after another failure: base · 2^n,
+    /// capped, then scaled by a random factor in [0.8, 1.2).
+    pub fn next_delay(&mut self) -> Duration {
+        let exp = self.failures.min(16);
+        self.failures = self.failures.saturating_add(1);
+        let delay = self.base.saturating_mul(1 << exp).min(self.max);
+        delay.mul_f64(0.8 + 0.4 * unit_random())
+    }
+
+    pub fn reset(&mut self) {
+        self.failures = 0;
+    }
+
+    pub fn failures(&self) -> u32 {
+        self.failures
+    }
+}
+
+/// Backoff used by the status, balance and init loops while the daemon
+/// is unreachable or timing out.
+pub fn unresponsive_backoff() -> Backoff {
+    Backoff::new(Duration::from_secs(2), Duration::from_secs(30))
+}
+
+// Jitter only needs to de-synchronize retries, not be unpredictable; the
+// std hasher's per-instance random keys are enough and avoid a rand dependency.
+fn unit_random() -> f64 {
+    let bits = RandomState::new().build_hasher().finish();
+    (bits >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// One pass of the initialization loop: the daemon must answer, run a
+/// compatible version and accept the client worker before polling starts.
+/// Errors are user-facing and emitted as `status` errors.
+pub async fn init_service(client: &impl DaemonClient) -> Result<command::InfoResponse, AppError> {
+    let info = query_info(client)
+        .await
+        .map_err(|e| AppError::new(e.code, "Failed to get service info").with_details(e))?;
+
+    if !is_version_compatible(&info.version) {
+        let supported = COMPATIBLE_VERSIONS.join(", ");
+        return Err(AppError::new(
+            ErrorCode::IncompatibleService,
+            format!(
+                "Incompatible service version: {}. Supported versions: {supported}. \
+                 If you just updated, please restart the app.",
+                info.version
+            ),
+        ));
+    }
+
+    start_client_worker(client, CLIENT_KEEP_ALIVE)
+        .await
+        .map_err(|e| {
+            AppError::new(
+                ErrorCode::WorkerStartFailed,
+                "Failed to start client worker",
+            )
+            .with_details(e)
+        })?;
+
+    Ok(info)
+}
+
+pub async fn query_balance(
+    client: &impl DaemonClient,
+) -> (Duration, Result<Option<BalanceResponse>, AppError>) {
+    let resp = client.process_cmd(&command::Command::Balance).await;
+    match resp {
+        Ok(command::Response::Balance(Ok(balance_resp))) => {
+            (Duration::from_secs(60), Ok(Some(balance_resp.into())))
+        }
+        Ok(command::Response::Balance(Err(_))) => (Duration::from_secs(5), Ok(None)),
+        Ok(command::Response::WorkerOffline) => (Duration::from_secs(5), Ok(None)),
+        Ok(unexpected) => (
+            Duration::from_secs(5),
+            Err(AppError::unexpected(&unexpected)),
+        ),
+        Err(e) => (Duration::from_secs(5), Err(e)),
+    }
+}
+
+/// Returns (needs_reinit, delay until the next tick) for a status.
+pub fn status_delay(status: &StatusResponse) -> (bool, Duration) {
+    if matches!(status.run_mode, RunMode::NotRunning) {
+        return (true, Duration::from_secs(5));
+    }
+    let is_in_transition = status.connecting.is_some() || status.reconnecting.is_some();
+    if is_in_transition {
+        (false, Duration::from_millis(222))
+    } else {
+        (false, Duration::from_secs(2))
+    }
+}
+
+/// Returns (needs_reinit, delay until the next tick, `status` event payload).
+pub async fn query_status(
+    client: &impl DaemonClient,
+) -> (bool, Duration, Result<Option<StatusResponse>, AppError>) {
+    let resp = client.process_cmd(&command::Command::Status).await;
+    match resp {
+        Ok(command::Response::Status(status_resp)) => {
+            let resp = StatusResponse::from(status_resp);
+            let (needs_reinit, delay) = status_delay(&resp);
+            (needs_reinit, delay, Ok(Some(resp)))
+        }
+        Ok(command::Response::WorkerOffline) => {
+            // socket-level response: worker process not running
+            (true, Duration::from_secs(5), Ok(None))
+        }
+        // Internal response sent by the root process to itself; never forwarded to the app.
+        Ok(command::Response::ForceReconnectAcknowledged) => {
+            (false, Duration::from_secs(2), Ok(None))
+        }
+        Ok(unexpected) => (
+            false,
+            Duration::from_secs(2),
+            Err(AppError::unexpected(&unexpected)),
+        ),
+        Err(e) => (false, Duration::from_secs(2), Err(e)),
+    }
+}
+
+/// Where the loops deliver each tick's result. The app updates
+/// `AppStateCache` and emits the `status`/`balance` event; the integration
+/// tests use the cache alone.
+pub trait PollSink {
+    fn publish_status(&self, result: &Result<Option<StatusResponse>, AppError>);
+    fn publish_balance(&self, result: &Result<Option<BalanceResponse>, AppError>);
+}
+
+impl PollSink for AppStateCache {
+    fn publish_status(&self, result: &Result<Option<StatusResponse>, AppError>) {
+        self.status.send_replace(Some(result.clone()));
+    }
+
+    fn publish_balance(&self, result: &Result<Option<BalanceResponse>, AppError>) {
+        self.balance.send_replace(Some(result.clone()));
+    }
+}
+
+/// One tick of the status loop: polls, adds the latency and score fields
+/// and publishes the result to `sink`. Returns (needs_reinit, delay until
+/// the next tick, the published result).
+pub async fn status_tick(
+    client: &impl DaemonClient,
+    backoff: &mut Backoff,
+    latency: &LatencyHistory,
+    sink: &impl PollSink,
+) -> (bool, Duration, Result<Option<StatusResponse>, AppError>) {
+    let (needs_reinit, delay, mut result) = query_status(client).await;
+    let delay = match &result {
+        Err(e) if e.is_unresponsive() => {
+            let delay = backoff.next_delay();
+            eprintln!("Daemon unresponsive, next status poll in {delay:?}: {e}");
+            delay
+        }
+        _ => {
+            backoff.reset();
+            delay
+        }
+    };
+    if let Ok(Some(status)) = &mut result {
+        status.latency = latency.observe(&status.destinations);
+        status.scores = score::score_all(&status.destinations, &status.latency);
+    }
+    sink.publish_status(&result);
+    (needs_reinit, delay, result)
+}
+
+/// One tick of the balance loop: polls and publishes the result to `sink`.
+/// Returns the delay until the next tick.
+pub async fn balance_tick(
+    client: &impl DaemonClient,
+    backoff: &mut Backoff,
+    sink: &impl PollSink,
+) -> Duration {
+    let (delay, result) = query_balance(client).await;
+    let delay = match &result {
+        Err(e) if e.is_unresponsive() => backoff.next_delay(),
+        _ => {
+            backoff.reset();
+            delay
+        }
+    };
+    sink.publish_balance(&result);
+    delay
+}
+
+/// What a successfully polled status means for tray, dock and auto-connect.
+#[derive(Clone, Debug)]
+pub struct StatusEffects {
+    pub conn_state: ConnectionState,
+    pub funds_level: FundsLevel,
+    pub tray_icon: &'static str,
+    pub app_icon: String,
+    /// Destination to connect to on startup; set at most once per loop.
+    pub startup_target: Option<String>,
+    /// Exit switch the failover policy asks for.
+    pub failover: Option<Failover>,
+}
+
+/// State the status loop carries from one tick to the next.
+#[derive(Debug, Default)]
+pub struct StatusTracker {
+    startup_connect_done: bool,
+    failover: FailoverMonitor,
+}
+
+impl StatusTracker {
+    /// `balance` is the balance loop's latest response: the funding level
+    /// prefers its fresher status over the one in the run mode.
+    pub fn on_status(
+        &mut self,
+        status: &StatusResponse,
+        balance: Option<&BalanceResponse>,
+        settings: &Settings,
+    ) -> StatusEffects {
+        let conn_state = ConnectionState::from(status);
+        let funds_level = icons::funds_level(&status.run_mode, balance);
+
+        // Auto-connect is decided once, on the first tick with a usable exit,
+        // so a later manual disconnect is not undone.
+        let mut startup_target = None;
+        if !self.startup_connect_done
+            && status
+                .destinations
+                .iter()
+                .any(|ds| is_positive_route_health(&ds.route_health))
+        {
+            self.startup_connect_done = true;
+            if settings.connect_on_startup {
+                startup_target = pick_startup_target(
+                    &status.destinations,
+                    &status.latency,
+                    &settings.preferred_exits,
+                    settings.exit_node_sort_order,
+                );
+            }
+        }
+
+        StatusEffects {
+            tray_icon: icons::determine_tray_icon(&conn_state, funds_level),
+            app_icon: icons::determine_app_icon(&conn_state, funds_level),
+            conn_state,
+            funds_level,
+            startup_target,
+            failover: self
+                .failover
+                .on_status(status, settings, time::Instant::now()),
+        }
+    }
+}
+
+fn is_positive_route_health(rh: &Option<command::RouteHealthView>) -> bool {
+    matches!(
+        rh.as_ref().map(|v| &v.state),
+        Some(RouteHealthState::ReadyToConnect { .. })
+    )
+}
+
+/// Smoothed latency of a `ReadyToConnect` exit; the latest ping until the
+/// exit has history.
+fn startup_latency(
+    ds: &command::DestinationState,
+    latency: &BTreeMap<String, LatencyStats>,
+) -> Option<Duration> {
+    match ds.route_health.as_ref().map(|v| &v.state)? {
+        RouteHealthState::ReadyToConnect { exit } => Some(
+            latency
+                .get(&ds.destination.id)
+                .map_or(exit.ping_rtt, LatencyStats::smoothed),
+        ),
+        _ => None,
+    }
+}
+
+/// Walks `preferences` in order, skipping exits that are not
+/// `ReadyToConnect`; when one entry (a metadata filter) matches several
+/// exits, the best wins. Falls back to the best ready exit overall.
+///
+/// "Best" follows the exit list's sort order: the highest composite score
+/// for `SortOrder::Score`, the lowest (smoothed) latency otherwise.
+pub fn pick_startup_target(
+    destinations: &[command::DestinationState],
+    latency: &BTreeMap<String, LatencyStats>,
+    preferences: &[ExitPreference],
+    order: SortOrder,
+) -> Option<String> {
+    let best = |preference: Option<&ExitPreference>| {
+        let candidates = destinations
+            .iter()
+            .filter(|ds| preference.is_none_or(|p| p.matches(&ds.destination)));
+        match order {
+            SortOrder::Score => candidates
+                .filter(|ds| is_positive_route_health(&ds.route_health))
+                .filter_map(|ds| {
+                    let id = &ds.destination.id;
+                    score::exit_score(ds, latency.get(id)).map(|score| (score, id.clone()))
+                })
+                .min_by(|(a, _), (b, _)| ExitScore::rank(a, b))
+                .map(|(_, id)| id),
+            SortOrder::Latency | SortOrder::Alpha => candidates
+                .filter_map(|ds| {
+                    startup_latency(ds, latency).map(|ms| (ms, ds.destination.id.clone()))
+                })
+                .min_by_key(|(ms, _)| *ms)
+                .map(|(_, id)| id),
+        }
+    };
+    preferences
+        .iter()
+        .find_map(|preference| best(Some(preference)))
+        .or_else(|| best(None))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_grows_exponentially_within_jitter_and_cap() {
+        let mut backoff = Backoff::new(Duration::from_secs(2), Duration::from_secs(30));
+        for expected in [2, 4, 8, 16, 30, 30] {
+            let delay = backoff.next_delay().as_secs_f64();
+            let expected = expected as f64;
+            assert!(
+                (expected * 0.8..expected * 1.2).contains(&delay),
+                "{delay} not within jitter of {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn backoff_restarts_from_base_after_reset() {
+        let mut backoff = Backoff::new(Duration::from_secs(2), Duration::from_secs(30));
+        backoff.next_delay();
+        backoff.next_delay();
+        backoff.reset();
+        assert_eq!(backoff.failures(), 0);
+        assert!(backoff.next_delay() < Duration::from_millis(2400));
+    }
+}
@@ -0,0 +1,191 @@
+//! Composite exit score: how good a ready exit is to connect to right now.
+//!
+//! Four parts, each normalized to 0..=1 (higher is better), are weighted
+//! into one score: round-trip time (smoothed when there is history), load
+//! per core, share of free slots and route-health stability (failed checks
+//! and jitter). A close exit that is busy or flaky no longer wins on RTT
+//! alone.
+
+use gnosis_vpn_lib::command::DestinationState;
+use gnosis_vpn_lib::route_health::RouteHealthState;
+use serde::Serialize;
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+use crate::latency::LatencyStats;
+
+/// RTT at which the latency part drops to one half.
+const RTT_HALF_MS: f64 = 100.0;
+/// Jitter at which the jitter half of stability drops to one half.
+const JITTER_HALF_MS: f64 = 20.0;
+
+const RTT_WEIGHT: f64 = 0.35;
+const LOAD_WEIGHT: f64 = 0.25;
+const SLOTS_WEIGHT: f64 = 0.25;
+const STABILITY_WEIGHT: f64 = 0.15;
+
+/// Sent with every `status` as `scores[destination_id]`.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ExitScore {
+    pub score: f64,
+    pub rtt: f64,
+    pub load: f64,
+    pub slots: f64,
+    pub stability: f64,
+}
+
+impl ExitScore {
+    /// Higher scores first.
+    pub fn rank(a: &ExitScore, b: &ExitScore) -> Ordering {
+        b.score.total_cmp(&a.score)
+    }
+}
+
+/// Scores a `ReadyToConnect` or `Connecting` exit; `None` for any other
+/// route health, which has no fresh health check to score.
+pub fn exit_score(ds: &DestinationState, latency: Option<&LatencyStats>) -> Option<ExitScore> {
+    let view = ds.route_health.as_ref()?;
+    let exit = match &view.state {
+        RouteHealthState::ReadyToConnect { exit } | RouteHealthState::Connecting { exit, .. } => {
+            exit
+        }
+        _ => return None,
+    };
+
+    let rtt_ms = latency.map_or(exit.ping_rtt.as_millis() as f64, |l| l.smoothed_ms as f64);
+    let rtt = half_at(rtt_ms, RTT_HALF_MS);
+
+    let load_avg = &exit.health.load_avg;
+    let per_core = f64::from(load_avg.five) / (load_avg.nproc as f64).max(1.0);
+    let load = (1.0 - per_core).clamp(0.0, 1.0);
+
+    let slots = &exit.health.slots;
+    let total = slots.available as f64 + slots.connected as f64;
+    let slots = if total > 0.0 {
+        slots.available as f64 / total
+    } else {
+        0.0
+    };
+
+    let jitter_ms = latency.map_or(0.0, |l| l.jitter_ms as f64);
+    let stability =
+        half_at(view.consecutive_failures as f64, 1.0) * half_at(jitter_ms, JITTER_HALF_MS);
+
+    Some(ExitScore {
+        score: RTT_WEIGHT * rtt
+            + LOAD_WEIGHT * load
+            + SLOTS_WEIGHT * slots
+            + STABILITY_WEIGHT * stability,
+        rtt,
+        load,
+        slots,
+        stability,
+    })
+}
+
+pub fn score_all(
+    destinations: &[DestinationState],
+    latency: &BTreeMap<String, LatencyStats>,
+) -> BTreeMap<String, ExitScore> {
+    destinations
+        .iter()
+        .filter_map(|ds| {
+            let id = &ds.destination.id;
+            exit_score(ds, latency.get(id)).map(|score| (id.clone(), score))
+        })
+        .collect()
+}
+
+/// 1 at zero, 1/2 at `half`, approaching 0 as `value` grows.
+fn half_at(value: f64, half: f64) -> f64 {
+    half / (half + value.max(0.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures;
+    use gnosis_vpn_lib::route_health::{Health, LoadAvg};
+
+    /// An idle exit with 10 free slots; `tweak` adjusts its health.
+    fn exit(rtt_ms: u64, tweak: impl FnOnce(&mut Health)) -> DestinationState {
+        let mut exit = test_fixtures::exit_health(rtt_ms);
+        exit.health.slots.connected = 0;
+        exit.health.load_avg = LoadAvg {
+            one: 0.0,
+            five: 0.0,
+            fifteen: 0.0,
+            nproc: 4,
+        };
+        tweak(&mut exit.health);
+        test_fixtures::destination_state("a", None, RouteHealthState::ReadyToConnect { exit })
+    }
+
+    #[test]
+    fn idle_exit_scores_one() {
+        let score = exit_score(&exit(0, |_| {}), None).unwrap();
+        assert!((score.score - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn busy_close_exit_loses_to_idle_farther_one() {
+        let busy = exit_score(
+            &exit(15, |h| {
+                h.slots.available = 1;
+                h.slots.connected = 19;
+                h.load_avg.five = 3.8;
+            }),
+            None,
+        )
+        .unwrap();
+        let idle = exit_score(
+            &exit(90, |h| {
+                h.slots.available = 18;
+                h.slots.connected = 2;
+                h.load_avg.five = 0.4;
+            }),
+            None,
+        )
+        .unwrap();
+        assert!(busy.rtt > idle.rtt);
+        assert_eq!(ExitScore::rank(&idle, &busy), Ordering::Less);
+    }
+
+    #[test]
+    fn full_or_overloaded_exit_gets_nothing_for_those_parts() {
+        let ds = exit(50, |h| {
+            h.slots.available = 0;
+            h.slots.connected = 20;
+            h.load_avg.five = 9.0;
+        });
+        let score = exit_score(&ds, None).unwrap();
+        assert_eq!((score.slots, score.load), (0.0, 0.0));
+    }
+
+    #[test]
+    fn failures_and_jitter_reduce_stability() {
+        let mut flaky = exit(50, |_| {});
+        flaky.route_health.as_mut().unwrap().consecutive_failures = 1;
+        let jittery = LatencyStats {
+            samples: 10,
+            last_ms: 50,
+            smoothed_ms: 50,
+            jitter_ms: 20,
+        };
+        assert_eq!(exit_score(&flaky, None).unwrap().stability, 0.5);
+        assert_eq!(
+            exit_score(&exit(50, |_| {}), Some(&jittery))
+                .unwrap()
+                .stability,
+            0.5
+        );
+    }
+
+    #[test]
+    fn unready_exit_has_no_score() {
+        let mut ds = exit(10, |_| {});
+        ds.route_health.as_mut().unwrap().state = RouteHealthState::Routable;
+        assert_eq!(exit_score(&ds, None), None);
+    }
+}
@@ -0,0 +1,611 @@
+//! Connection session history, persisted as `app_data_dir/sessions.json`.
+//!
+//! A session starts when the status loop first sees `Connecting` (or a
+//! connection already up at launch) and ends at `Disconnected`, a switch to
+//! another exit, the service stopping or the app exiting. Attempts that
+//! never reach `Connected` are kept too, ended as `ConnectFailed`.
+
+use chrono::{DateTime, SecondsFormat};
+use gnosis_vpn_lib::command::DestinationState;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, State};
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::error::{AppError, ErrorCode};
+use crate::types::{ConnectionState, RunMode, StatusResponse};
+
+/// Oldest sessions are dropped beyond this.
+const MAX_SESSIONS: usize = 1000;
+
+pub const SESSION_RECORDED_EVENT: &str = "session-recorded";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EndReason {
+    /// Ended by the user's own disconnect.
+    UserDisconnect,
+    /// Never reached `Connected`.
+    ConnectFailed,
+    /// Was connected and dropped without a disconnect request.
+    Dropped,
+    /// Replaced by a connection to another exit.
+    Switched,
+    /// Switched away by the failover policy; `end_detail` says why.
+    FailedOver,
+    /// The daemon stopped running.
+    ServiceStopped,
+    AppExit,
+}
+
+impl EndReason {
+    fn as_str(self) -> &'static str {
+        match self {
+            EndReason::UserDisconnect => "UserDisconnect",
+            EndReason::ConnectFailed => "ConnectFailed",
+            EndReason::Dropped => "Dropped",
+            EndReason::Switched => "Switched",
+            EndReason::FailedOver => "FailedOver",
+            EndReason::ServiceStopped => "ServiceStopped",
+            EndReason::AppExit => "AppExit",
+        }
+    }
+}
+
+/// Timestamps are Unix milliseconds, like `Settings::last_checked_at`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Session {
+    pub destination_id: String,
+    pub meta: BTreeMap<String, String>,
+    pub started_at: i64,
+    pub connected_at: Option<i64>,
+    pub ended_at: i64,
+    /// Only known when the session was seen from its first `Connecting`.
+    pub time_to_connect_ms: Option<u64>,
+    pub reconnect_count: u32,
+    pub end_reason: EndReason,
+    #[serde(default)]
+    pub end_detail: Option<String>,
+}
+
+#[derive(Debug)]
+struct ActiveSession {
+    destination_id: String,
+    meta: BTreeMap<String, String>,
+    started_at: i64,
+    connected_at: Option<i64>,
+    seen_connecting: bool,
+    reconnect_count: u32,
+    reconnecting: bool,
+}
+
+impl ActiveSession {
+    fn start(id: &str, destinations: &[DestinationState], now: i64) -> Self {
+        let meta = destinations
+            .iter()
+            .find(|ds| ds.destination.id == id)
+            .map(|ds| {
+                ds.destination
+                    .meta
+                    .iter()
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self {
+            destination_id: id.to_string(),
+            meta,
+            started_at: now,
+            connected_at: None,
+            seen_connecting: false,
+            reconnect_count: 0,
+            reconnecting: false,
+        }
+    }
+
+    fn finish(self, reason: EndReason, detail: Option<String>, now: i64) -> Session {
+        let time_to_connect_ms = match (self.seen_connecting, self.connected_at) {
+            (true, Some(connected_at)) => u64::try_from(connected_at - self.started_at).ok(),
+            _ => None,
+        };
+        Session {
+            destination_id: self.destination_id,
+            meta: self.meta,
+            started_at: self.started_at,
+            connected_at: self.connected_at,
+            ended_at: now,
+            time_to_connect_ms,
+            reconnect_count: self.reconnect_count,
+            end_reason: reason,
+            end_detail: detail,
+        }
+    }
+}
+
+/// Turns the status loop's observations into finished sessions.
+#[derive(Debug, Default)]
+pub struct SessionRecorder {
+    active: Option<ActiveSession>,
+    user_disconnect_requested: bool,
+    failover_reason: Option<String>,
+}
+
+impl SessionRecorder {
+    /// Feeds one status; returns the sessions it ended (a switch ends one
+    /// and starts another in the same tick).
+    pub fn observe(&mut self, status: &StatusResponse, now: i64) -> Vec<Session> {
+        let mut finished = Vec::new();
+        if matches!(status.run_mode, RunMode::NotRunning) {
+            finished.extend(self.end(EndReason::ServiceStopped, now));
+            return finished;
+        }
+
+        let (id, phase) = match ConnectionState::from(status) {
+            ConnectionState::Connecting(id) => (id, Phase::Connecting),
+            ConnectionState::Connected(id) => (id, Phase::Connected),
+            ConnectionState::Reconnecting(id) => (id, Phase::Reconnecting),
+            // the session ends once the tunnel is actually down
+            ConnectionState::Disconnecting => return finished,
+            ConnectionState::Disconnected => {
+                let reason = match &self.active {
+                    _ if self.user_disconnect_requested => EndReason::UserDisconnect,
+                    Some(active) if active.connected_at.is_none() => EndReason::ConnectFailed,
+                    _ => EndReason::Dropped,
+                };
+                finished.extend(self.end(reason, now));
+                return finished;
+            }
+        };
+
+        if self
+            .active
+            .as_ref()
+            .is_some_and(|active| active.destination_id != id)
+        {
+            let reason = match self.failover_reason {
+                Some(_) => EndReason::FailedOver,
+                None => EndReason::Switched,
+            };
+            finished.extend(self.end(reason, now));
+        }
+        let active = self.active.get_or_insert_with(|| {
+            let mut session = ActiveSession::start(&id, &status.destinations, now);
+            session.seen_connecting = phase == Phase::Connecting;
+            session
+        });
+        match phase {
+            Phase::Connecting => {}
+            Phase::Connected => {
+                active.connected_at.get_or_insert(now);
+                active.reconnecting = false;
+            }
+            Phase::Reconnecting => {
+                if !active.reconnecting {
+                    active.reconnecting = true;
+                    active.reconnect_count += 1;
+                }
+            }
+        }
+        finished
+    }
+
+    /// The next `Disconnected` is the user's doing, not a drop.
+    pub fn mark_user_disconnect(&mut self) {
+        if self.active.is_some() {
+            self.user_disconnect_requested = true;
+        }
+    }
+
+    /// The user's disconnect failed; the session keeps running.
+    pub fn clear_user_disconnect(&mut self) {
+        self.user_disconnect_requested = false;
+    }
+
+    /// The next switch of exit is the failover policy's, for `reason`.
+    pub fn mark_failover(&mut self, reason: String) {
+        if self.active.is_some() {
+            self.failover_reason = Some(reason);
+        }
+    }
+
+    pub fn end(&mut self, reason: EndReason, now: i64) -> Option<Session> {
+        self.user_disconnect_requested = false;
+        let detail = self.failover_reason.take();
+        let detail = (reason == EndReason::FailedOver)
+            .then_some(detail)
+            .flatten();
+        self.active
+            .take()
+            .map(|active| active.finish(reason, detail, now))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Phase {
+    Connecting,
+    Connected,
+    Reconnecting,
+}
+
+struct Inner {
+    sessions: Vec<Session>,
+    recorder: SessionRecorder,
+}
+
+/// Managed state: recorded sessions plus the recorder for the current one.
+pub struct SessionHistory {
+    inner: Mutex<Inner>,
+    path: PathBuf,
+}
+
+impl SessionHistory {
+    pub fn load(path: PathBuf) -> Self {
+        let sessions = match std::fs::read(&path) {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => {
+                eprintln!("sessions: cannot read {}: {e}", path.display());
+                Vec::new()
+            }
+            Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_else(|e| {
+                eprintln!("sessions: cannot parse {}: {e}", path.display());
+                Vec::new()
+            }),
+        };
+        Self {
+            inner: Mutex::new(Inner {
+                sessions,
+                recorder: SessionRecorder::default(),
+            }),
+            path,
+        }
+    }
+
+    pub fn sessions(&self) -> Vec<Session> {
+        self.lock().sessions.clone()
+    }
+
+    /// Records whatever `status` ended; returns the sessions added.
+    pub fn observe(&self, status: &StatusResponse) -> Vec<Session> {
+        let mut inner = self.lock();
+        let finished = inner.recorder.observe(status, now_ms());
+        if !finished.is_empty() {
+            self.push(&mut inner, finished.clone());
+        }
+        finished
+    }
+
+    pub fn mark_user_disconnect(&self) {
+        self.lock().recorder.mark_user_disconnect();
+    }
+
+    pub fn clear_user_disconnect(&self) {
+        self.lock().recorder.clear_user_disconnect();
+    }
+
+    pub fn mark_failover(&self, reason: String) {
+        self.lock().recorder.mark_failover(reason);
+    }
+
+    pub fn end_active(&self, reason: EndReason) -> Option<Session> {
+        let mut inner = self.lock();
+        let session = inner.recorder.end(reason, now_ms())?;
+        self.push(&mut inner, vec![session.clone()]);
+        Some(session)
+    }
+
+    fn push(&self, inner: &mut Inner, finished: Vec<Session>) {
+        inner.sessions.extend(finished);
+        let excess = inner.sessions.len().saturating_sub(MAX_SESSIONS);
+        inner.sessions.drain(..excess);
+        if let Err(e) = persist(&self.path, &inner.sessions) {
+            eprintln!("sessions: failed to persist: {e}");
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Inner> {
+        self.inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+// write-then-rename, like settings.json
+fn persist(path: &Path, sessions: &[Session]) -> Result<(), String> {
+    let json =
+        serde_json::to_string(sessions).map_err(|e| format!("cannot serialize sessions: {e}"))?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("cannot create data dir: {e}"))?;
+    }
+    let tmp_path = path.with_extension("json.tmp");
+    std::fs::write(&tmp_path, json).map_err(|e| format!("cannot write sessions file: {e}"))?;
+    std::fs::rename(&tmp_path, path)
+        .map_err(|e| format!("cannot move sessions file into place: {e}"))
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or_default()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+const CSV_HEADER: &str = "destination_id,location,started_at,connected_at,ended_at,\
+                          time_to_connect_ms,reconnect_count,end_reason,end_detail,meta";
+
+/// One row per session; times as RFC 3339 UTC, other metadata as `key=value;…`.
+pub fn to_csv(sessions: &[Session]) -> String {
+    let mut out = String::from(CSV_HEADER);
+    out.push('\n');
+    for s in sessions {
+        let meta = s
+            .meta
+            .iter()
+            .map(|(k, v)| format!("{k}={v}"))
+            .collect::<Vec<_>>()
+            .join(";");
+        let fields = [
+            s.destination_id.clone(),
+            s.meta.get("location").cloned().unwrap_or_default(),
+            rfc3339(s.started_at),
+            s.connected_at.map(rfc3339).unwrap_or_default(),
+            rfc3339(s.ended_at),
+            s.time_to_connect_ms
+                .map(|ms| ms.to_string())
+                .unwrap_or_default(),
+            s.reconnect_count.to_string(),
+            s.end_reason.as_str().to_string(),
+            s.end_detail.clone().unwrap_or_default(),
+            meta,
+        ];
+        let row = fields.iter().map(|f| csv_field(f)).collect::<Vec<_>>();
+        out.push_str(&row.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn rfc3339(ms: i64) -> String {
+    DateTime::from_timestamp_millis(ms)
+        .map(|t| t.to_rfc3339_opts(SecondsFormat::Secs, true))
+        .unwrap_or_default()
+}
+
+/// Recorded sessions, oldest first.
+#[tauri::command]
+pub fn get_session_history(history: State<'_, SessionHistory>) -> Vec<Session> {
+    history.sessions()
+}
+
+#[tauri::command]
+pub fn export_session_history(
+    history: State<'_, SessionHistory>,
+    dest_path: String,
+    format: ExportFormat,
+) -> Result<(), AppError> {
+    let sessions = history.sessions();
+    let contents = match format {
+        ExportFormat::Csv => to_csv(&sessions),
+        ExportFormat::Json => serde_json::to_string_pretty(&sessions)
+            .map_err(|e| AppError::internal(format!("cannot serialize sessions: {e}")))?,
+    };
+    std::fs::write(&dest_path, contents).map_err(|e| {
+        AppError::new(ErrorCode::Io, "Failed to write session history").with_details(e)
+    })
+}
+
+/// Called from the status loop for every status it applies; returns the
+/// sessions `status` ended.
+pub fn record(app: &AppHandle, history: &SessionHistory, status: &StatusResponse) -> Vec<Session> {
+    let finished = history.observe(status);
+    for session in &finished {
+        let _ = app.emit(SESSION_RECORDED_EVENT, session);
+    }
+    finished
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gnosis_vpn_lib::command::{ConnectedInfo, ConnectingInfo, ReconnectingInfo};
+    use gnosis_vpn_lib::connection::UpPhase;
+
+    fn status() -> StatusResponse {
+        StatusResponse {
+            run_mode: RunMode::Running {
+                funding_status: None,
+                hopr_status: None,
+            },
+            destinations: vec![],
+            target_destination: None,
+            connected: None,
+            connecting: None,
+            reconnecting: None,
+            disconnecting: vec![],
+            latency: BTreeMap::new(),
+            scores: BTreeMap::new(),
+        }
+    }
+
+    fn connecting(id: &str) -> StatusResponse {
+        StatusResponse {
+            connecting: Some(ConnectingInfo {
+                destination_id: id.to_string(),
+                since: UNIX_EPOCH,
+                phase: UpPhase::Init,
+            }),
+            ..status()
+        }
+    }
+
+    fn connected(id: &str) -> StatusResponse {
+        StatusResponse {
+            connected: Some(ConnectedInfo {
+                destination_id: id.to_string(),
+                since: UNIX_EPOCH,
+            }),
+            ..status()
+        }
+    }
+
+    fn reconnecting(id: &str) -> StatusResponse {
+        StatusResponse {
+            reconnecting: Some(ReconnectingInfo {
+                destination_id: id.to_string(),
+                since: UNIX_EPOCH,
+                phase: UpPhase::Init,
+            }),
+            ..status()
+        }
+    }
+
+    #[test]
+    fn records_full_session_with_time_to_connect_and_reconnects() {
+        let mut rec = SessionRecorder::default();
+        assert!(rec.observe(&connecting("a"), 1_000).is_empty());
+        assert!(rec.observe(&connected("a"), 3_500).is_empty());
+        assert!(rec.observe(&reconnecting("a"), 4_000).is_empty());
+        assert!(rec.observe(&reconnecting("a"), 4_200).is_empty());
+        assert!(rec.observe(&connected("a"), 5_000).is_empty());
+        assert!(rec.observe(&reconnecting("a"), 6_000).is_empty());
+        assert!(rec.observe(&connected("a"), 7_000).is_empty());
+
+        let done = rec.observe(&status(), 9_000);
+        assert_eq!(done.len(), 1);
+        let s = &done[0];
+        assert_eq!(s.destination_id, "a");
+        assert_eq!(
+            (s.started_at, s.connected_at, s.ended_at),
+            (1_000, Some(3_500), 9_000)
+        );
+        assert_eq!(s.time_to_connect_ms, Some(2_500));
+        assert_eq!(s.reconnect_count, 2);
+        assert_eq!(s.end_reason, EndReason::Dropped);
+    }
+
+    #[test]
+    fn end_reason_reflects_how_the_session_ended() {
+        let mut rec = SessionRecorder::default();
+        rec.observe(&connecting("a"), 0);
+        let done = rec.observe(&status(), 10);
+        assert_eq!(done[0].end_reason, EndReason::ConnectFailed);
+        assert_eq!(done[0].time_to_connect_ms, None);
+
+        rec.observe(&connected("a"), 20);
+        rec.mark_user_disconnect();
+        let done = rec.observe(&status(), 30);
+        assert_eq!(done[0].end_reason, EndReason::UserDisconnect);
+        // already running at launch: connected, but no time-to-connect
+        assert_eq!(done[0].time_to_connect_ms, None);
+
+        rec.observe(&connected("a"), 40);
+        let done = rec.observe(&connecting("b"), 50);
+        assert_eq!(done[0].end_reason, EndReason::Switched);
+        assert_eq!(done[0].destination_id, "a");
+
+        let stopped = StatusResponse {
+            run_mode: RunMode::NotRunning,
+            ..status()
+        };
+        let done = rec.observe(&stopped, 60);
+        assert_eq!(done[0].destination_id, "b");
+        assert_eq!(done[0].end_reason, EndReason::ServiceStopped);
+    }
+
+    #[test]
+    fn failover_switch_records_its_reason() {
+        let mut rec = SessionRecorder::default();
+        rec.observe(&connected("a"), 0);
+        rec.mark_failover("3 failed health checks for 60s".to_string());
+        let done = rec.observe(&connecting("b"), 10);
+        assert_eq!(done[0].end_reason, EndReason::FailedOver);
+        assert_eq!(
+            done[0].end_detail.as_deref(),
+            Some("3 failed health checks for 60s")
+        );
+
+        // only the switch it was marked for
+        rec.observe(&connected("b"), 20);
+        let done = rec.observe(&connecting("c"), 30);
+        assert_eq!(done[0].end_reason, EndReason::Switched);
+        assert_eq!(done[0].end_detail, None);
+    }
+
+    #[test]
+    fn user_disconnect_flag_does_not_leak_into_next_session() {
+        let mut rec = SessionRecorder::default();
+        rec.mark_user_disconnect(); // nothing active: ignored
+        rec.observe(&connected("a"), 0);
+        let done = rec.observe(&status(), 10);
+        assert_eq!(done[0].end_reason, EndReason::Dropped);
+
+        rec.observe(&connected("a"), 20);
+        rec.mark_user_disconnect();
+        rec.clear_user_disconnect(); // the disconnect failed
+        let done = rec.observe(&status(), 30);
+        assert_eq!(done[0].end_reason, EndReason::Dropped);
+    }
+
+    #[test]
+    fn csv_quotes_fields_and_formats_times() {
+        let session = Session {
+            destination_id: "exit,1".to_string(),
+            meta: BTreeMap::from([
+                ("location".to_string(), "EU".to_string()),
+                ("flag".to_string(), "de".to_string()),
+            ]),
+            started_at: 1_700_000_000_000,
+            connected_at: Some(1_700_000_002_000),
+            ended_at: 1_700_000_060_000,
+            time_to_connect_ms: Some(2_000),
+            reconnect_count: 1,
+            end_reason: EndReason::FailedOver,
+            end_detail: Some("3 failed health checks, \"timeout\"".to_string()),
+        };
+        let csv = to_csv(&[session]);
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some(CSV_HEADER));
+        assert_eq!(
+            lines.next(),
+            Some(
+                "\"exit,1\",EU,2023-11-14T22:13:20Z,2023-11-14T22:13:22Z,\
+                 2023-11-14T22:14:20Z,2000,1,FailedOver,\"3 failed health checks, \"\"timeout\"\"\",\
+                 flag=de;location=EU"
+            )
+        );
+    }
+
+    #[test]
+    fn history_persists_and_reloads() {
+        let path = std::env::temp_dir()
+            .join(format!("gnosis_vpn_sessions_{}", std::process::id()))
+            .join("sessions.json");
+        let history = SessionHistory::load(path.clone());
+        history.observe(&connecting("a"));
+        history.observe(&connected("a"));
+        assert!(history.end_active(EndReason::AppExit).is_some());
+
+        let reloaded = SessionHistory::load(path.clone());
+        let sessions = reloaded.sessions();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].end_reason, EndReason::AppExit);
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+}
@@ -1,21 +1,41 @@
 use gnosis_vpn_lib::check_update::Manifest;
+use gnosis_vpn_lib::connection::destination::Destination;
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use tauri::{AppHandle, Emitter, State};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::watch;
 
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+use crate::daemon::DaemonSocket;
+use crate::error::{AppError, ErrorCode};
 
 /// App settings owned by the Rust layer and mirrored by the webviews.
 /// Persisted as a flat JSON object in `app_data_dir/settings.json` —
-/// the same path and format previously written via tauri-plugin-store.
+/// the same path and format previously written via tauri-plugin-store,
+/// plus a `schemaVersion` key (see `SCHEMA_VERSION`).
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase", default)]
 pub struct Settings {
-    pub preferred_location: Option<String>,
+    /// Ordered exit preferences for automatic selection; the first entry,
+    /// if it names an id, is also the exit failover never leaves.
+    pub preferred_exits: Vec<ExitPreference>,
     pub connect_on_startup: bool,
+    /// Reconnect to the last exit after a connection drops without the
+    /// user disconnecting.
+    pub always_on: bool,
+    /// Switch exits once the connected one has been unhealthy for
+    /// `failover_after_secs` (see `failover`).
+    pub exit_failover: bool,
+    pub failover_after_secs: u64,
     pub start_minimized: bool,
+    /// Mirrors the OS autostart entry; `autostart` reconciles the two at
+    /// startup and whenever the UI asks for the real state.
+    pub launch_at_login: bool,
     pub update_check: bool,
     pub exit_node_sort_order: SortOrder,
     pub last_checked_at: Option<i64>,
@@ -25,14 +45,31 @@ pub struct Settings {
     pub installed_version: Option<String>,
     pub show_detailed_metrics: bool,
     pub flag_display: FlagDisplay,
+    /// Root daemon socket; `None` uses the library default. Ignored while
+    /// the flag or env var pins the socket (see `daemon`).
+    pub daemon_socket_path: Option<String>,
+    /// Serve OpenMetrics on `127.0.0.1:metrics_port` (see `metrics`).
+    pub metrics_endpoint: bool,
+    pub metrics_port: u16,
+    /// Desktop notification categories and quiet hours (see `notifications`).
+    pub notifications: NotificationSettings,
+    /// Fields of the tray tooltip, in this order.
+    pub tray_tooltip_fields: Vec<TrayField>,
+    /// Fields of the compact label next to the Linux indicator icon
+    /// (`EU · 42ms`); empty shows no label.
+    pub tray_title_fields: Vec<TrayField>,
 }
 
 impl Default for Settings {
     fn default() -> Self {
         Self {
-            preferred_location: None,
+            preferred_exits: Vec::new(),
             connect_on_startup: false,
+            always_on: false,
+            exit_failover: false,
+            failover_after_secs: 60,
             start_minimized: false,
+            launch_at_login: false,
             update_check: true,
             exit_node_sort_order: SortOrder::default(),
             last_checked_at: None,
@@ -42,10 +79,77 @@ impl Default for Settings {
             installed_version: None,
             show_detailed_metrics: false,
             flag_display: FlagDisplay::default(),
+            daemon_socket_path: None,
+            metrics_endpoint: false,
+            metrics_port: 9687,
+            notifications: NotificationSettings::default(),
+            tray_tooltip_fields: vec![
+                TrayField::Destination,
+                TrayField::Location,
+                TrayField::ConnectedSince,
+                TrayField::Latency,
+                TrayField::FundsLevel,
+            ],
+            tray_title_fields: Vec::new(),
+        }
+    }
+}
+
+/// One toggle per notification category, all on by default.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NotificationSettings {
+    pub connected: bool,
+    /// A connection dropped without the user disconnecting.
+    pub disconnected: bool,
+    pub funds_low: bool,
+    /// Funds arrived while the node was waiting in `PreparingSafe`.
+    pub safe_funded: bool,
+    pub update_available: bool,
+    pub quiet_hours: Option<QuietHours>,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            connected: true,
+            disconnected: true,
+            funds_low: true,
+            safe_funded: true,
+            update_available: true,
+            quiet_hours: None,
         }
     }
 }
 
+/// Local-time window (`"HH:MM"`, end exclusive) in which notifications
+/// are dropped; `start` after `end` spans midnight.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuietHours {
+    pub start: String,
+    pub end: String,
+}
+
+impl QuietHours {
+    /// False when either bound does not parse, or both are equal.
+    pub fn contains(&self, minute_of_day: u16) -> bool {
+        let (Some(start), Some(end)) = (parse_hhmm(&self.start), parse_hhmm(&self.end)) else {
+            return false;
+        };
+        if start <= end {
+            (start..end).contains(&minute_of_day)
+        } else {
+            minute_of_day >= start || minute_of_day < end
+        }
+    }
+}
+
+fn parse_hhmm(value: &str) -> Option<u16> {
+    let (hours, minutes) = value.split_once(':')?;
+    let (hours, minutes) = (hours.parse::<u16>().ok()?, minutes.parse::<u16>().ok()?);
+    (hours < 24 && minutes < 60).then_some(hours * 60 + minutes)
+}
+
 // All enums serialize as plain strings (not tagged objects) to stay
 // wire-compatible with the persisted settings.json and the TS union types.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
@@ -54,6 +158,8 @@ pub enum SortOrder {
     #[default]
     Latency,
     Alpha,
+    /// Composite of latency, load, free slots and stability (`score.rs`).
+    Score,
 }
 
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
@@ -65,6 +171,45 @@ pub enum FlagDisplay {
     Color,
 }
 
+/// What the tray tooltip and indicator label can show (see `tray`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TrayField {
+    Destination,
+    Location,
+    ConnectedSince,
+    Latency,
+    FundsLevel,
+}
+
+/// `{"id": "exit-1"}` names one exit; `{"meta": {"location": "EU"}}`
+/// matches every exit whose metadata has all the given values.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExitPreference {
+    Id(String),
+    Meta(BTreeMap<String, String>),
+}
+
+impl ExitPreference {
+    pub fn matches(&self, destination: &Destination) -> bool {
+        match self {
+            ExitPreference::Id(id) => destination.id == *id,
+            ExitPreference::Meta(filter) => filter
+                .iter()
+                .all(|(key, value)| destination.meta.get(key) == Some(value)),
+        }
+    }
+}
+
+/// The exit failover must not switch away from: a leading id preference.
+pub fn pinned_exit(preferences: &[ExitPreference]) -> Option<&str> {
+    match preferences.first()? {
+        ExitPreference::Id(id) => Some(id),
+        ExitPreference::Meta(_) => None,
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum UpdateChannel {
@@ -77,13 +222,21 @@ pub enum UpdateChannel {
 #[derive(Debug, Default, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SettingsPatch {
-    #[serde(default, deserialize_with = "double_option")]
-    pub preferred_location: Option<Option<String>>,
+    #[serde(default)]
+    pub preferred_exits: Option<Vec<ExitPreference>>,
     #[serde(default)]
     pub connect_on_startup: Option<bool>,
     #[serde(default)]
+    pub always_on: Option<bool>,
+    #[serde(default)]
+    pub exit_failover: Option<bool>,
+    #[serde(default)]
+    pub failover_after_secs: Option<u64>,
+    #[serde(default)]
     pub start_minimized: Option<bool>,
     #[serde(default)]
+    pub launch_at_login: Option<bool>,
+    #[serde(default)]
     pub update_check: Option<bool>,
     #[serde(default)]
     pub exit_node_sort_order: Option<SortOrder>,
@@ -101,6 +254,18 @@ pub struct SettingsPatch {
     pub show_detailed_metrics: Option<bool>,
     #[serde(default)]
     pub flag_display: Option<FlagDisplay>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub daemon_socket_path: Option<Option<String>>,
+    #[serde(default)]
+    pub metrics_endpoint: Option<bool>,
+    #[serde(default)]
+    pub metrics_port: Option<u16>,
+    #[serde(default)]
+    pub notifications: Option<NotificationSettings>,
+    #[serde(default)]
+    pub tray_tooltip_fields: Option<Vec<TrayField>>,
+    #[serde(default)]
+    pub tray_title_fields: Option<Vec<TrayField>>,
 }
 
 fn double_option<'de, T, D>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
@@ -113,15 +278,27 @@ where
 
 impl Settings {
     fn apply(&mut self, patch: SettingsPatch) {
-        if let Some(v) = patch.preferred_location {
-            self.preferred_location = v;
+        if let Some(v) = patch.preferred_exits {
+            self.preferred_exits = v;
         }
         if let Some(v) = patch.connect_on_startup {
             self.connect_on_startup = v;
         }
+        if let Some(v) = patch.always_on {
+            self.always_on = v;
+        }
+        if let Some(v) = patch.exit_failover {
+            self.exit_failover = v;
+        }
+        if let Some(v) = patch.failover_after_secs {
+            self.failover_after_secs = v;
+        }
         if let Some(v) = patch.start_minimized {
             self.start_minimized = v;
         }
+        if let Some(v) = patch.launch_at_login {
+            self.launch_at_login = v;
+        }
         if let Some(v) = patch.update_check {
             self.update_check = v;
         }
@@ -149,14 +326,82 @@ impl Settings {
         if let Some(v) = patch.flag_display {
             self.flag_display = v;
         }
+        if let Some(v) = patch.daemon_socket_path {
+            self.daemon_socket_path = v;
+        }
+        if let Some(v) = patch.metrics_endpoint {
+            self.metrics_endpoint = v;
+        }
+        if let Some(v) = patch.metrics_port {
+            self.metrics_port = v;
+        }
+        if let Some(v) = patch.notifications {
+            self.notifications = v;
+        }
+        if let Some(v) = patch.tray_tooltip_fields {
+            self.tray_tooltip_fields = v;
+        }
+        if let Some(v) = patch.tray_title_fields {
+            self.tray_title_fields = v;
+        }
+    }
+}
+
+const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Version of the settings.json layout, stored as `schemaVersion`. Bump it
+/// together with a new entry in `MIGRATIONS` whenever a key is renamed or
+/// restructured; purely additive fields need neither.
+pub const SCHEMA_VERSION: u64 = 1;
+
+/// `MIGRATIONS[n]` upgrades the raw file from version `n` to `n + 1`.
+const MIGRATIONS: [fn(&mut serde_json::Map<String, Value>); SCHEMA_VERSION as usize] =
+    [migrate_v0_to_v1];
+
+/// Version 0 is every file written before `schemaVersion` existed: the
+/// tauri-plugin-store era with its `theme` key (the theme now follows the
+/// OS) and the single `preferredLocation` id that predates the preference
+/// list.
+fn migrate_v0_to_v1(map: &mut serde_json::Map<String, Value>) {
+    map.remove("theme");
+    let legacy = map.remove("preferredLocation");
+    if let Some(Value::String(id)) = legacy.filter(|_| !map.contains_key("preferredExits")) {
+        map.insert(
+            "preferredExits".to_string(),
+            serde_json::json!([{ "id": id }]),
+        );
+    }
+}
+
+/// Takes `schemaVersion` out of the raw file; a missing or invalid one is
+/// treated as 0, which every migration tolerates.
+fn take_schema_version(map: &mut serde_json::Map<String, Value>) -> u64 {
+    match map.remove("schemaVersion") {
+        None => 0,
+        Some(value) => value.as_u64().unwrap_or_else(|| {
+            eprintln!("settings: invalid schemaVersion {value}, migrating from 0");
+            0
+        }),
+    }
+}
+
+fn migrate(map: &mut serde_json::Map<String, Value>, from: u64) {
+    for migration in MIGRATIONS.iter().skip(from as usize) {
+        migration(map);
     }
 }
 
 /// Builds settings from a raw JSON map one key at a time, so a single
-/// invalid or legacy value falls back to its default instead of
+/// invalid or unknown value falls back to its default instead of
 /// discarding the whole file.
 fn lenient_from_map(map: serde_json::Map<String, Value>) -> Settings {
     let mut settings = Settings::default();
+    lenient_apply(&mut settings, map);
+    settings
+}
+
+/// Applies each valid key of a raw JSON map on top of `settings`.
+fn lenient_apply(settings: &mut Settings, map: serde_json::Map<String, Value>) {
     for (key, value) in map {
         let single = Value::Object([(key.clone(), value)].into_iter().collect());
         match serde_json::from_value::<SettingsPatch>(single) {
@@ -164,55 +409,121 @@ fn lenient_from_map(map: serde_json::Map<String, Value>) -> Settings {
             Err(e) => eprintln!("settings: ignoring invalid value for '{key}': {e}"),
         }
     }
+}
+
+/// Copy of the file as it was before a migration, next to it as
+/// `settings.json.v<version>.bak`.
+fn backup_path(path: &Path, version: u64) -> PathBuf {
+    path.with_extension(format!("json.v{version}.bak"))
+}
+
+/// The file's settings, migrated in memory only: for readers like the CLI
+/// that must not back up or rewrite the running app's file.
+pub fn read(path: &Path) -> Settings {
+    read_map(path).map_or_else(Settings::default, |mut map| {
+        let version = take_schema_version(&mut map);
+        migrate(&mut map, version);
+        lenient_from_map(map)
+    })
+}
+
+/// `None` (so defaults) if the file is missing, unreadable or not an object.
+fn read_map(path: &Path) -> Option<serde_json::Map<String, Value>> {
+    match std::fs::read(path) {
+        // missing file is the regular first run — start from defaults
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => {
+            eprintln!(
+                "settings: cannot read {}, using defaults: {e}",
+                path.display()
+            );
+            None
+        }
+        Ok(bytes) => match serde_json::from_slice(&bytes) {
+            Ok(map) => Some(map),
+            Err(e) => {
+                eprintln!(
+                    "settings: cannot parse {}, using defaults: {e}",
+                    path.display()
+                );
+                None
+            }
+        },
+    }
+}
+
+/// Migrates a parsed file, backing up the original first and writing the
+/// upgraded one back so each migration runs once. A file from a newer app
+/// is backed up too and loaded leniently: its unknown keys are lost on the
+/// next write.
+fn load_versioned(path: &Path, mut map: serde_json::Map<String, Value>) -> Settings {
+    let version = take_schema_version(&mut map);
+    if version == SCHEMA_VERSION {
+        return lenient_from_map(map);
+    }
+    let backup = backup_path(path, version);
+    if let Err(e) = std::fs::copy(path, &backup) {
+        eprintln!("settings: cannot back up {}: {e}", path.display());
+    }
+    if version > SCHEMA_VERSION {
+        eprintln!(
+            "settings: {} has schema version {version}, newer than {SCHEMA_VERSION}; backed up to {}",
+            path.display(),
+            backup.display()
+        );
+        return lenient_from_map(map);
+    }
+    migrate(&mut map, version);
+    let settings = lenient_from_map(map);
+    println!("settings: migrated from schema version {version} to {SCHEMA_VERSION}");
+    if let Err(e) = persist(path, &settings) {
+        eprintln!("settings: cannot write migrated settings: {e}");
+    }
     settings
 }
 
-// write-then-rename keeps the previous file intact if the app dies mid-write
-fn persist(path: &Path, settings: &Settings) -> Result<(), String> {
-    let json = serde_json::to_string_pretty(settings)
+// write-then-rename keeps the previous file intact if the app dies mid-write;
+// returns the bytes written
+fn persist(path: &Path, settings: &Settings) -> Result<Vec<u8>, String> {
+    let mut value =
+        serde_json::to_value(settings).map_err(|e| format!("cannot serialize settings: {e}"))?;
+    if let Value::Object(map) = &mut value {
+        map.insert("schemaVersion".to_string(), SCHEMA_VERSION.into());
+    }
+    let json = serde_json::to_string_pretty(&value)
         .map_err(|e| format!("cannot serialize settings: {e}"))?;
     if let Some(parent) = path.parent() {
         std::fs::create_dir_all(parent).map_err(|e| format!("cannot create settings dir: {e}"))?;
     }
     let tmp_path = path.with_extension("json.tmp");
-    std::fs::write(&tmp_path, json).map_err(|e| format!("cannot write settings file: {e}"))?;
+    std::fs::write(&tmp_path, &json).map_err(|e| format!("cannot write settings file: {e}"))?;
     std::fs::rename(&tmp_path, path)
-        .map_err(|e| format!("cannot move settings file into place: {e}"))
+        .map_err(|e| format!("cannot move settings file into place: {e}"))?;
+    Ok(json.into_bytes())
 }
 
-/// Single owner of the settings: loads/persists the file and hands out
-/// snapshots. Webviews mirror it via `get_settings` + the
+/// Single owner of the settings: loads/persists the file, merges external
+/// edits of it (`spawn_watcher`) and hands out snapshots. Webviews mirror it via `get_settings` + the
 /// `settings-changed` event.
 pub struct SettingsStore {
     settings: Mutex<Settings>,
     path: PathBuf,
+    /// The file as last read or written by the store, so its own writes
+    /// are not mistaken for external edits. Only locked under `settings`.
+    on_disk: Mutex<Vec<u8>>,
+    /// Latest committed snapshot, for in-process listeners (`control`).
+    changes: watch::Sender<Settings>,
 }
 
 impl SettingsStore {
     pub fn load(path: PathBuf) -> Self {
-        let settings = match std::fs::read(&path) {
-            // missing file is the regular first run — start from defaults
-            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Settings::default(),
-            Err(e) => {
-                eprintln!(
-                    "settings: cannot read {}, using defaults: {e}",
-                    path.display()
-                );
-                Settings::default()
-            }
-            Ok(bytes) => match serde_json::from_slice(&bytes) {
-                Ok(map) => lenient_from_map(map),
-                Err(e) => {
-                    eprintln!(
-                        "settings: cannot parse {}, using defaults: {e}",
-                        path.display()
-                    );
-                    Settings::default()
-                }
-            },
-        };
+        let settings =
+            read_map(&path).map_or_else(Settings::default, |map| load_versioned(&path, map));
         Self {
+            changes: watch::Sender::new(settings.clone()),
             settings: Mutex::new(settings),
+            // after a migration this is the rewritten file
+            on_disk: Mutex::new(std::fs::read(&path).unwrap_or_default()),
             path,
         }
     }
@@ -226,15 +537,77 @@ impl SettingsStore {
     /// stale snapshots out of order and memory never diverges from disk.
     /// On failure the update is rejected wholesale; the mirrors receive no
     /// event, so their controls visibly snap back.
+    /// An external edit the watcher has not picked up yet is merged first,
+    /// so the write does not discard it.
     pub fn update(&self, patch: SettingsPatch) -> Result<Settings, String> {
+        self.modify(|settings| settings.apply(patch))
+    }
+
+    /// `update` for changes that are not a patch (import, reset).
+    fn modify(&self, edit: impl FnOnce(&mut Settings)) -> Result<Settings, String> {
         let mut guard = self.lock();
         let mut updated = guard.clone();
-        updated.apply(patch);
-        persist(&self.path, &updated)?;
+        self.merge_external(&mut updated);
+        edit(&mut updated);
+        let written = persist(&self.path, &updated)?;
+        *self.on_disk.lock().unwrap_or_else(|e| e.into_inner()) = written;
         *guard = updated.clone();
+        self.changes.send_replace(updated.clone());
         Ok(updated)
     }
 
+    /// Merges an edit made outside the app into memory and returns the new
+    /// snapshot; `None` if the file still holds what the store last read or
+    /// wrote, is gone, or does not parse.
+    pub fn reload(&self) -> Option<Settings> {
+        let mut guard = self.lock();
+        let mut updated = guard.clone();
+        if !self.merge_external(&mut updated) {
+            return None;
+        }
+        *guard = updated.clone();
+        self.changes.send_replace(updated.clone());
+        Some(updated)
+    }
+
+    /// Keys present in the file replace the values in `settings`; absent
+    /// and invalid ones keep them. Runs the migrations, but leaves the
+    /// file as its author wrote it.
+    fn merge_external(&self, settings: &mut Settings) -> bool {
+        let Ok(bytes) = std::fs::read(&self.path) else {
+            return false;
+        };
+        let mut on_disk = self.on_disk.lock().unwrap_or_else(|e| e.into_inner());
+        if *on_disk == bytes {
+            return false;
+        }
+        let parsed = serde_json::from_slice::<serde_json::Map<String, Value>>(&bytes);
+        // remembered even if invalid, so a broken edit is reported once
+        *on_disk = bytes;
+        let mut map = match parsed {
+            Ok(map) => map,
+            Err(e) => {
+                eprintln!(
+                    "settings: ignoring edit of {}, cannot parse: {e}",
+                    self.path.display()
+                );
+                return false;
+            }
+        };
+        let version = take_schema_version(&mut map);
+        migrate(&mut map, version);
+        lenient_apply(settings, map);
+        println!(
+            "settings: reloaded {} after an external edit",
+            self.path.display()
+        );
+        true
+    }
+
+    pub fn subscribe(&self) -> watch::Receiver<Settings> {
+        self.changes.subscribe()
+    }
+
     fn lock(&self) -> MutexGuard<'_, Settings> {
         // a poisoned lock cannot leave Settings logically broken
         // (apply is plain field assignments), so recover instead of erroring
@@ -244,6 +617,47 @@ impl SettingsStore {
     }
 }
 
+/// Sends a committed snapshot to the webviews and to the parts of the app
+/// that act on a setting.
+pub(crate) fn publish(app: &AppHandle, snapshot: &Settings) {
+    let _ = app.emit("settings-changed", snapshot);
+    // the status loop watches the socket and restarts against the new one
+    app.state::<DaemonSocket>()
+        .apply_setting(snapshot.daemon_socket_path.as_deref());
+    crate::metrics::apply_setting(app, snapshot);
+    // a fresh manifest or an undismissed version may announce an update
+    crate::notifications::check_update(app, snapshot);
+}
+
+/// Polls settings.json for edits from config management, dotfile sync or
+/// another tool and publishes them like an `update_settings` call. Edits
+/// are told by content, not modification time and size, which two quick
+/// same-length edits can leave unchanged; the store's own writes compare
+/// equal to what it remembers and are skipped.
+pub fn spawn_watcher(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let path = app.state::<SettingsStore>().path.clone();
+        let mut interval = tokio::time::interval(WATCH_INTERVAL);
+        let mut seen = None;
+        loop {
+            interval.tick().await;
+            let content = std::fs::read(&path).ok();
+            if content == seen {
+                continue;
+            }
+            seen = content;
+            let store = app.state::<SettingsStore>();
+            let launch_at_login = store.current().launch_at_login;
+            if let Some(snapshot) = store.reload() {
+                publish(&app, &snapshot);
+                if snapshot.launch_at_login != launch_at_login {
+                    crate::autostart::apply(&app, snapshot.launch_at_login);
+                }
+            }
+        }
+    });
+}
+
 #[tauri::command]
 pub fn get_settings(store: State<'_, SettingsStore>) -> Settings {
     store.current()
@@ -254,15 +668,122 @@ pub fn update_settings(
     app: AppHandle,
     store: State<'_, SettingsStore>,
     patch: SettingsPatch,
-) -> Result<Settings, String> {
-    let snapshot = store.update(patch).map_err(|e| {
-        eprintln!("settings: failed to persist: {e}");
-        e
-    })?;
-    let _ = app.emit("settings-changed", &snapshot);
+) -> Result<Settings, AppError> {
+    commit(&app, &store, |settings| settings.apply(patch))
+}
+
+/// `SettingsStore::modify` plus `publish`. The OS autostart entry follows
+/// `launch_at_login` whenever the committed value differs from the one
+/// before, whether the edit or an external edit merged on the way changed it.
+fn commit(
+    app: &AppHandle,
+    store: &SettingsStore,
+    edit: impl FnOnce(&mut Settings),
+) -> Result<Settings, AppError> {
+    let launch_at_login = store.current().launch_at_login;
+    let snapshot = store.modify(edit).map_err(persist_error)?;
+    publish(app, &snapshot);
+    if snapshot.launch_at_login != launch_at_login {
+        crate::autostart::apply(app, snapshot.launch_at_login);
+    }
     Ok(snapshot)
 }
 
+/// Transient or tied to this machine, so left out of exports and ignored
+/// on import: update state, the installed-version marker, and the mirror
+/// of the OS autostart entry.
+const NOT_EXPORTED: [&str; 5] = [
+    "updateManifest",
+    "lastCheckedAt",
+    "dismissedUpdateVersion",
+    "installedVersion",
+    "launchAtLogin",
+];
+
+/// The shareable part of `settings`, with `schemaVersion` so an import
+/// into a later app can migrate it.
+fn export_value(settings: &Settings) -> Result<Value, String> {
+    let mut value =
+        serde_json::to_value(settings).map_err(|e| format!("cannot serialize settings: {e}"))?;
+    if let Value::Object(map) = &mut value {
+        for key in NOT_EXPORTED {
+            map.remove(key);
+        }
+        map.insert("schemaVersion".to_string(), SCHEMA_VERSION.into());
+    }
+    Ok(value)
+}
+
+/// Migrates an exported (or hand-written) file and applies its valid keys
+/// over `settings`, like an external edit; excluded keys are ignored.
+fn import_map(settings: &mut Settings, mut map: serde_json::Map<String, Value>) {
+    let version = take_schema_version(&mut map);
+    migrate(&mut map, version);
+    for key in NOT_EXPORTED {
+        map.remove(key);
+    }
+    lenient_apply(settings, map);
+}
+
+/// `settings.json.<local time>.bak`, kept by `reset_settings`; a counter
+/// is appended rather than overwrite an earlier backup.
+fn timestamped_backup_path(path: &Path) -> PathBuf {
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S%.3f").to_string();
+    let mut backup = path.with_extension(format!("json.{stamp}.bak"));
+    let mut n = 1;
+    while backup.exists() {
+        backup = path.with_extension(format!("json.{stamp}-{n}.bak"));
+        n += 1;
+    }
+    backup
+}
+
+fn persist_error(e: String) -> AppError {
+    eprintln!("settings: failed to persist: {e}");
+    AppError::new(ErrorCode::SettingsPersistFailed, "Failed to save settings").with_details(e)
+}
+
+#[tauri::command]
+pub fn export_settings(store: State<'_, SettingsStore>, dest_path: String) -> Result<(), AppError> {
+    let json = export_value(&store.current())
+        .and_then(|value| serde_json::to_string_pretty(&value).map_err(|e| e.to_string()))
+        .map_err(AppError::internal)?;
+    std::fs::write(&dest_path, json)
+        .map_err(|e| AppError::new(ErrorCode::Io, "Failed to write settings").with_details(e))
+}
+
+#[tauri::command]
+pub fn import_settings(
+    app: AppHandle,
+    store: State<'_, SettingsStore>,
+    src_path: String,
+) -> Result<Settings, AppError> {
+    let bytes = std::fs::read(&src_path)
+        .map_err(|e| AppError::new(ErrorCode::Io, "Failed to read settings").with_details(e))?;
+    let map = serde_json::from_slice::<serde_json::Map<String, Value>>(&bytes).map_err(|e| {
+        AppError::new(ErrorCode::InvalidSettingsFile, "Not a settings file").with_details(e)
+    })?;
+    commit(&app, &store, |settings| import_map(settings, map))
+}
+
+/// Restores `Settings::default()` after copying the current file aside.
+#[tauri::command]
+pub fn reset_settings(
+    app: AppHandle,
+    store: State<'_, SettingsStore>,
+) -> Result<Settings, AppError> {
+    let backup = timestamped_backup_path(&store.path);
+    match std::fs::copy(&store.path, &backup) {
+        // nothing was ever saved, so there is nothing to keep
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => {
+            return Err(AppError::new(ErrorCode::Io, "Failed to back up settings").with_details(e));
+        }
+        Ok(_) => println!("settings: backed up to {} before reset", backup.display()),
+    }
+    commit(&app, &store, |settings| *settings = Settings::default())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -288,7 +809,7 @@ mod tests {
     fn load_missing_file_yields_defaults() {
         let store = SettingsStore::load(temp_settings_path());
         let settings = store.current();
-        assert_eq!(settings.preferred_location, None);
+        assert!(settings.preferred_exits.is_empty());
         assert!(!settings.connect_on_startup);
         assert_eq!(settings.exit_node_sort_order, SortOrder::Latency);
         assert_eq!(settings.channel, None);
@@ -311,18 +832,263 @@ mod tests {
         .unwrap();
 
         let settings = SettingsStore::load(path).current();
-        // legacy key dropped, invalid value falls back, valid values survive
+        // legacy key dropped or migrated, invalid value falls back, valid values survive
         assert_eq!(settings.exit_node_sort_order, SortOrder::Latency);
-        assert_eq!(settings.preferred_location, Some("exit-1".to_string()));
+        assert_eq!(
+            settings.preferred_exits,
+            vec![ExitPreference::Id("exit-1".to_string())]
+        );
         assert!(settings.show_detailed_metrics);
     }
 
+    #[test]
+    fn legacy_preferred_location_does_not_override_a_list() {
+        let path = temp_settings_path();
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(
+            &path,
+            json!({
+                "preferredLocation": "exit-1",
+                "preferredExits": [{ "meta": { "location": "EU" } }, { "id": "exit-2" }]
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        let settings = SettingsStore::load(path).current();
+        assert_eq!(
+            settings.preferred_exits,
+            vec![
+                ExitPreference::Meta(BTreeMap::from([("location".to_string(), "EU".to_string())])),
+                ExitPreference::Id("exit-2".to_string()),
+            ]
+        );
+        assert_eq!(pinned_exit(&settings.preferred_exits), None);
+    }
+
+    fn read_json(path: &Path) -> Value {
+        serde_json::from_slice(&std::fs::read(path).unwrap()).unwrap()
+    }
+
+    fn write_json(path: &Path, value: &Value) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, value.to_string()).unwrap();
+    }
+
+    #[test]
+    fn migrates_plugin_store_era_file() {
+        let path = temp_settings_path();
+        let original = json!({
+            "theme": "dark",
+            "preferredLocation": "exit-1",
+            "connectOnStartup": true,
+            "exitNodeSortOrder": "alpha"
+        });
+        write_json(&path, &original);
+
+        let settings = SettingsStore::load(path.clone()).current();
+        assert_eq!(
+            settings.preferred_exits,
+            vec![ExitPreference::Id("exit-1".to_string())]
+        );
+        assert!(settings.connect_on_startup);
+        assert_eq!(settings.exit_node_sort_order, SortOrder::Alpha);
+
+        // the original is kept verbatim, the file is rewritten at the current version
+        assert_eq!(read_json(&backup_path(&path, 0)), original);
+        let migrated = read_json(&path);
+        assert_eq!(migrated["schemaVersion"], json!(SCHEMA_VERSION));
+        assert!(migrated.get("theme").is_none());
+        assert!(migrated.get("preferredLocation").is_none());
+        assert_eq!(migrated["preferredExits"], json!([{ "id": "exit-1" }]));
+    }
+
+    #[test]
+    fn read_migrates_without_touching_the_file() {
+        let path = temp_settings_path();
+        let original = json!({ "preferredLocation": "exit-1", "alwaysOn": true });
+        write_json(&path, &original);
+
+        let settings = read(&path);
+        assert_eq!(
+            settings.preferred_exits,
+            vec![ExitPreference::Id("exit-1".to_string())]
+        );
+        assert!(settings.always_on);
+        assert_eq!(read_json(&path), original);
+        assert!(!backup_path(&path, 0).exists());
+    }
+
+    #[test]
+    fn migrates_unversioned_preference_list_file() {
+        // written after the preference list replaced the single location,
+        // before settings were versioned
+        let path = temp_settings_path();
+        write_json(
+            &path,
+            &json!({
+                "preferredExits": [{ "id": "exit-2" }],
+                "alwaysOn": true,
+                "exitNodeSortOrder": "score"
+            }),
+        );
+
+        let settings = SettingsStore::load(path.clone()).current();
+        assert_eq!(
+            settings.preferred_exits,
+            vec![ExitPreference::Id("exit-2".to_string())]
+        );
+        assert!(settings.always_on);
+        assert_eq!(settings.exit_node_sort_order, SortOrder::Score);
+        assert!(backup_path(&path, 0).exists());
+        assert_eq!(read_json(&path)["schemaVersion"], json!(SCHEMA_VERSION));
+    }
+
+    #[test]
+    fn current_version_loads_without_backup() {
+        let path = temp_settings_path();
+        let store = SettingsStore::load(path.clone());
+        store
+            .update(patch(json!({ "alwaysOn": true })))
+            .expect("update should succeed");
+        assert_eq!(read_json(&path)["schemaVersion"], json!(SCHEMA_VERSION));
+
+        assert!(SettingsStore::load(path.clone()).current().always_on);
+        assert!(!backup_path(&path, 0).exists());
+        assert!(!backup_path(&path, SCHEMA_VERSION).exists());
+    }
+
+    #[test]
+    fn newer_version_is_backed_up_and_not_rewritten() {
+        let path = temp_settings_path();
+        let original = json!({
+            "schemaVersion": SCHEMA_VERSION + 1,
+            "alwaysOn": true,
+            "renamedInTheFuture": "x"
+        });
+        write_json(&path, &original);
+
+        assert!(SettingsStore::load(path.clone()).current().always_on);
+        assert_eq!(read_json(&backup_path(&path, SCHEMA_VERSION + 1)), original);
+        assert_eq!(read_json(&path), original);
+    }
+
+    #[test]
+    fn own_writes_are_not_reloaded() {
+        let store = SettingsStore::load(temp_settings_path());
+        assert!(store.reload().is_none());
+        store
+            .update(patch(json!({ "alwaysOn": true })))
+            .expect("update should succeed");
+        assert!(store.reload().is_none());
+    }
+
+    #[test]
+    fn external_edit_is_merged() {
+        let path = temp_settings_path();
+        let store = SettingsStore::load(path.clone());
+        store
+            .update(patch(
+                json!({ "alwaysOn": true, "exitNodeSortOrder": "alpha" }),
+            ))
+            .expect("update should succeed");
+
+        write_json(
+            &path,
+            &json!({ "connectOnStartup": true, "exitNodeSortOrder": "bogus" }),
+        );
+        let snapshot = store.reload().expect("edit should be picked up");
+        assert!(snapshot.connect_on_startup);
+        // absent and invalid keys keep the values in memory
+        assert!(snapshot.always_on);
+        assert_eq!(snapshot.exit_node_sort_order, SortOrder::Alpha);
+        assert!(store.current().connect_on_startup);
+        assert!(store.reload().is_none());
+
+        std::fs::write(&path, b"{ half written").unwrap();
+        assert!(store.reload().is_none());
+        assert!(store.current().connect_on_startup);
+    }
+
+    #[test]
+    fn update_keeps_an_unseen_external_edit() {
+        let path = temp_settings_path();
+        let store = SettingsStore::load(path.clone());
+        write_json(&path, &json!({ "startMinimized": true }));
+
+        let snapshot = store
+            .update(patch(json!({ "alwaysOn": true })))
+            .expect("update should succeed");
+        assert!(snapshot.start_minimized);
+        assert!(snapshot.always_on);
+        let reloaded = SettingsStore::load(path).current();
+        assert!(reloaded.start_minimized);
+        assert!(reloaded.always_on);
+    }
+
+    #[test]
+    fn export_leaves_out_transient_fields() {
+        let settings = Settings {
+            always_on: true,
+            last_checked_at: Some(1720000000000),
+            installed_version: Some("0.50.0".to_string()),
+            launch_at_login: true,
+            ..Settings::default()
+        };
+        let exported = export_value(&settings).unwrap();
+        assert_eq!(exported["alwaysOn"], json!(true));
+        assert_eq!(exported["schemaVersion"], json!(SCHEMA_VERSION));
+        for key in NOT_EXPORTED {
+            assert!(exported.get(key).is_none(), "{key}");
+        }
+    }
+
+    #[test]
+    fn import_applies_shared_keys_only() {
+        let shared = Settings {
+            always_on: true,
+            exit_node_sort_order: SortOrder::Score,
+            ..Settings::default()
+        };
+        let Value::Object(mut map) = export_value(&shared).unwrap() else {
+            panic!("export is an object");
+        };
+        map.insert("metricsPort".to_string(), json!("not a port"));
+        map.insert("installedVersion".to_string(), json!("0.1.0"));
+
+        let mut local = Settings {
+            installed_version: Some("0.50.0".to_string()),
+            metrics_port: 9000,
+            ..Settings::default()
+        };
+        import_map(&mut local, map);
+        assert!(local.always_on);
+        assert_eq!(local.exit_node_sort_order, SortOrder::Score);
+        // invalid values and machine-specific keys leave the local ones
+        assert_eq!(local.metrics_port, 9000);
+        assert_eq!(local.installed_version, Some("0.50.0".to_string()));
+
+        // files from before versioning go through the migrations
+        let mut local = Settings::default();
+        import_map(
+            &mut local,
+            json!({ "preferredLocation": "exit-1" })
+                .as_object()
+                .unwrap()
+                .clone(),
+        );
+        assert_eq!(
+            local.preferred_exits,
+            vec![ExitPreference::Id("exit-1".to_string())]
+        );
+    }
+
     #[test]
     fn patch_distinguishes_null_from_absent() {
         let store = SettingsStore::load(temp_settings_path());
         store
             .update(patch(
-                json!({ "preferredLocation": "exit-1", "channel": "stable" }),
+                json!({ "dismissedUpdateVersion": "0.28.0", "channel": "stable" }),
             ))
             .expect("update should succeed");
 
@@ -330,14 +1096,17 @@ mod tests {
         let snapshot = store
             .update(patch(json!({ "connectOnStartup": true })))
             .expect("update should succeed");
-        assert_eq!(snapshot.preferred_location, Some("exit-1".to_string()));
+        assert_eq!(
+            snapshot.dismissed_update_version,
+            Some("0.28.0".to_string())
+        );
         assert_eq!(snapshot.channel, Some(UpdateChannel::Stable));
 
         // explicit null clears
         let snapshot = store
-            .update(patch(json!({ "preferredLocation": null })))
+            .update(patch(json!({ "dismissedUpdateVersion": null })))
             .expect("update should succeed");
-        assert_eq!(snapshot.preferred_location, None);
+        assert_eq!(snapshot.dismissed_update_version, None);
         assert_eq!(snapshot.channel, Some(UpdateChannel::Stable));
     }
 
@@ -398,4 +1167,38 @@ mod tests {
         assert!(reloaded.connect_on_startup);
         assert!(reloaded.show_detailed_metrics);
     }
+
+    #[test]
+    fn timestamped_backups_do_not_overwrite_each_other() {
+        let path = temp_settings_path();
+        let first = timestamped_backup_path(&path);
+        std::fs::create_dir_all(first.parent().unwrap()).unwrap();
+        std::fs::write(&first, b"{}").unwrap();
+        assert_ne!(timestamped_backup_path(&path), first);
+    }
+
+    #[test]
+    fn quiet_hours_may_span_midnight() {
+        let night = QuietHours {
+            start: "22:00".to_string(),
+            end: "07:30".to_string(),
+        };
+        assert!(night.contains(22 * 60));
+        assert!(night.contains(3 * 60));
+        assert!(!night.contains(7 * 60 + 30));
+        assert!(!night.contains(12 * 60));
+
+        let lunch = QuietHours {
+            start: "12:00".to_string(),
+            end: "13:00".to_string(),
+        };
+        assert!(lunch.contains(12 * 60 + 59));
+        assert!(!lunch.contains(13 * 60));
+
+        let invalid = QuietHours {
+            start: "25:00".to_string(),
+            end: "07:00".to_string(),
+        };
+        assert!(!invalid.contains(3 * 60));
+    }
 }
@@ -0,0 +1,82 @@
+//! Daemon status fixtures for tests. Shared by the unit tests and, through
+//! `tests/common`, the integration tests, so it only uses the daemon
+//! library's types.
+
+use gnosis_vpn_lib::command::{DestinationState, RouteHealthView};
+use gnosis_vpn_lib::connection::destination::{Destination, HopRouting};
+use gnosis_vpn_lib::prelude::Address;
+use gnosis_vpn_lib::route_health::{
+    ExitHealth, Health, LoadAvg, RouteHealthState, Slots, Versions,
+};
+
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime};
+
+pub fn address() -> Address {
+    Address::from([0xaau8; 20])
+}
+
+/// A one-hop exit, with a `location` meta entry if given.
+pub fn destination(id: &str, location: Option<&str>) -> Destination {
+    let meta = location
+        .map(|l| HashMap::from([("location".to_string(), l.to_string())]))
+        .unwrap_or_default();
+    Destination::new(
+        id.to_string(),
+        address(),
+        HopRouting::try_from(1).unwrap(),
+        meta,
+    )
+}
+
+/// Checked at the epoch, 10 free slots with 1 connected, light load.
+pub fn exit_health(rtt_ms: u64) -> ExitHealth {
+    ExitHealth {
+        checked_at: SystemTime::UNIX_EPOCH,
+        versions: Versions {
+            versions: vec!["v1".to_string()],
+            latest: "v1".to_string(),
+        },
+        ping_rtt: Duration::from_millis(rtt_ms),
+        health: Health {
+            slots: Slots {
+                available: 10,
+                connected: 1,
+            },
+            load_avg: LoadAvg {
+                one: 0.1,
+                five: 0.2,
+                fifteen: 0.3,
+                nproc: 4,
+            },
+        },
+    }
+}
+
+pub fn ready_state(rtt_ms: u64) -> RouteHealthState {
+    RouteHealthState::ReadyToConnect {
+        exit: exit_health(rtt_ms),
+    }
+}
+
+/// Route health in `state` with no failures; tests that need some set
+/// `consecutive_failures` and `last_error` on the result.
+pub fn destination_state(
+    id: &str,
+    location: Option<&str>,
+    state: RouteHealthState,
+) -> DestinationState {
+    DestinationState {
+        destination: destination(id, location),
+        route_health: Some(RouteHealthView {
+            state,
+            last_error: None,
+            checking_since: None,
+            consecutive_failures: 0,
+        }),
+    }
+}
+
+pub fn ready(id: &str, rtt_ms: u64) -> DestinationState {
+    destination_state(id, None, ready_state(rtt_ms))
+}
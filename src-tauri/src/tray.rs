@@ -1,21 +1,258 @@
 use std::sync::Mutex;
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 use tauri::{
     AppHandle, Emitter, Listener, Manager,
-    menu::{Menu, MenuBuilder, MenuItem},
+    menu::{CheckMenuItem, Menu, MenuBuilder, MenuItem, Submenu},
     tray::TrayIconEvent,
 };
 use tokio::time::sleep;
 
+use crate::exits::{health_label, latency_ms, sort_exits};
+use crate::icons::{FundsLevel, TrayIconState};
+use crate::settings::{Settings, SortOrder, TrayField};
+use crate::types::{BalanceResponse, ConnectionState, StatusResponse, tokens};
+
+/// Menu id prefix of the "Connect to" entries; the rest is the exit id.
+pub const CONNECT_TO_PREFIX: &str = "connect_to:";
+
 /// State to hold a reference to the tray "status" menu item so commands can update it.
 pub struct TrayStatusItem(pub Mutex<MenuItem<tauri::Wry>>);
 
 /// State to hold a reference to the tray "quit" menu item so commands can update its label.
 pub struct TrayQuitItem(pub Mutex<MenuItem<tauri::Wry>>);
 
+/// State to hold a reference to the tray "Connect"/"Disconnect" menu item so
+/// the status loop can flip it.
+pub struct TrayConnectItem(pub Mutex<MenuItem<tauri::Wry>>);
+
+/// State to hold a reference to the read-only tray funds line.
+pub struct TrayFundsItem(pub Mutex<MenuItem<tauri::Wry>>);
+
+/// `Funds: low — 3.2 wxHOPR`, from the level and the safe's balance.
+pub fn funds_label(level: FundsLevel, balance: Option<&BalanceResponse>) -> String {
+    let level = level.name();
+    match balance.and_then(|b| tokens(&b.safe)) {
+        Some(safe) => format!("Funds: {level} — {safe:.1} wxHOPR"),
+        None => format!("Funds: {level}"),
+    }
+}
+
+/// What the tooltip and the indicator label are made of, for the exit
+/// being connected to, connected or reconnected.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct TrayFieldValues {
+    pub destination: Option<String>,
+    pub location: Option<String>,
+    pub connected_for: Option<Duration>,
+    pub latency_ms: Option<u64>,
+}
+
+impl TrayFieldValues {
+    pub fn new(status: &StatusResponse, conn_state: &ConnectionState, now: SystemTime) -> Self {
+        let id = match conn_state {
+            ConnectionState::Connected(id)
+            | ConnectionState::Connecting(id)
+            | ConnectionState::Reconnecting(id) => id,
+            ConnectionState::Disconnecting | ConnectionState::Disconnected => {
+                return Self::default();
+            }
+        };
+        let ds = status
+            .destinations
+            .iter()
+            .find(|ds| ds.destination.id == *id);
+        Self {
+            destination: Some(id.clone()),
+            location: ds.and_then(|ds| ds.destination.meta.get("location").cloned()),
+            connected_for: status
+                .connected
+                .as_ref()
+                .filter(|info| info.destination_id == *id)
+                .and_then(|info| now.duration_since(info.since).ok()),
+            // a connected exit is usually no longer `ReadyToConnect`
+            latency_ms: ds
+                .and_then(|ds| latency_ms(ds, &status.latency))
+                .or_else(|| status.latency.get(id).map(|stats| stats.smoothed_ms)),
+        }
+    }
+}
+
+/// One line per chosen field under the app name; the destination line is
+/// the connection state, so it also says "Disconnected".
+pub fn tooltip(
+    fields: &[TrayField],
+    values: &TrayFieldValues,
+    conn_state: &ConnectionState,
+    funds_level: FundsLevel,
+) -> String {
+    let mut lines = vec!["Gnosis VPN".to_string()];
+    for field in fields {
+        let line = match field {
+            TrayField::Destination => Some(conn_state.to_string()),
+            TrayField::Location => values.location.as_ref().map(|l| format!("Location: {l}")),
+            TrayField::ConnectedSince => values
+                .connected_for
+                .map(|d| format!("Connected for {}", format_duration(d))),
+            TrayField::Latency => values.latency_ms.map(|ms| format!("Latency: {ms} ms")),
+            TrayField::FundsLevel => Some(format!("Funds: {}", funds_level.name())),
+        };
+        lines.extend(line);
+    }
+    lines.join("\n")
+}
+
+/// The chosen fields' bare values, e.g. `EU · 42ms`; `None` when none has
+/// a value, so a disconnected indicator shows just its icon.
+pub fn indicator_title(
+    fields: &[TrayField],
+    values: &TrayFieldValues,
+    funds_level: FundsLevel,
+) -> Option<String> {
+    let parts: Vec<String> = fields
+        .iter()
+        .filter_map(|field| match field {
+            TrayField::Destination => values.destination.clone(),
+            TrayField::Location => values.location.clone(),
+            TrayField::ConnectedSince => values.connected_for.map(format_duration),
+            TrayField::Latency => values.latency_ms.map(|ms| format!("{ms}ms")),
+            // only worth the space when something is wrong
+            TrayField::FundsLevel => (funds_level != FundsLevel::Sufficient)
+                .then(|| format!("funds {}", funds_level.name())),
+        })
+        .collect();
+    (!parts.is_empty()).then(|| parts.join(" · "))
+}
+
+fn format_duration(duration: Duration) -> String {
+    let minutes = duration.as_secs() / 60;
+    match minutes / 60 {
+        0 => format!("{minutes}m"),
+        hours => format!("{hours}h {:02}m", minutes % 60),
+    }
+}
+
+/// Sets the tooltip and, on Linux where tooltips are not shown, the
+/// indicator label next to the icon.
+pub fn update_tray_text(
+    app: &AppHandle,
+    settings: &Settings,
+    status: &StatusResponse,
+    conn_state: &ConnectionState,
+    funds_level: FundsLevel,
+) {
+    let values = TrayFieldValues::new(status, conn_state, SystemTime::now());
+    let tray_state = app.state::<TrayIconState>();
+    let Ok(tray) = tray_state.tray.lock() else {
+        return;
+    };
+    let text = tooltip(
+        &settings.tray_tooltip_fields,
+        &values,
+        conn_state,
+        funds_level,
+    );
+    let _ = tray.set_tooltip(Some(text));
+    #[cfg(target_os = "linux")]
+    {
+        let title = indicator_title(&settings.tray_title_fields, &values, funds_level);
+        let _ = tray.set_title(title);
+    }
+}
+
+/// State to hold the tray "Connect to" submenu and its current entries.
+pub struct TrayExitMenu(pub Mutex<ExitMenu>);
+
+pub struct ExitMenu {
+    submenu: Submenu<tauri::Wry>,
+    items: Vec<(String, CheckMenuItem<tauri::Wry>)>,
+}
+
+/// One "Connect to" entry; `checked` marks the daemon's connect target.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ExitEntry {
+    pub id: String,
+    pub label: String,
+    pub checked: bool,
+}
+
+/// Entries in the exit list's order, labelled `id · location · 42 ms`, or
+/// with the health state instead of the latency when an exit is not ready.
+pub fn exit_entries(status: &StatusResponse, order: SortOrder) -> Vec<ExitEntry> {
+    sort_exits(&status.destinations, &status.latency, &status.scores, order)
+        .into_iter()
+        .map(|ds| {
+            let id = &ds.destination.id;
+            let mut label = id.clone();
+            if let Some(location) = ds.destination.meta.get("location") {
+                label.push_str(&format!(" · {location}"));
+            }
+            match latency_ms(ds, &status.latency) {
+                Some(ms) => label.push_str(&format!(" · {ms} ms")),
+                None => label.push_str(&format!(" · {}", health_label(ds))),
+            }
+            ExitEntry {
+                id: id.clone(),
+                label,
+                checked: status.target_destination.as_ref() == Some(id),
+            }
+        })
+        .collect()
+}
+
+/// Relabels the entries in place; only a changed set of exits rebuilds
+/// the submenu. Checks are reasserted every time because clicking a check
+/// item toggles it natively.
+pub fn update_exit_menu(app: &AppHandle, entries: &[ExitEntry]) {
+    let Some(menu) = app.try_state::<TrayExitMenu>() else {
+        return;
+    };
+    let Ok(mut menu) = menu.0.lock() else {
+        return;
+    };
+    let unchanged = menu
+        .items
+        .iter()
+        .map(|(id, _)| id)
+        .eq(entries.iter().map(|entry| &entry.id));
+    if !unchanged {
+        for (_, item) in menu.items.drain(..) {
+            let _ = menu.submenu.remove(&item);
+        }
+        for entry in entries {
+            let item = match CheckMenuItem::with_id(
+                app,
+                format!("{CONNECT_TO_PREFIX}{}", entry.id),
+                &entry.label,
+                true,
+                entry.checked,
+                None::<&str>,
+            ) {
+                Ok(item) => item,
+                Err(e) => {
+                    eprintln!("Failed to create exit menu item: {e}");
+                    continue;
+                }
+            };
+            let _ = menu.submenu.append(&item);
+            menu.items.push((entry.id.clone(), item));
+        }
+        let _ = menu.submenu.set_enabled(!menu.items.is_empty());
+        return;
+    }
+    for ((_, item), entry) in menu.items.iter().zip(entries) {
+        let _ = item.set_text(&entry.label);
+        let _ = item.set_checked(entry.checked);
+    }
+}
+
 pub fn create_tray_menu(app: &AppHandle) -> Result<Menu<tauri::Wry>, tauri::Error> {
     let status_item =
         MenuItem::with_id(app, "status", "Status: Disconnected", false, None::<&str>)?;
+    let funds_item = MenuItem::with_id(app, "funds", "Funds: unknown", false, None::<&str>)?;
+    // enabled by the status loop once there is an exit to connect to
+    let connect_item = MenuItem::with_id(app, "toggle_connection", "Connect", false, None::<&str>)?;
+    // filled from the status loop, see `update_exit_menu`
+    let exits_submenu = Submenu::with_id(app, "connect_to", "Connect to", false)?;
     let show_item = MenuItem::with_id(app, "show", "Show", true, None::<&str>)?;
     let settings_item = MenuItem::with_id(app, "settings", "Settings", true, None::<&str>)?;
     let logs_item = MenuItem::with_id(app, "logs", "Logs", true, None::<&str>)?;
@@ -26,9 +263,18 @@ pub fn create_tray_menu(app: &AppHandle) -> Result<Menu<tauri::Wry>, tauri::Erro
 
     app.manage(TrayStatusItem(Mutex::new(status_item.clone())));
     app.manage(TrayQuitItem(Mutex::new(quit_item.clone())));
+    app.manage(TrayConnectItem(Mutex::new(connect_item.clone())));
+    app.manage(TrayFundsItem(Mutex::new(funds_item.clone())));
+    app.manage(TrayExitMenu(Mutex::new(ExitMenu {
+        submenu: exits_submenu.clone(),
+        items: Vec::new(),
+    })));
 
     MenuBuilder::new(app)
         .item(&status_item)
+        .item(&funds_item)
+        .item(&connect_item)
+        .item(&exits_submenu)
         .separator()
         .item(&show_item)
         .item(&settings_item)
@@ -40,6 +286,53 @@ pub fn create_tray_menu(app: &AppHandle) -> Result<Menu<tauri::Wry>, tauri::Erro
         .build()
 }
 
+/// Shows and focuses the main window, e.g. for a second launch.
+pub fn show_main_window(app: &AppHandle) {
+    #[cfg(target_os = "macos")]
+    {
+        let _ = app.set_activation_policy(tauri::ActivationPolicy::Regular);
+    }
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+/// A window, or a page of the settings window, that links and launch
+/// arguments can open.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WindowTarget {
+    Main,
+    Settings,
+    Logs,
+    Usage,
+    Updates,
+}
+
+impl WindowTarget {
+    /// `main`, `settings`, `logs`, `usage` or `updates`.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "main" => Some(Self::Main),
+            "settings" => Some(Self::Settings),
+            "logs" => Some(Self::Logs),
+            "usage" => Some(Self::Usage),
+            "updates" => Some(Self::Updates),
+            _ => None,
+        }
+    }
+}
+
+pub fn show_window_target(app: &AppHandle, target: WindowTarget) {
+    match target {
+        WindowTarget::Main => show_main_window(app),
+        WindowTarget::Settings => show_settings(app, "settings"),
+        WindowTarget::Logs => show_settings(app, "logs"),
+        WindowTarget::Usage => show_settings(app, "usage"),
+        WindowTarget::Updates => show_settings(app, "updates"),
+    }
+}
+
 pub fn toggle_main_window_visibility(app: &AppHandle) {
     if let Some(window) = app.get_webview_window("main") {
         let is_visible = window.is_visible().unwrap_or(false);
@@ -143,3 +436,142 @@ pub fn show_settings_and_check(app: &AppHandle) {
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{address, destination_state, ready_state};
+    use crate::types::RunMode;
+    use gnosis_vpn_lib::command::{self, ConnectedInfo};
+    use gnosis_vpn_lib::route_health::RouteHealthState;
+
+    use std::collections::BTreeMap;
+    use std::time::SystemTime;
+
+    #[test]
+    fn funds_label_shows_level_and_safe_balance() {
+        let balance = BalanceResponse {
+            node: "1500000000000000000".to_string(),
+            safe: "3240000000000000000".to_string(),
+            channels_out: "0".to_string(),
+            info: command::Info {
+                node_address: address(),
+                node_peer_id: "16Uiu2HAmTest".to_string(),
+                safe_address: address(),
+            },
+            funding_status: None,
+            ideal_balance: None,
+            capacity_allocations: None,
+        };
+        assert_eq!(
+            funds_label(FundsLevel::Low, Some(&balance)),
+            "Funds: low — 3.2 wxHOPR"
+        );
+        assert_eq!(
+            funds_label(FundsLevel::Sufficient, None),
+            "Funds: sufficient"
+        );
+    }
+
+    #[test]
+    fn tooltip_and_title_show_the_chosen_fields() {
+        let status = StatusResponse {
+            run_mode: RunMode::NotRunning,
+            destinations: vec![destination_state(
+                "exit-1",
+                Some("EU"),
+                RouteHealthState::Routable,
+            )],
+            target_destination: Some("exit-1".to_string()),
+            connected: Some(ConnectedInfo {
+                destination_id: "exit-1".to_string(),
+                since: SystemTime::UNIX_EPOCH,
+            }),
+            connecting: None,
+            reconnecting: None,
+            disconnecting: vec![],
+            latency: BTreeMap::new(),
+            scores: BTreeMap::new(),
+        };
+        let connected = ConnectionState::Connected("exit-1".to_string());
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(3900);
+        let values = TrayFieldValues::new(&status, &connected, now);
+        assert_eq!(values.location.as_deref(), Some("EU"));
+        assert_eq!(values.connected_for, Some(Duration::from_secs(3900)));
+        let values = TrayFieldValues {
+            latency_ms: Some(42),
+            ..values
+        };
+
+        let all = Settings::default().tray_tooltip_fields;
+        assert_eq!(
+            tooltip(&all, &values, &connected, FundsLevel::Low),
+            "Gnosis VPN\nConnected to exit-1\nLocation: EU\nConnected for 1h 05m\nLatency: 42 ms\nFunds: low"
+        );
+        let compact = [
+            TrayField::Location,
+            TrayField::Latency,
+            TrayField::FundsLevel,
+        ];
+        assert_eq!(
+            indicator_title(&compact, &values, FundsLevel::Sufficient).as_deref(),
+            Some("EU · 42ms")
+        );
+
+        let disconnected = TrayFieldValues::new(&status, &ConnectionState::Disconnected, now);
+        assert_eq!(
+            tooltip(
+                &all,
+                &disconnected,
+                &ConnectionState::Disconnected,
+                FundsLevel::Sufficient
+            ),
+            "Gnosis VPN\nDisconnected\nFunds: sufficient"
+        );
+        assert_eq!(
+            indicator_title(&compact, &disconnected, FundsLevel::Sufficient),
+            None
+        );
+    }
+
+    #[test]
+    fn exit_entries_follow_the_exit_list() {
+        let status = StatusResponse {
+            run_mode: RunMode::NotRunning,
+            destinations: vec![
+                destination_state("exit-a", None, RouteHealthState::NeedsChannel),
+                destination_state("exit-b", Some("EU"), ready_state(80)),
+                destination_state("exit-c", Some("US"), ready_state(42)),
+            ],
+            target_destination: Some("exit-b".to_string()),
+            connected: None,
+            connecting: None,
+            reconnecting: None,
+            disconnecting: vec![],
+            latency: BTreeMap::new(),
+            scores: BTreeMap::new(),
+        };
+
+        let entries = exit_entries(&status, SortOrder::Latency);
+        assert_eq!(
+            entries,
+            [
+                ExitEntry {
+                    id: "exit-c".to_string(),
+                    label: "exit-c · US · 42 ms".to_string(),
+                    checked: false,
+                },
+                ExitEntry {
+                    id: "exit-b".to_string(),
+                    label: "exit-b · EU · 80 ms".to_string(),
+                    checked: true,
+                },
+                ExitEntry {
+                    id: "exit-a".to_string(),
+                    label: "exit-a · needs channel".to_string(),
+                    checked: false,
+                },
+            ]
+        );
+    }
+}
@@ -3,9 +3,12 @@ use gnosis_vpn_lib::command::{self, HoprInitStatus, HoprStatus};
 
 use serde::Serialize;
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::{self, Display};
 
+use crate::latency::LatencyStats;
+use crate::score::ExitScore;
+
 // Sanitized library responses — only types that need reshaping for the UI layer live here.
 // For everything else, library types are used directly since their serde output already
 // matches what the TypeScript Zod layer expects.
@@ -19,6 +22,11 @@ pub struct StatusResponse {
     pub connecting: Option<command::ConnectingInfo>,
     pub reconnecting: Option<command::ReconnectingInfo>,
     pub disconnecting: Vec<command::DisconnectingInfo>,
+    /// Smoothed health-check latency per destination, filled in by the
+    /// status loop from its `LatencyHistory`.
+    pub latency: BTreeMap<String, LatencyStats>,
+    /// Composite exit score per ready destination, see `score.rs`.
+    pub scores: BTreeMap<String, ExitScore>,
 }
 
 #[derive(Debug, Clone)]
@@ -121,6 +129,14 @@ impl From<balance::FundingStatus> for TauriFundingStatus {
     }
 }
 
+/// wei per xDAI / wxHOPR; balances arrive as raw integer strings.
+pub const WEI: f64 = 1e18;
+
+/// A raw wei balance in whole tokens; `None` if it is not a number.
+pub fn tokens(wei: &str) -> Option<f64> {
+    wei.parse::<f64>().ok().map(|wei| wei / WEI)
+}
+
 #[derive(Clone, Debug, Serialize)]
 pub struct BalanceResponse {
     pub node: String,
@@ -297,6 +313,22 @@ impl From<command::BalanceResponse> for BalanceResponse {
     }
 }
 
+impl From<command::StatusResponse> for StatusResponse {
+    fn from(sr: command::StatusResponse) -> Self {
+        StatusResponse {
+            run_mode: sr.run_mode.into(),
+            destinations: sr.destinations,
+            target_destination: sr.target_destination,
+            connected: sr.connected,
+            connecting: sr.connecting,
+            reconnecting: sr.reconnecting,
+            disconnecting: sr.disconnecting,
+            latency: BTreeMap::new(),
+            scores: BTreeMap::new(),
+        }
+    }
+}
+
 impl From<&StatusResponse> for ConnectionState {
     fn from(sr: &StatusResponse) -> Self {
         if let Some(ref info) = sr.connected {
@@ -3,12 +3,17 @@
 
 use tauri::AppHandle;
 
+use crate::error::{AppError, ErrorCode};
+
 #[tauri::command]
 pub async fn get_toolkit_version() -> Option<String> {
     None
 }
 
 #[tauri::command]
-pub fn install_update(_app: AppHandle, _channel: String, _force: bool) -> Result<(), String> {
-    Err("UnsupportedPlatform".to_string())
+pub fn install_update(_app: AppHandle, _channel: String, _force: bool) -> Result<(), AppError> {
+    Err(AppError::new(
+        ErrorCode::UnsupportedPlatform,
+        "In-app updates are not available on this platform",
+    ))
 }
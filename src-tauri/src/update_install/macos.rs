@@ -10,6 +10,7 @@ use serde::Deserialize;
 use tauri::{AppHandle, Emitter, Manager, State};
 
 use super::{InstallStatus, UpdateInstallState};
+use crate::error::{AppError, ErrorCode};
 
 const UPDATER_PATH: &str = "/usr/local/bin/gnosis_vpn-update";
 const INSTALL_STATUS_EVENT: &str = "update-install-status";
@@ -83,7 +84,7 @@ pub async fn get_toolkit_version() -> Option<String> {
 /// exclusively through `update-install-status` events. `Err` means the run
 /// was not started (bad channel, already running, spawn failure).
 #[tauri::command]
-pub fn install_update(app: AppHandle, channel: String, force: bool) -> Result<(), String> {
+pub fn install_update(app: AppHandle, channel: String, force: bool) -> Result<(), AppError> {
     use std::collections::VecDeque;
     use std::io::BufRead;
     use std::process::{Command, Stdio};
@@ -91,16 +92,25 @@ pub fn install_update(app: AppHandle, channel: String, force: bool) -> Result<()
     const STDERR_TAIL_LINES: usize = 10;
 
     if channel != "stable" && channel != "snapshot" {
-        return Err("InvalidChannel".to_string());
+        return Err(AppError::new(
+            ErrorCode::InvalidChannel,
+            format!("Unknown update channel: {channel}"),
+        ));
     }
 
     // Claim the in-progress guard before spawning; every exit path of the
     // reader thread below ends in a terminal status, which re-arms it.
     {
         let state: State<UpdateInstallState> = app.state();
-        let mut guard = state.0.lock().map_err(|e| e.to_string())?;
+        let mut guard = state
+            .0
+            .lock()
+            .map_err(|e| AppError::internal(e.to_string()))?;
         if matches!(&*guard, Some(s) if !s.is_terminal()) {
-            return Err("InstallInProgress".to_string());
+            return Err(AppError::new(
+                ErrorCode::InstallInProgress,
+                "An update is already being installed",
+            ));
         }
         *guard = Some(InstallStatus::Checking);
     }
@@ -128,7 +138,10 @@ pub fn install_update(app: AppHandle, channel: String, force: bool) -> Result<()
                     error: e.to_string(),
                 },
             );
-            return Err(format!("Spawn: {e}"));
+            return Err(
+                AppError::new(ErrorCode::SpawnFailed, "Failed to start the updater")
+                    .with_details(e),
+            );
         }
     };
 
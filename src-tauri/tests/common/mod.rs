@@ -0,0 +1,150 @@
+//! In-process stand-in for the root daemon: serves scripted responses over a
+//! temporary Unix socket, so tests exercise the real socket client end to end.
+
+#![allow(dead_code)]
+
+// the unit tests' fixtures, compiled into each test binary
+#[path = "../../src/test_fixtures.rs"]
+pub mod fixtures;
+
+use gnosis_vpn_lib::command::{Command, Response};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{UnixListener, UnixStream};
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+
+static SOCKET_COUNTER: AtomicU32 = AtomicU32::new(0);
+
+pub fn temp_socket_path() -> PathBuf {
+    let unique = format!(
+        "gnosis_vpn_fake_daemon_{}_{}.sock",
+        std::process::id(),
+        SOCKET_COUNTER.fetch_add(1, Ordering::Relaxed)
+    );
+    std::env::temp_dir().join(unique)
+}
+
+/// Requests are routed by command name (the serde variant, e.g. "Status").
+/// Each name has a queue of responses; the last one repeats once the queue
+/// is drained, so a script only lists the transitions it cares about.
+#[derive(Default)]
+struct Script {
+    responses: HashMap<String, VecDeque<serde_json::Value>>,
+    received: Vec<String>,
+    /// Commands accepted but never answered, like a hung daemon.
+    stalled: HashSet<String>,
+}
+
+pub struct FakeDaemon {
+    path: PathBuf,
+    script: Arc<Mutex<Script>>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl FakeDaemon {
+    pub fn start() -> Self {
+        let path = temp_socket_path();
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixListener::bind(&path).expect("bind fake daemon socket");
+        let script = Arc::new(Mutex::new(Script::default()));
+        let task = tokio::spawn(serve(listener, script.clone()));
+        Self { path, script, task }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Appends responses for `command` (the variant name, e.g. "Status").
+    pub fn script(&self, command: &str, responses: impl IntoIterator<Item = Response>) {
+        let mut script = self.script.lock().unwrap();
+        let queue = script.responses.entry(command.to_string()).or_default();
+        for response in responses {
+            queue.push_back(serde_json::to_value(&response).expect("serializable response"));
+        }
+    }
+
+    /// Reads `command` requests but never answers them.
+    pub fn stall(&self, command: &str) {
+        self.script
+            .lock()
+            .unwrap()
+            .stalled
+            .insert(command.to_string());
+    }
+
+    /// Names of the commands received so far, in order.
+    pub fn received(&self) -> Vec<String> {
+        self.script.lock().unwrap().received.clone()
+    }
+}
+
+impl Drop for FakeDaemon {
+    fn drop(&mut self) {
+        self.task.abort();
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+async fn serve(listener: UnixListener, script: Arc<Mutex<Script>>) {
+    while let Ok((stream, _)) = listener.accept().await {
+        tokio::spawn(handle(stream, script.clone()));
+    }
+}
+
+// One request per connection. The request is read until it parses as a
+// complete JSON value, which works whether the client half-closes the
+// stream or terminates the request with a newline.
+async fn handle(mut stream: UnixStream, script: Arc<Mutex<Script>>) {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+    let request: serde_json::Value = loop {
+        let mut values =
+            serde_json::Deserializer::from_slice(&buf).into_iter::<serde_json::Value>();
+        match values.next() {
+            Some(Ok(value)) => break value,
+            Some(Err(e)) if !e.is_eof() => panic!("fake daemon: malformed request: {e}"),
+            _ => {}
+        }
+        match stream.read(&mut chunk).await {
+            Ok(0) | Err(_) => return,
+            Ok(n) => buf.extend_from_slice(&chunk[..n]),
+        }
+    };
+
+    let name = command_name(&request);
+    serde_json::from_value::<Command>(request).expect("request is a daemon command");
+    let response = {
+        let mut script = script.lock().unwrap();
+        script.received.push(name.clone());
+        if script.stalled.contains(&name) {
+            drop(script);
+            return std::future::pending().await;
+        }
+        match script.responses.get_mut(&name) {
+            Some(queue) if queue.len() > 1 => queue.pop_front(),
+            Some(queue) => queue.front().cloned(),
+            None => None,
+        }
+    };
+
+    // unscripted commands get a closed connection, like a crashed daemon
+    if let Some(response) = response {
+        let mut bytes = serde_json::to_vec(&response).expect("serializable response");
+        bytes.push(b'\n');
+        let _ = stream.write_all(&bytes).await;
+    }
+    let _ = stream.shutdown().await;
+}
+
+// Unit variants serialize as a string, data-carrying ones as a single-key object.
+fn command_name(request: &serde_json::Value) -> String {
+    match request {
+        serde_json::Value::String(name) => name.clone(),
+        serde_json::Value::Object(map) => map.keys().next().cloned().unwrap_or_default(),
+        other => other.to_string(),
+    }
+}
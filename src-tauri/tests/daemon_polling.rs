@@ -0,0 +1,376 @@
+// Drives the status/balance loop ticks against a scripted daemon on a temp
+// Unix socket, through the same socket client the app uses, and checks what
+// they publish to the app state cache.
+
+mod common;
+
+use common::FakeDaemon;
+use common::fixtures::{self, address};
+use gnosis_vpn_app_lib::AppStateCache;
+use gnosis_vpn_app_lib::daemon::{DaemonClient, DaemonSocket};
+use gnosis_vpn_app_lib::error::ErrorCode;
+use gnosis_vpn_app_lib::icons::{self, FundsLevel};
+use gnosis_vpn_app_lib::latency::{LatencyHistory, LatencyStats};
+use gnosis_vpn_app_lib::polling::{self, StatusTracker};
+use gnosis_vpn_app_lib::settings::{ExitPreference, Settings, SortOrder};
+use gnosis_vpn_app_lib::types::ConnectionState;
+use gnosis_vpn_lib::balance::{Balance, Balances, FundingLevel, FundingStatus, WxHOPR, XDai};
+use gnosis_vpn_lib::command::{self, Command, Response};
+
+use serde::Serialize;
+use tokio::sync::watch;
+
+use std::collections::{BTreeMap, HashMap};
+use std::time::{Duration, SystemTime};
+
+fn ready(id: &str, rtt_ms: u64) -> command::DestinationState {
+    fixtures::destination_state(id, Some("EU"), fixtures::ready_state(rtt_ms))
+}
+
+fn status(run_mode: command::RunMode) -> command::StatusResponse {
+    command::StatusResponse {
+        run_mode,
+        destinations: vec![],
+        target_destination: None,
+        connected: None,
+        connecting: None,
+        reconnecting: None,
+        disconnecting: vec![],
+    }
+}
+
+fn running() -> command::RunMode {
+    command::RunMode::Running {
+        funding_status: None,
+        hopr_status: None,
+    }
+}
+
+fn balance(traffic: FundingLevel) -> Response {
+    let info = command::Info {
+        node_address: address(),
+        node_peer_id: "16Uiu2HAmTest".to_string(),
+        safe_address: address(),
+    };
+    let balances = Balances {
+        node_xdai: Balance::<XDai>::zero(),
+        safe_wxhopr: Balance::<WxHOPR>::zero(),
+        channels_out: HashMap::new(),
+    };
+    let funding = FundingStatus {
+        traffic,
+        gas: FundingLevel::Good,
+        wxhopr_deficit: None,
+        xdai_deficit: None,
+    };
+    Response::Balance(Ok(command::BalanceResponse::build(
+        &info,
+        &balances,
+        &HashMap::new(),
+        None,
+        None,
+        Some(funding),
+    )))
+}
+
+fn info(version: &str) -> Response {
+    Response::Info(command::InfoResponse {
+        version: version.to_string(),
+        log_file: None,
+        package_version: None,
+    })
+}
+
+fn client_for(daemon: &FakeDaemon) -> DaemonSocket {
+    let path = daemon.path().to_string_lossy().into_owned();
+    DaemonSocket::new(None, Some(&path))
+}
+
+fn cache() -> AppStateCache {
+    AppStateCache {
+        status: watch::channel(None).0,
+        balance: watch::channel(None).0,
+        service_info: watch::channel(None).0,
+    }
+}
+
+/// The cached result as the `status`/`balance` event serializes it.
+fn published<T: Serialize>(slot: &watch::Sender<Option<T>>) -> serde_json::Value {
+    let cached = slot.borrow();
+    serde_json::to_value(cached.as_ref().expect("tick published a result")).unwrap()
+}
+
+#[tokio::test]
+async fn status_ticks_follow_daemon_from_not_running_to_connected() {
+    let daemon = FakeDaemon::start();
+    let warming = status(command::RunMode::Init { last_error: None });
+    let mut ready_to_connect = status(running());
+    ready_to_connect.destinations = vec![ready("slow-exit", 90), ready("fast-exit", 12)];
+    let mut connected = status(running());
+    connected.destinations = vec![ready("slow-exit", 90), ready("fast-exit", 12)];
+    connected.target_destination = Some("fast-exit".to_string());
+    connected.connected = Some(command::ConnectedInfo {
+        destination_id: "fast-exit".to_string(),
+        since: SystemTime::UNIX_EPOCH,
+    });
+    daemon.script(
+        "Status",
+        [
+            Response::Status(status(command::RunMode::NotRunning)),
+            Response::Status(warming),
+            Response::Status(ready_to_connect),
+            Response::Status(connected),
+        ],
+    );
+    daemon.script(
+        "Connect",
+        [Response::Connect(command::ConnectResponse::Connecting(
+            fixtures::destination("fast-exit", Some("EU")),
+        ))],
+    );
+
+    let client = client_for(&daemon);
+    let settings = Settings {
+        connect_on_startup: true,
+        ..Settings::default()
+    };
+    let cache = cache();
+    let latency = LatencyHistory::default();
+    let mut backoff = polling::unresponsive_backoff();
+    let mut tracker = StatusTracker::default();
+
+    // NotRunning: published as-is, and the loop has to re-initialize
+    let (needs_reinit, delay, _) =
+        polling::status_tick(&client, &mut backoff, &latency, &cache).await;
+    assert!(needs_reinit);
+    assert_eq!(delay, Duration::from_secs(5));
+    assert_eq!(published(&cache.status)["Ok"]["run_mode"], "NotRunning");
+
+    // Warmup: no exit is usable yet, so auto-connect stays undecided
+    let (needs_reinit, _, payload) =
+        polling::status_tick(&client, &mut backoff, &latency, &cache).await;
+    assert!(!needs_reinit);
+    let warmup = payload.unwrap().unwrap();
+    let event = published(&cache.status);
+    assert_eq!(event["Ok"]["run_mode"]["Warmup"]["status"], "Initializing");
+    let effects = tracker.on_status(&warmup, None, &settings);
+    assert!(matches!(effects.conn_state, ConnectionState::Disconnected));
+    assert_eq!(effects.app_icon, icons::APP_ICON_DISCONNECTED);
+    assert!(effects.startup_target.is_none());
+
+    // Running with two ready exits: the lower-latency one is picked once
+    let (_, delay, payload) = polling::status_tick(&client, &mut backoff, &latency, &cache).await;
+    assert_eq!(delay, Duration::from_secs(2));
+    let running = payload.unwrap().unwrap();
+    // the published status carries the derived latency and score fields
+    let event = published(&cache.status);
+    assert_eq!(event["Ok"]["latency"]["fast-exit"]["last_ms"], 12);
+    assert!(event["Ok"]["scores"]["fast-exit"].is_object());
+    let effects = tracker.on_status(&running, None, &settings);
+    assert_eq!(effects.startup_target.as_deref(), Some("fast-exit"));
+    assert!(
+        tracker
+            .on_status(&running, None, &settings)
+            .startup_target
+            .is_none()
+    );
+
+    let cmd = Command::Connect("fast-exit".to_string());
+    let resp = client.process_cmd(&cmd).await.unwrap();
+    assert!(matches!(
+        resp,
+        Response::Connect(command::ConnectResponse::Connecting(_))
+    ));
+
+    // Connected: tray and dock switch to the connected artwork
+    let (_, _, payload) = polling::status_tick(&client, &mut backoff, &latency, &cache).await;
+    let connected = payload.unwrap().unwrap();
+    let event = published(&cache.status);
+    assert_eq!(event["Ok"]["connected"]["destination_id"], "fast-exit");
+    let effects = tracker.on_status(&connected, None, &settings);
+    assert!(matches!(effects.conn_state, ConnectionState::Connected(ref id) if id == "fast-exit"));
+    assert_eq!(effects.funds_level, FundsLevel::Sufficient);
+    assert_eq!(effects.app_icon, icons::APP_ICON_CONNECTED);
+    assert_eq!(
+        effects.tray_icon,
+        icons::determine_tray_icon(&effects.conn_state, FundsLevel::Sufficient)
+    );
+
+    assert_eq!(
+        daemon.received(),
+        ["Status", "Status", "Status", "Connect", "Status"]
+    );
+}
+
+#[test]
+fn startup_selection_walks_preferences_skipping_unready_exits() {
+    let us_fast = fixtures::destination_state("us-fast", Some("US"), fixtures::ready_state(5));
+    let mut pinned_down = ready("pinned", 1);
+    pinned_down.route_health = None;
+    let destinations = vec![
+        us_fast,
+        pinned_down,
+        ready("eu-slow", 80),
+        ready("eu-fast", 40),
+    ];
+    let eu = ExitPreference::Meta(BTreeMap::from([("location".to_string(), "EU".to_string())]));
+
+    // the pinned exit is down, so the EU filter decides, by latency
+    let preferences = [ExitPreference::Id("pinned".to_string()), eu];
+    assert_eq!(
+        polling::pick_startup_target(
+            &destinations,
+            &BTreeMap::new(),
+            &preferences,
+            SortOrder::Latency
+        )
+        .as_deref(),
+        Some("eu-fast")
+    );
+    // a ready exact match wins over faster exits
+    let preferences = [ExitPreference::Id("eu-slow".to_string())];
+    assert_eq!(
+        polling::pick_startup_target(
+            &destinations,
+            &BTreeMap::new(),
+            &preferences,
+            SortOrder::Latency
+        )
+        .as_deref(),
+        Some("eu-slow")
+    );
+    // nothing matches: fastest ready exit overall
+    let apac = ExitPreference::Meta(BTreeMap::from([(
+        "location".to_string(),
+        "APAC".to_string(),
+    )]));
+    assert_eq!(
+        polling::pick_startup_target(&destinations, &BTreeMap::new(), &[apac], SortOrder::Latency)
+            .as_deref(),
+        Some("us-fast")
+    );
+}
+
+#[test]
+fn startup_selection_ranks_by_smoothed_latency() {
+    // eu-fast just answered one quick ping but is usually slower than eu-slow
+    let destinations = vec![ready("eu-slow", 80), ready("eu-fast", 40)];
+    let stats = |smoothed_ms| LatencyStats {
+        samples: 10,
+        last_ms: 0,
+        smoothed_ms,
+        jitter_ms: 0,
+    };
+    let latency = BTreeMap::from([
+        ("eu-slow".to_string(), stats(60)),
+        ("eu-fast".to_string(), stats(120)),
+    ]);
+    assert_eq!(
+        polling::pick_startup_target(&destinations, &latency, &[], SortOrder::Latency).as_deref(),
+        Some("eu-slow")
+    );
+}
+
+#[tokio::test]
+async fn balance_ticks_drive_funds_level_and_icons() {
+    let daemon = FakeDaemon::start();
+    daemon.script(
+        "Balance",
+        [Response::WorkerOffline, balance(FundingLevel::Low)],
+    );
+    let client = client_for(&daemon);
+    let cache = cache();
+    let mut backoff = polling::unresponsive_backoff();
+
+    // worker not up yet: an empty balance is published, retry soon
+    let delay = polling::balance_tick(&client, &mut backoff, &cache).await;
+    assert_eq!(delay, Duration::from_secs(5));
+    assert!(matches!(*cache.balance.borrow(), Some(Ok(None))));
+
+    let delay = polling::balance_tick(&client, &mut backoff, &cache).await;
+    assert_eq!(delay, Duration::from_secs(60));
+    let event = published(&cache.balance);
+    assert_eq!(event["Ok"]["funding_status"]["traffic"], "Low");
+    let balance = cache.balance.borrow().clone().unwrap().unwrap().unwrap();
+
+    let mut connected = gnosis_vpn_app_lib::types::StatusResponse::from(status(running()));
+    connected.connected = Some(command::ConnectedInfo {
+        destination_id: "test-exit".to_string(),
+        since: SystemTime::UNIX_EPOCH,
+    });
+    let effects =
+        StatusTracker::default().on_status(&connected, Some(&balance), &Settings::default());
+    assert_eq!(effects.funds_level, FundsLevel::Low);
+    assert_eq!(effects.app_icon, icons::APP_ICON_CONNECTED_LOW_FUNDS);
+    assert_eq!(
+        effects.tray_icon,
+        icons::determine_tray_icon(&effects.conn_state, FundsLevel::Low)
+    );
+}
+
+#[tokio::test]
+async fn init_rejects_incompatible_daemon() {
+    let daemon = FakeDaemon::start();
+    daemon.script("Info", [info("0.1.0")]);
+
+    let err = polling::init_service(&client_for(&daemon))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code, ErrorCode::IncompatibleService);
+    assert!(
+        err.message
+            .starts_with("Incompatible service version: 0.1.0"),
+        "{err}"
+    );
+    // the worker is never started against an incompatible daemon
+    assert_eq!(daemon.received(), ["Info"]);
+}
+
+#[tokio::test]
+async fn unreachable_daemon_surfaces_as_status_error() {
+    let daemon = FakeDaemon::start();
+    let client = client_for(&daemon);
+    drop(daemon);
+
+    let err = polling::init_service(&client).await.unwrap_err();
+    assert_eq!(err.code, ErrorCode::DaemonUnreachable);
+    assert_eq!(err.message, "Failed to get service info");
+
+    let (needs_reinit, delay, payload) = polling::query_status(&client).await;
+    assert!(!needs_reinit);
+    assert_eq!(delay, Duration::from_secs(2));
+    let event = serde_json::to_value(&payload).unwrap();
+    assert_eq!(event["Err"]["code"], "DaemonUnreachable");
+    assert!(event["Err"]["details"].is_string());
+}
+
+#[tokio::test(start_paused = true)]
+async fn hung_daemon_times_out_instead_of_stalling_the_loop() {
+    let daemon = FakeDaemon::start();
+    daemon.stall("Status");
+    let client = client_for(&daemon);
+
+    let (needs_reinit, _, payload) = polling::query_status(&client).await;
+    assert!(!needs_reinit);
+    let err = payload.unwrap_err();
+    assert_eq!(err.code, ErrorCode::DaemonTimeout);
+    assert!(err.is_unresponsive());
+    // Status is safe to repeat, so it gets one retry before giving up
+    assert_eq!(daemon.received(), ["Status", "Status"]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn slow_connect_is_awaited_not_timed_out_or_retried() {
+    let daemon = FakeDaemon::start();
+    daemon.stall("Connect");
+    let client = client_for(&daemon);
+
+    // a timeout could report a failure the daemon then turns into a connection
+    let pending = tokio::time::timeout(
+        Duration::from_secs(600),
+        client.process_cmd(&Command::Connect("test-exit".to_string())),
+    )
+    .await;
+    assert!(pending.is_err(), "connect gave up on its own: {pending:?}");
+    assert_eq!(daemon.received(), ["Connect"]);
+}
@@ -0,0 +1,161 @@
+//! The `org.gnosis.VPN` service on a private `dbus-daemon`, driven through
+//! its channels the way the app's status loop and call handler drive it.
+//! Skipped where no `dbus-daemon` binary is installed.
+#![cfg(target_os = "linux")]
+
+use futures_core::Stream;
+use gnosis_vpn_app_lib::dbus::{self, BUS_NAME, DbusCall, OBJECT_PATH, VpnProperties};
+use gnosis_vpn_app_lib::icons::FundsLevel;
+use gnosis_vpn_app_lib::types::{ConnectionState, RunMode};
+use tokio::sync::{mpsc, watch};
+use zbus::{Connection, Proxy};
+
+use std::io::{BufRead, BufReader};
+use std::pin::Pin;
+use std::process::{Child, Command, Stdio};
+use std::time::Duration;
+
+struct PrivateBus {
+    daemon: Child,
+    address: String,
+}
+
+impl PrivateBus {
+    fn start() -> Option<Self> {
+        let mut daemon = Command::new("dbus-daemon")
+            .args(["--session", "--nofork", "--print-address"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .inspect_err(|e| eprintln!("skipping: cannot start dbus-daemon: {e}"))
+            .ok()?;
+        let mut address = String::new();
+        BufReader::new(daemon.stdout.take()?)
+            .read_line(&mut address)
+            .ok()?;
+        Some(Self {
+            daemon,
+            address: address.trim().to_string(),
+        })
+    }
+
+    async fn connect(&self) -> Connection {
+        zbus::connection::Builder::address(self.address.as_str())
+            .unwrap()
+            .build()
+            .await
+            .unwrap()
+    }
+}
+
+impl Drop for PrivateBus {
+    fn drop(&mut self) {
+        let _ = self.daemon.kill();
+        let _ = self.daemon.wait();
+    }
+}
+
+async fn next<S: Stream + Unpin>(stream: &mut S) -> Option<S::Item> {
+    let next = std::future::poll_fn(|cx| Pin::new(&mut *stream).poll_next(cx));
+    tokio::time::timeout(Duration::from_secs(5), next)
+        .await
+        .expect("timed out waiting for D-Bus")
+}
+
+/// Skips the current value the stream may start with.
+async fn wait_for(changes: &mut zbus::proxy::PropertyStream<'_, String>, expected: &str) {
+    loop {
+        let changed = next(changes).await.expect("property stream ended");
+        if changed.get().await.unwrap() == expected {
+            return;
+        }
+    }
+}
+
+#[tokio::test]
+async fn serves_properties_signals_and_methods() {
+    let Some(bus) = PrivateBus::start() else {
+        return;
+    };
+    let service = bus.connect().await;
+    let client = bus.connect().await;
+
+    let (props_tx, props_rx) = watch::channel(VpnProperties::new(
+        &ConnectionState::Disconnected,
+        FundsLevel::Sufficient,
+        &RunMode::NotRunning,
+    ));
+    let (calls_tx, mut calls_rx) = mpsc::channel(4);
+    dbus::register(&service, props_rx.clone(), calls_tx)
+        .await
+        .unwrap();
+    let publisher = service.clone();
+    tokio::spawn(async move { dbus::publish_changes(&publisher, props_rx).await });
+
+    let proxy = Proxy::new(&client, BUS_NAME, OBJECT_PATH, BUS_NAME)
+        .await
+        .unwrap();
+    let state: String = proxy.get_property("ConnectionState").await.unwrap();
+    assert_eq!(state, "Disconnected");
+    let run_mode: String = proxy.get_property("RunMode").await.unwrap();
+    assert_eq!(run_mode, "NotRunning");
+
+    // PropertiesChanged, as the status loop would trigger it
+    let mut state_changes = proxy
+        .receive_property_changed::<String>("ConnectionState")
+        .await;
+    let mut destination_changes = proxy
+        .receive_property_changed::<String>("Destination")
+        .await;
+    let mut funds_changes = proxy.receive_property_changed::<String>("FundsLevel").await;
+    props_tx.send_replace(VpnProperties::new(
+        &ConnectionState::Connected("exit-1".to_string()),
+        FundsLevel::Low,
+        &RunMode::NotRunning,
+    ));
+    wait_for(&mut state_changes, "Connected").await;
+    wait_for(&mut destination_changes, "exit-1").await;
+    wait_for(&mut funds_changes, "Low").await;
+
+    // methods reach the app's call handler, whose answer is the reply
+    let handler = tokio::spawn(async move {
+        let mut seen = Vec::new();
+        while let Some(call) = calls_rx.recv().await {
+            match call {
+                DbusCall::Connect(id, reply) => {
+                    let result = if id == "exit-1" {
+                        Ok(())
+                    } else {
+                        Err("No such exit".to_string())
+                    };
+                    seen.push(format!("connect {id}"));
+                    let _ = reply.send(result);
+                }
+                DbusCall::Disconnect(reply) => {
+                    seen.push("disconnect".to_string());
+                    let _ = reply.send(Ok(()));
+                }
+                DbusCall::ShowWindow => {
+                    seen.push("show".to_string());
+                    return seen;
+                }
+            }
+        }
+        seen
+    });
+    proxy
+        .call::<_, _, ()>("Connect", &("exit-1",))
+        .await
+        .unwrap();
+    let error = proxy
+        .call::<_, _, ()>("Connect", &("nowhere",))
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("No such exit"), "{error}");
+    proxy.call::<_, _, ()>("Disconnect", &()).await.unwrap();
+    proxy.call::<_, _, ()>("ShowWindow", &()).await.unwrap();
+    assert_eq!(
+        handler.await.unwrap(),
+        ["connect exit-1", "connect nowhere", "disconnect", "show"]
+    );
+}
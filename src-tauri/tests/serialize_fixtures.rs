@@ -1,4 +1,13 @@
-use gnosis_vpn_app_lib::settings::{FlagDisplay, Settings, SortOrder, UpdateChannel};
+use gnosis_vpn_app_lib::auto_connect::{AutoConnectEvent, Trigger};
+use gnosis_vpn_app_lib::error::AppError;
+use gnosis_vpn_app_lib::latency::{LatencyHistory, LatencyStats};
+use gnosis_vpn_app_lib::notifications;
+use gnosis_vpn_app_lib::score::ExitScore;
+use gnosis_vpn_app_lib::sessions::{EndReason, Session};
+use gnosis_vpn_app_lib::settings::{
+    ExitPreference, FlagDisplay, NotificationSettings, QuietHours, Settings, SortOrder, TrayField,
+    UpdateChannel,
+};
 use gnosis_vpn_app_lib::types;
 use gnosis_vpn_app_lib::update_install::InstallStatus;
 use gnosis_vpn_lib::balance::{
@@ -13,7 +22,7 @@ use gnosis_vpn_lib::route_health::{
     ExitHealth, Health, LoadAvg, RouteHealthState, Slots, UnrecoverableReason, Versions,
 };
 use gnosis_vpn_lib::{command, connection};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
 use std::time::{Duration, SystemTime};
 
@@ -80,6 +89,8 @@ fn status_base(run_mode: types::RunMode) -> types::StatusResponse {
         connecting: None,
         reconnecting: None,
         disconnecting: vec![],
+        latency: BTreeMap::new(),
+        scores: BTreeMap::new(),
     }
 }
 
@@ -212,6 +223,25 @@ fn generate_fixtures() {
                 since: SystemTime::UNIX_EPOCH,
                 phase: connection::DownPhase::Disconnecting,
             }],
+            latency: BTreeMap::from([(
+                "test-exit".to_string(),
+                LatencyStats {
+                    samples: 12,
+                    last_ms: 48,
+                    smoothed_ms: 42,
+                    jitter_ms: 5,
+                },
+            )]),
+            scores: BTreeMap::from([(
+                "test-exit".to_string(),
+                ExitScore {
+                    score: 0.7625,
+                    rtt: 0.5,
+                    load: 1.0,
+                    slots: 0.75,
+                    stability: 1.0,
+                },
+            )]),
         },
     );
 
@@ -267,6 +297,8 @@ fn generate_fixtures() {
             connecting: None,
             reconnecting: None,
             disconnecting: vec![],
+            latency: BTreeMap::new(),
+            scores: BTreeMap::new(),
         },
     );
 
@@ -436,36 +468,190 @@ fn generate_fixtures() {
             error: "connection reset".to_string(),
         },
     );
+
+    // command rejections and `status` error events (see appError.test.ts)
+    write(
+        &fixtures_dir,
+        "error_vpn_not_connected.json",
+        &AppError::from(check_update::Error::VpnNotConnected),
+    );
+    write(
+        &fixtures_dir,
+        "status_event_error.json",
+        &Err::<Option<types::StatusResponse>, AppError>(AppError::daemon("connection refused")),
+    );
+
+    write(
+        &fixtures_dir,
+        "auto_connect_events.json",
+        &vec![
+            AutoConnectEvent::Attempt {
+                destination_id: "exit-1".to_string(),
+                trigger: Trigger::Dropped,
+                attempt: 2,
+                delay_ms: 4000,
+            },
+            AutoConnectEvent::Succeeded {
+                destination_id: "exit-1".to_string(),
+                attempts: 2,
+            },
+            AutoConnectEvent::GaveUp {
+                destination_id: "exit-1".to_string(),
+                attempts: 5,
+            },
+            AutoConnectEvent::Cancelled,
+        ],
+    );
+
+    // One session per end reason keeps every variant covered.
+    let session = |end_reason, connected: bool| Session {
+        destination_id: "exit-1".to_string(),
+        meta: BTreeMap::from([("location".to_string(), "EU".to_string())]),
+        started_at: 1_720_000_000_000,
+        connected_at: connected.then_some(1_720_000_002_500),
+        ended_at: 1_720_000_600_000,
+        time_to_connect_ms: connected.then_some(2_500),
+        reconnect_count: u32::from(connected),
+        end_reason,
+        end_detail: (end_reason == EndReason::FailedOver)
+            .then(|| "3 failed health checks (ping timeout) for 60s".to_string()),
+    };
+    write(
+        &fixtures_dir,
+        "session_history.json",
+        &vec![
+            session(EndReason::UserDisconnect, true),
+            session(EndReason::ConnectFailed, false),
+            session(EndReason::Dropped, true),
+            session(EndReason::Switched, true),
+            session(EndReason::FailedOver, true),
+            session(EndReason::ServiceStopped, true),
+            session(EndReason::AppExit, true),
+        ],
+    );
+
+    let ready = |exit: ExitHealth| command::DestinationState {
+        destination: destination(),
+        route_health: Some(route_health_view(RouteHealthState::ReadyToConnect { exit })),
+    };
+    let history = LatencyHistory::default();
+    history.observe(&[ready(exit_health())]);
+    history.observe(&[ready(ExitHealth {
+        checked_at: SystemTime::UNIX_EPOCH + Duration::from_secs(30),
+        ping_rtt: Duration::from_millis(58),
+        ..exit_health()
+    })]);
+    write(&fixtures_dir, "latency_history.json", &history.snapshot());
+
+    // Shared with updateAvailability.test.ts so the update notification and
+    // the in-app banner agree on what is an update.
+    let manifest = manifest(release("0.29.0"), release("0.29.0+build.12"));
+    let cases = [
+        ("0.28.5", None, None, Some("0.29.0")),
+        ("0.29.0", None, None, None),
+        ("0.29.1", None, None, None),
+        ("0.29.0+build.3", None, None, Some("0.29.0+build.12")),
+        ("0.29.0+build.12", None, None, None),
+        ("0.29.0-rc.1", None, None, Some("0.29.0+build.12")),
+        (
+            "0.29.0+build.3",
+            Some(UpdateChannel::Stable),
+            None,
+            Some("0.29.0"),
+        ),
+        (
+            "0.29.0",
+            Some(UpdateChannel::Snapshot),
+            None,
+            Some("0.29.0+build.12"),
+        ),
+        ("0.28.5", None, Some("0.29.0"), None),
+    ]
+    .map(|(package_version, channel, dismissed, expected)| {
+        let settings = Settings {
+            update_manifest: Some(manifest.clone()),
+            channel,
+            dismissed_update_version: dismissed.map(str::to_string),
+            ..Settings::default()
+        };
+        assert_eq!(
+            notifications::available_update(package_version, &settings).as_deref(),
+            expected,
+            "{package_version} on {channel:?}"
+        );
+        UpdateCase {
+            package_version,
+            channel,
+            dismissed_version: dismissed,
+            available_version: expected,
+        }
+    });
+    write(
+        &fixtures_dir,
+        "update_availability.json",
+        &UpdateCases {
+            manifest,
+            cases: Vec::from(cases),
+        },
+    );
 }
 
-fn full_settings() -> Settings {
-    // Built via deserialization so the manifest field types (Timestamp, Url,
-    // ByteSize, Hash) don't require their crates as test dependencies;
-    // from_value fails loudly if the wire format drifts.
-    let manifest: check_update::Manifest = serde_json::from_value(serde_json::json!({
+#[derive(serde::Serialize)]
+struct UpdateCases {
+    manifest: check_update::Manifest,
+    cases: Vec<UpdateCase>,
+}
+
+/// The inputs of `evaluateUpdate` and the version it should offer.
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UpdateCase {
+    package_version: &'static str,
+    channel: Option<UpdateChannel>,
+    dismissed_version: Option<&'static str>,
+    available_version: Option<&'static str>,
+}
+
+fn release(version: &str) -> serde_json::Value {
+    serde_json::json!({
+        "version": version,
+        "published_at": "2026-07-01T12:00:00Z",
+        "download_url": format!("https://download.gnosisvpn.io/app/{version}.AppImage"),
+        "size_bytes": 123456789,
+        "sha256": "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+        "artifact_signature": "sig",
+        "release_notes": "notes",
+        "min_os_version": "10.15",
+        "min_app_version": "0.28.0"
+    })
+}
+
+// Built via deserialization so the manifest field types (Timestamp, Url,
+// ByteSize, Hash) don't require their crates as test dependencies;
+// from_value fails loudly if the wire format drifts.
+fn manifest(stable: serde_json::Value, snapshot: serde_json::Value) -> check_update::Manifest {
+    serde_json::from_value(serde_json::json!({
         "schema_version": 1,
         "generated_at": "2026-07-06T00:00:00Z",
-        "channels": {
-            "stable": {
-                "version": "0.29.0",
-                "published_at": "2026-07-01T12:00:00Z",
-                "download_url": "https://download.gnosisvpn.io/app/0.29.0.AppImage",
-                "size_bytes": 123456789,
-                "sha256": "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
-                "artifact_signature": "sig",
-                "release_notes": "notes",
-                "min_os_version": "10.15",
-                "min_app_version": "0.28.0"
-            },
-            "snapshot": null
-        }
+        "channels": { "stable": stable, "snapshot": snapshot }
     }))
-    .expect("valid manifest fixture");
+    .expect("valid manifest fixture")
+}
+
+fn full_settings() -> Settings {
+    let manifest = manifest(release("0.29.0"), serde_json::Value::Null);
 
     Settings {
-        preferred_location: Some("exit-1".to_string()),
+        preferred_exits: vec![
+            ExitPreference::Id("exit-1".to_string()),
+            ExitPreference::Meta(BTreeMap::from([("location".to_string(), "EU".to_string())])),
+        ],
         connect_on_startup: true,
+        always_on: true,
+        exit_failover: true,
+        failover_after_secs: 90,
         start_minimized: true,
+        launch_at_login: true,
         update_check: true,
         exit_node_sort_order: SortOrder::Alpha,
         last_checked_at: Some(1_720_000_000_000),
@@ -475,5 +661,21 @@ fn full_settings() -> Settings {
         installed_version: Some("0.28.5".to_string()),
         show_detailed_metrics: true,
         flag_display: FlagDisplay::Mono,
+        daemon_socket_path: Some("/run/gnosisvpn-staging/gnosisvpn.sock".to_string()),
+        metrics_endpoint: true,
+        metrics_port: 9100,
+        notifications: NotificationSettings {
+            connected: false,
+            disconnected: true,
+            funds_low: true,
+            safe_funded: false,
+            update_available: true,
+            quiet_hours: Some(QuietHours {
+                start: "22:00".to_string(),
+                end: "07:00".to_string(),
+            }),
+        },
+        tray_tooltip_fields: vec![TrayField::Destination, TrayField::FundsLevel],
+        tray_title_fields: vec![TrayField::Location, TrayField::Latency],
     }
 }
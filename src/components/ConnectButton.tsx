@@ -21,7 +21,12 @@ export default function ConnectButton() {
     return resolveAutoDestination(
       appState.availableDestinations,
       appState.destinations,
-      settings.preferredLocation,
+      settings.preferredExits,
+      {
+        latency: appState.latency,
+        scores: appState.scores,
+        order: settings.exitNodeSortOrder,
+      },
     )?.id ?? appState.availableDestinations[0]?.id;
   });
 
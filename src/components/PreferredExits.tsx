@@ -0,0 +1,121 @@
+import { createMemo, For, Show } from "solid-js";
+import { Dropdown } from "./common/Dropdown.tsx";
+import { useAppStore } from "../stores/appStore.ts";
+import {
+  type ExitPreference,
+  useSettingsStore,
+} from "../stores/settingsStore.ts";
+import { destinationLabel, preferenceLabel } from "../utils/destinations.ts";
+
+type AddOption = { preference: ExitPreference; label: string };
+
+const samePreference = (a: ExitPreference, b: ExitPreference) =>
+  JSON.stringify(a) === JSON.stringify(b);
+
+/**
+ * Ordered exit preferences for automatic selection: exact exits and
+ * location filters. The first ready match wins; the rest fall back to
+ * latency.
+ */
+export default function PreferredExits() {
+  const [appState] = useAppStore();
+  const [settings, settingsActions] = useSettingsStore();
+
+  const addOptions = createMemo<AddOption[]>(() => {
+    const locations = [
+      ...new Set(
+        appState.availableDestinations
+          .map((d) => d.meta?.location)
+          .filter((l): l is string => !!l),
+      ),
+    ].sort();
+    const options: AddOption[] = [
+      ...locations.map((location) => ({
+        preference: { meta: { location } },
+        label: `Any exit in ${location}`,
+      })),
+      ...appState.availableDestinations.map((d) => ({
+        preference: { id: d.id },
+        label: destinationLabel(d),
+      })),
+    ];
+    return options.filter((o) =>
+      !settings.preferredExits.some((p) => samePreference(p, o.preference))
+    );
+  });
+
+  const save = (preferences: ExitPreference[]) =>
+    void settingsActions.setPreferredExits(preferences);
+
+  const moveUp = (index: number) => {
+    const next = [...settings.preferredExits];
+    [next[index - 1], next[index]] = [next[index], next[index - 1]];
+    save(next);
+  };
+
+  const remove = (index: number) =>
+    save(settings.preferredExits.filter((_, i) => i !== index));
+
+  return (
+    <div class="flex flex-col gap-2 text-text-primary">
+      <div class="flex items-center justify-between gap-2">
+        Preferred exits
+        <Show
+          when={addOptions().length > 0}
+          fallback={
+            <div class="text-sm text-text-secondary">No servers available</div>
+          }
+        >
+          <Dropdown
+            options={addOptions()}
+            value={null}
+            placeholder="Add preference"
+            onChange={(o) => save([...settings.preferredExits, o.preference])}
+            size="sm"
+            itemToString={(o) => o.label}
+          />
+        </Show>
+      </div>
+      <Show
+        when={settings.preferredExits.length > 0}
+        fallback={
+          <div class="text-xs text-text-secondary">
+            No preference: the fastest available exit is used
+          </div>
+        }
+      >
+        <ol class="flex flex-col gap-1 text-sm">
+          <For each={settings.preferredExits}>
+            {(preference, index) => (
+              <li class="flex items-center justify-between gap-2">
+                <span class="truncate">
+                  {index() + 1}.{" "}
+                  {preferenceLabel(preference, appState.availableDestinations)}
+                </span>
+                <span class="flex gap-1">
+                  <button
+                    type="button"
+                    class="px-2 text-text-secondary enabled:cursor-pointer disabled:opacity-30"
+                    disabled={index() === 0}
+                    aria-label="Move up"
+                    onClick={() => moveUp(index())}
+                  >
+                    ↑
+                  </button>
+                  <button
+                    type="button"
+                    class="px-2 text-text-secondary cursor-pointer"
+                    aria-label="Remove"
+                    onClick={() => remove(index())}
+                  >
+                    ✕
+                  </button>
+                </span>
+              </li>
+            )}
+          </For>
+        </ol>
+      </Show>
+    </div>
+  );
+}
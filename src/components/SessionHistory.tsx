@@ -0,0 +1,170 @@
+import {
+  createSignal,
+  For,
+  Match,
+  onCleanup,
+  onMount,
+  Show,
+  Switch,
+} from "solid-js";
+import { listen } from "@tauri-apps/api/event";
+import { save } from "@tauri-apps/plugin-dialog";
+import { downloadDir, join } from "@tauri-apps/api/path";
+import {
+  type EndReason,
+  exportSessionHistory,
+  getSessionHistory,
+  type Session,
+  SESSION_RECORDED_EVENT,
+  sessionDurationMs,
+  type SessionExportFormat,
+} from "../services/sessionHistory.ts";
+import { errorMessage } from "../services/appError.ts";
+import Button from "./common/Button.tsx";
+
+const RECENT_COUNT = 5;
+
+const END_REASON_LABELS: Record<EndReason, string> = {
+  UserDisconnect: "Disconnected",
+  ConnectFailed: "Failed to connect",
+  Dropped: "Dropped",
+  Switched: "Switched exit",
+  FailedOver: "Switched (exit unhealthy)",
+  ServiceStopped: "Service stopped",
+  AppExit: "App closed",
+};
+
+function formatDuration(ms: number): string {
+  const minutes = Math.floor(ms / 60_000);
+  if (minutes < 1) return `${Math.floor(ms / 1000)}s`;
+  if (minutes < 60) return `${minutes}m`;
+  return `${Math.floor(minutes / 60)}h ${minutes % 60}m`;
+}
+
+export default function SessionHistory() {
+  const [sessions, setSessions] = createSignal<Session[]>([]);
+  const [loading, setLoading] = createSignal(false);
+  const [savedPath, setSavedPath] = createSignal<string | null>(null);
+  const [error, setError] = createSignal<string | null>(null);
+
+  const recent = () => sessions().slice(-RECENT_COUNT).reverse();
+
+  async function refresh() {
+    try {
+      setSessions(await getSessionHistory());
+    } catch (e) {
+      setError(errorMessage(e));
+    }
+  }
+
+  let unlistenRecorded: (() => void) | undefined;
+  let disposed = false;
+
+  onMount(() => {
+    void refresh();
+    void listen(SESSION_RECORDED_EVENT, () => void refresh()).then(
+      (unlisten) => {
+        if (disposed) unlisten();
+        else unlistenRecorded = unlisten;
+      },
+    );
+  });
+
+  onCleanup(() => {
+    disposed = true;
+    unlistenRecorded?.();
+  });
+
+  async function onExport(format: SessionExportFormat) {
+    setLoading(true);
+    setError(null);
+    setSavedPath(null);
+    try {
+      const date = new Date().toISOString().slice(0, 10);
+      const defaultPath = await join(
+        await downloadDir(),
+        `gnosis_vpn-sessions-${date}.${format}`,
+      );
+      const dest = await save({
+        defaultPath,
+        filters: [{ name: format.toUpperCase(), extensions: [format] }],
+      });
+      if (!dest) {
+        setError("Export canceled");
+        return;
+      }
+      await exportSessionHistory(dest, format);
+      setSavedPath(dest);
+    } catch (e) {
+      setError(errorMessage(e));
+    } finally {
+      setLoading(false);
+    }
+  }
+
+  return (
+    <div class="w-full max-w-md flex flex-col gap-2">
+      <div class="text-sm font-medium">Recent sessions</div>
+      <Show
+        when={recent().length > 0}
+        fallback={
+          <div class="text-xs text-text-secondary">
+            No sessions recorded yet
+          </div>
+        }
+      >
+        <div class="flex flex-col gap-1 text-xs">
+          <For each={recent()}>
+            {(session) => (
+              <div class="flex flex-row justify-between gap-2">
+                <span class="truncate">
+                  {session.meta.location ?? session.destinationId}
+                </span>
+                <span
+                  class="text-text-secondary"
+                  title={session.endDetail ?? undefined}
+                >
+                  {new Date(session.startedAt).toLocaleString()} ·{" "}
+                  {formatDuration(sessionDurationMs(session))} ·{" "}
+                  {END_REASON_LABELS[session.endReason]}
+                </span>
+              </div>
+            )}
+          </For>
+        </div>
+      </Show>
+      <div class="flex flex-row gap-2 justify-center">
+        <Button
+          size="sm"
+          variant="outline"
+          loading={loading()}
+          disabled={sessions().length === 0}
+          onClick={() => onExport("csv")}
+        >
+          Export CSV
+        </Button>
+        <Button
+          size="sm"
+          variant="outline"
+          loading={loading()}
+          disabled={sessions().length === 0}
+          onClick={() => onExport("json")}
+        >
+          Export JSON
+        </Button>
+      </div>
+      <div class="w-full h-4 flex items-center justify-center">
+        <Switch>
+          <Match when={savedPath()}>
+            <span class="text-xs text-text-secondary overflow-x-auto">
+              Saved to: <span class="font-mono">{savedPath()}</span>
+            </span>
+          </Match>
+          <Match when={error()}>
+            <span class="text-xs text-red-600">{error()}</span>
+          </Match>
+        </Switch>
+      </div>
+    </div>
+  );
+}
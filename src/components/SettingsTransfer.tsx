@@ -0,0 +1,105 @@
+import { createSignal, Match, Switch } from "solid-js";
+import { ask, open, save } from "@tauri-apps/plugin-dialog";
+import { downloadDir, join } from "@tauri-apps/api/path";
+import { errorMessage } from "@src/services/appError.ts";
+import { useSettingsStore } from "@src/stores/settingsStore.ts";
+import Button from "./common/Button.tsx";
+
+const FILTERS = [{ name: "JSON", extensions: ["json"] }];
+
+// Export, import and reset of the whole configuration, e.g. to hand a
+// known-good setup to a colleague. Update state and launch at login stay
+// local (see `NOT_EXPORTED` in settings.rs).
+export default function SettingsTransfer() {
+  const [, settingsActions] = useSettingsStore();
+  const [loading, setLoading] = createSignal(false);
+  const [result, setResult] = createSignal<string | null>(null);
+  const [error, setError] = createSignal<string | null>(null);
+
+  async function run(action: () => Promise<string | null>) {
+    setLoading(true);
+    setError(null);
+    setResult(null);
+    try {
+      setResult(await action());
+    } catch (e) {
+      setError(errorMessage(e));
+    } finally {
+      setLoading(false);
+    }
+  }
+
+  const onExport = () =>
+    run(async () => {
+      const date = new Date().toISOString().slice(0, 10);
+      const defaultPath = await join(
+        await downloadDir(),
+        `gnosis_vpn-settings-${date}.json`,
+      );
+      const dest = await save({ defaultPath, filters: FILTERS });
+      if (!dest) return null;
+      await settingsActions.exportSettings(dest);
+      return `Saved to ${dest}`;
+    });
+
+  const onImport = () =>
+    run(async () => {
+      const src = await open({ multiple: false, filters: FILTERS });
+      if (!src) return null;
+      await settingsActions.importSettings(src);
+      return "Settings imported";
+    });
+
+  const onReset = () =>
+    run(async () => {
+      const confirmed = await ask(
+        "All settings return to their defaults. A backup of the current file is kept next to it.",
+        { title: "Reset settings", kind: "warning" },
+      );
+      if (!confirmed) return null;
+      await settingsActions.resetSettings();
+      return "Settings reset to defaults";
+    });
+
+  return (
+    <div class="space-y-2">
+      <h3 class="text-sm font-semibold text-text-secondary">Configuration</h3>
+      <div class="flex flex-row gap-2">
+        <Button
+          size="sm"
+          variant="outline"
+          loading={loading()}
+          onClick={onExport}
+        >
+          Export
+        </Button>
+        <Button
+          size="sm"
+          variant="outline"
+          loading={loading()}
+          onClick={onImport}
+        >
+          Import
+        </Button>
+        <Button
+          size="sm"
+          variant="outline"
+          loading={loading()}
+          onClick={onReset}
+        >
+          Reset to defaults
+        </Button>
+      </div>
+      <Switch>
+        <Match when={result()}>
+          <span class="text-xs text-text-secondary overflow-x-auto">
+            {result()}
+          </span>
+        </Match>
+        <Match when={error()}>
+          <span class="text-xs text-red-600">{error()}</span>
+        </Match>
+      </Switch>
+    </div>
+  );
+}
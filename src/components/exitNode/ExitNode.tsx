@@ -20,7 +20,12 @@ export default function ExitNode() {
     resolveAutoDestination(
       appState.availableDestinations,
       appState.destinations,
-      settings.preferredLocation,
+      settings.preferredExits,
+      {
+        latency: appState.latency,
+        scores: appState.scores,
+        order: settings.exitNodeSortOrder,
+      },
     )
   );
 
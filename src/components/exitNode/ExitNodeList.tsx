@@ -17,6 +17,7 @@ import {
   resolveAutoDestination,
   sortAlphaDestinations,
   sortByHealthScore,
+  sortByScore,
 } from "@src/utils/destinations.ts";
 import ExitNodeCard from "./ExitNodeCard.tsx";
 import UnreachableDialog from "./UnreachableDialog.tsx";
@@ -52,7 +53,12 @@ export default function ExitNodeList(props: { onClose: () => void }) {
     resolveAutoDestination(
       appState.availableDestinations,
       appState.destinations,
-      settings.preferredLocation,
+      settings.preferredExits,
+      {
+        latency: appState.latency,
+        scores: appState.scores,
+        order: settings.exitNodeSortOrder,
+      },
     )
   );
 
@@ -63,9 +69,13 @@ export default function ExitNodeList(props: { onClose: () => void }) {
         appState.destinations,
       );
     }
+    if (settings.exitNodeSortOrder === "score") {
+      return sortByScore(appState.availableDestinations, appState.scores);
+    }
     return sortByHealthScore(
       appState.availableDestinations,
       appState.destinations,
+      appState.latency,
     );
   });
 
@@ -163,6 +173,7 @@ export default function ExitNodeList(props: { onClose: () => void }) {
   const sortOptions = [
     { order: "latency" as const, label: "Latency" },
     { order: "alpha" as const, label: "A–Z" },
+    { order: "score" as const, label: "Score" },
   ];
 
   return (
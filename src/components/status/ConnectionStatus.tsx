@@ -12,6 +12,7 @@ import { destinationLabel } from "../../utils/destinations.ts";
  *  2. Connecting   → "{phase}" or "Connecting to {location}"
  *  3. Connected    → "Connected to {location}"
  *  4. Disconnecting (only when nothing is connecting) → "{phase}" or "Disconnecting from {location}"
+ *  5. Scheduled auto-connect → "Reconnecting to {location} (attempt n)"
  */
 function deriveStatus(appState: AppState): string | undefined {
   if (appState.reconnecting) {
@@ -53,6 +54,14 @@ function deriveStatus(appState: AppState): string | undefined {
     return phaseLabel !== d.phase ? phaseLabel : `Disconnecting from ${label}`;
   }
 
+  if (appState.autoConnect) {
+    const { destination_id, trigger, attempt } = appState.autoConnect;
+    const dest = appState.destinations[destination_id]?.destination;
+    const label = dest ? destinationLabel(dest) : destination_id;
+    const verb = trigger === "Dropped" ? "Reconnecting" : "Connecting";
+    return `${verb} to ${label} (attempt ${attempt})`;
+  }
+
   return undefined;
 }
 
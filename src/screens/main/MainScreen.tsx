@@ -59,7 +59,12 @@ export function MainScreen() {
     const resolved = resolveAutoDestination(
       appState.availableDestinations,
       appState.destinations,
-      settings.preferredLocation,
+      settings.preferredExits,
+      {
+        latency: appState.latency,
+        scores: appState.scores,
+        order: settings.exitNodeSortOrder,
+      },
     );
     return resolved ? appState.destinations[resolved.id] : undefined;
   });
@@ -1,15 +1,14 @@
+import { For, onMount, Show } from "solid-js";
 import { Dropdown } from "../../components/common/Dropdown.tsx";
 import Toggle from "@src/components/common/Toggle.tsx";
-import { useAppStore } from "@src/stores/appStore.ts";
 import {
   type FlagDisplay,
+  type NotificationSettings,
+  type TrayField,
   useSettingsStore,
 } from "@src/stores/settingsStore.ts";
-import {
-  destinationLabel,
-  destinationLabelById,
-} from "@src/utils/destinations.ts";
-import { Show } from "solid-js";
+import PreferredExits from "@src/components/PreferredExits.tsx";
+import SettingsTransfer from "@src/components/SettingsTransfer.tsx";
 
 const FLAG_DISPLAY_OPTIONS: { id: FlagDisplay; label: string }[] = [
   { id: "none", label: "Off" },
@@ -17,55 +16,76 @@ const FLAG_DISPLAY_OPTIONS: { id: FlagDisplay; label: string }[] = [
   { id: "color", label: "Colored" },
 ];
 
+type NotificationCategory = Exclude<keyof NotificationSettings, "quietHours">;
+
+const NOTIFICATION_CATEGORIES: { id: NotificationCategory; label: string }[] = [
+  { id: "connected", label: "Connected" },
+  { id: "disconnected", label: "Connection dropped" },
+  { id: "fundsLow", label: "Funds low or empty" },
+  { id: "safeFunded", label: "Node funded during setup" },
+  { id: "updateAvailable", label: "Update available" },
+];
+
+// Listed in display order; the stored field lists keep this order too.
+const TRAY_FIELDS: { id: TrayField; label: string }[] = [
+  { id: "destination", label: "Exit" },
+  { id: "location", label: "Location" },
+  { id: "connectedSince", label: "Connected for" },
+  { id: "latency", label: "Latency" },
+  { id: "fundsLevel", label: "Funds level" },
+];
+
+const toggleField = (fields: TrayField[], field: TrayField, on: boolean) =>
+  TRAY_FIELDS.map((f) => f.id).filter((id) =>
+    id === field ? on : fields.includes(id)
+  );
+
+const DEFAULT_QUIET_HOURS = { start: "22:00", end: "07:00" };
+
 export default function Settings() {
-  const [appState] = useAppStore();
   const [settings, settingsActions] = useSettingsStore();
 
+  const setNotifications = (changes: Partial<NotificationSettings>) =>
+    void settingsActions.setNotifications({
+      ...settings.notifications,
+      ...changes,
+    });
+
+  onMount(() => void settingsActions.refreshLaunchAtLogin());
+
   return (
     <div class="space-y-4 w-full p-6 max-w-lg bg-bg-primary flex flex-col h-full">
-      <label class="flex items-center justify-between gap-2 text-text-primary">
-        Preferred server location
-        <Show
-          when={appState.availableDestinations.length > 0}
-          fallback={
-            <div class="text-sm text-text-secondary">No servers available</div>
-          }
-        >
-          <Dropdown
-            options={[
-              { id: null as string | null, label: "No preference" },
-              ...appState.availableDestinations.map((e) => ({
-                id: e.id as string | null,
-                label: destinationLabel(e),
-              })),
-            ]}
-            value={settings.preferredLocation
-              ? {
-                id: settings.preferredLocation,
-                label: destinationLabelById(
-                  settings.preferredLocation,
-                  appState.availableDestinations,
-                ),
-              }
-              : { id: null, label: "No preference" }}
-            onChange={(e) => void settingsActions.setPreferredLocation(e.id)}
-            size="sm"
-            itemToString={(e) => e.label}
-          />
-        </Show>
-      </label>
+      <PreferredExits />
       <Toggle
         label="Connect on application startup"
         checked={settings.connectOnStartup}
         onChange={(e) =>
           void settingsActions.setConnectOnStartup(e.currentTarget.checked)}
       />
+      <Toggle
+        label="Reconnect automatically when the connection drops"
+        checked={settings.alwaysOn}
+        onChange={(e) =>
+          void settingsActions.setAlwaysOn(e.currentTarget.checked)}
+      />
+      <Toggle
+        label="Switch exit when the connected one becomes unhealthy"
+        checked={settings.exitFailover}
+        onChange={(e) =>
+          void settingsActions.setExitFailover(e.currentTarget.checked)}
+      />
       <Toggle
         label="Start application minimized"
         checked={settings.startMinimized}
         onChange={(e) =>
           void settingsActions.setStartMinimized(e.currentTarget.checked)}
       />
+      <Toggle
+        label="Launch at login"
+        checked={settings.launchAtLogin}
+        onChange={(e) =>
+          void settingsActions.setLaunchAtLogin(e.currentTarget.checked)}
+      />
       <label class="flex items-center justify-between gap-2 text-text-primary">
         Exit node flags
         <Dropdown
@@ -78,6 +98,129 @@ export default function Settings() {
           itemToString={(o) => o.label}
         />
       </label>
+      <Toggle
+        label="Serve metrics on localhost"
+        checked={settings.metricsEndpoint}
+        onChange={(e) =>
+          void settingsActions.setMetricsEndpoint(e.currentTarget.checked)}
+      />
+      <Show when={settings.metricsEndpoint}>
+        <label class="flex items-center justify-between gap-2 text-text-primary">
+          Metrics port
+          <input
+            type="number"
+            min="1"
+            max="65535"
+            aria-label="Metrics port"
+            value={settings.metricsPort}
+            onChange={(e) => {
+              const port = Number(e.currentTarget.value);
+              if (Number.isInteger(port) && port >= 1 && port <= 65535) {
+                void settingsActions.setMetricsPort(port);
+              } else {
+                e.currentTarget.value = String(settings.metricsPort);
+              }
+            }}
+            class="w-24 bg-bg-surface rounded-xl px-3 py-1 text-sm text-text-primary outline-none focus:ring-1 focus:ring-text-secondary"
+          />
+        </label>
+      </Show>
+      <div class="space-y-2">
+        <h3 class="text-sm font-semibold text-text-secondary">
+          Notifications
+        </h3>
+        <For each={NOTIFICATION_CATEGORIES}>
+          {(category) => (
+            <Toggle
+              label={category.label}
+              checked={settings.notifications[category.id]}
+              onChange={(e) =>
+                setNotifications({ [category.id]: e.currentTarget.checked })}
+            />
+          )}
+        </For>
+        <Toggle
+          label="Quiet hours"
+          checked={settings.notifications.quietHours !== null}
+          onChange={(e) =>
+            setNotifications({
+              quietHours: e.currentTarget.checked ? DEFAULT_QUIET_HOURS : null,
+            })}
+        />
+        <Show when={settings.notifications.quietHours}>
+          {(hours) => (
+            <div class="flex items-center justify-between gap-2 text-text-primary">
+              From
+              <input
+                type="time"
+                aria-label="Quiet hours start"
+                value={hours().start}
+                onChange={(e) =>
+                  setNotifications({
+                    quietHours: { ...hours(), start: e.currentTarget.value },
+                  })}
+                class="bg-bg-surface rounded-xl px-3 py-1 text-sm text-text-primary outline-none focus:ring-1 focus:ring-text-secondary"
+              />
+              to
+              <input
+                type="time"
+                aria-label="Quiet hours end"
+                value={hours().end}
+                onChange={(e) =>
+                  setNotifications({
+                    quietHours: { ...hours(), end: e.currentTarget.value },
+                  })}
+                class="bg-bg-surface rounded-xl px-3 py-1 text-sm text-text-primary outline-none focus:ring-1 focus:ring-text-secondary"
+              />
+            </div>
+          )}
+        </Show>
+      </div>
+      <div class="space-y-2">
+        <div class="flex items-center justify-between gap-2 text-sm font-semibold text-text-secondary">
+          <span>Tray icon</span>
+          <span class="flex gap-3">
+            <span>Tooltip</span>
+            <span title="Shown next to the icon on Linux">Label</span>
+          </span>
+        </div>
+        <For each={TRAY_FIELDS}>
+          {(field) => (
+            <div class="flex items-center justify-between gap-2 text-text-primary">
+              {field.label}
+              <span class="flex gap-6">
+                <Toggle
+                  small
+                  aria-label={`${field.label} in tooltip`}
+                  checked={settings.trayTooltipFields.includes(field.id)}
+                  onChange={(e) =>
+                    void settingsActions.setTrayTooltipFields(
+                      toggleField(
+                        settings.trayTooltipFields,
+                        field.id,
+                        e.currentTarget.checked,
+                      ),
+                    )}
+                />
+                <Toggle
+                  small
+                  aria-label={`${field.label} in label`}
+                  checked={settings.trayTitleFields.includes(field.id)}
+                  onChange={(e) =>
+                    void settingsActions.setTrayTitleFields(
+                      toggleField(
+                        settings.trayTitleFields,
+                        field.id,
+                        e.currentTarget.checked,
+                      ),
+                    )}
+                />
+              </span>
+            </div>
+          )}
+        </For>
+      </div>
+      <SettingsTransfer />
       <div class="grow" />
     </div>
   );
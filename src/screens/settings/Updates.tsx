@@ -34,6 +34,7 @@ import {
   installUpdate,
   UPDATE_INSTALL_STATUS_EVENT,
 } from "@src/services/updateInstall.ts";
+import { errorMessage, hasErrorCode } from "@src/services/appError.ts";
 
 const REVEAL_CLICKS = 7;
 const REVEAL_WINDOW_MS = 2000;
@@ -88,7 +89,7 @@ export default function Updates() {
       });
       await settingsActions.setUpdateCheckResult(manifest, Date.now());
     } catch (e) {
-      if (e === "VpnNotConnected") {
+      if (hasErrorCode(e, "VpnNotConnected")) {
         setShowCheckModal(true);
       }
       // TODO: surface other errors
@@ -179,9 +180,9 @@ export default function Updates() {
     // Synchronous phase set: disables the button before the invoke resolves.
     setInstallPhase("downloading");
     installUpdate(effectiveChannel(), force).catch((e) => {
-      if (e === "InstallInProgress") return; // already streaming events
+      if (hasErrorCode(e, "InstallInProgress")) return; // already streaming events
       setInstallPhase(null);
-      setInstallError(String(e));
+      setInstallError(errorMessage(e));
     });
   };
 
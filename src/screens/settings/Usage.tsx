@@ -29,6 +29,7 @@ import WarningIcon from "../../components/icons/WarningIcon.tsx";
 import Button from "../../components/common/Button.tsx";
 import { useAppStore } from "../../stores/appStore.ts";
 import AddFundsModal from "@src/components/AddFundsModal.tsx";
+import SessionHistory from "@src/components/SessionHistory.tsx";
 
 export default function Usage() {
   const [isAddFundsOpen, setIsAddFundsOpen] = createSignal(false);
@@ -209,6 +210,7 @@ export default function Usage() {
           />
         </Match>
       </Switch>
+      <SessionHistory />
     </div>
   );
 }
@@ -0,0 +1,50 @@
+import { describe, expect, it } from "vitest";
+
+import { AppErrorSchema, errorMessage, hasErrorCode } from "./appError.ts";
+
+// Fixtures are generated by `cargo test --test serialize_fixtures` in
+// src-tauri from the real serde output of error::AppError.
+import vpnNotConnected from "./fixtures/error_vpn_not_connected.json";
+import statusEventError from "./fixtures/status_event_error.json";
+
+describe("AppErrorSchema fixtures", () => {
+  it("parses a command rejection", () => {
+    const parsed = AppErrorSchema.safeParse(vpnNotConnected);
+    expect(parsed.success).toBe(true);
+    expect(hasErrorCode(vpnNotConnected, "VpnNotConnected")).toBe(true);
+    expect(hasErrorCode(vpnNotConnected, "InstallInProgress")).toBe(false);
+  });
+
+  it("parses the Err payload of a status event", () => {
+    const parsed = AppErrorSchema.safeParse(statusEventError.Err);
+    expect(parsed.success).toBe(true);
+    if (parsed.success) {
+      expect(parsed.data.code).toBe("DaemonUnreachable");
+      expect(parsed.data.details).toBe("connection refused");
+    }
+  });
+
+  it("rejects unknown codes and bare strings", () => {
+    expect(
+      AppErrorSchema.safeParse({ code: "Nope", message: "", details: null })
+        .success,
+    ).toBe(false);
+    expect(AppErrorSchema.safeParse("VpnNotConnected").success).toBe(false);
+  });
+});
+
+describe("errorMessage", () => {
+  it("joins message and details", () => {
+    expect(errorMessage(statusEventError.Err)).toBe(
+      "Cannot reach the VPN service: connection refused",
+    );
+    expect(errorMessage(vpnNotConnected)).toBe(
+      "Connect to the VPN to check for updates",
+    );
+  });
+
+  it("falls back for non-AppError values", () => {
+    expect(errorMessage(new Error("boom"))).toBe("boom");
+    expect(errorMessage("plain")).toBe("plain");
+  });
+});
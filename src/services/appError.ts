@@ -0,0 +1,59 @@
+import { z } from "zod";
+
+// Wire type of every rejected Tauri command and of `Err` payloads on the
+// `status` / `balance` events (src-tauri/src/error.rs). Branch on `code`;
+// `message` is display text and may change between releases.
+// Fixtures for this schema are generated by
+// `cargo test --test serialize_fixtures` — see appError.test.ts.
+
+export const ErrorCodeSchema = z.enum([
+  "DaemonUnreachable",
+  "DaemonTimeout",
+  "UnexpectedResponse",
+  "IncompatibleService",
+  "WorkerStartFailed",
+  "PollingStartFailed",
+  "NotAvailable",
+  "VpnNotConnected",
+  "UpdateIntegrity",
+  "UpdateCheckFailed",
+  "InstallInProgress",
+  "InvalidChannel",
+  "UnsupportedPlatform",
+  "SpawnFailed",
+  "InvalidPath",
+  "Io",
+  "SettingsPersistFailed",
+  "InvalidSettingsFile",
+  "InvalidIcon",
+  "InvalidRequest",
+  "Internal",
+]);
+export type ErrorCode = z.infer<typeof ErrorCodeSchema>;
+
+export const AppErrorSchema = z.object({
+  code: ErrorCodeSchema,
+  message: z.string(),
+  details: z.string().nullable(),
+});
+export type AppError = z.infer<typeof AppErrorSchema>;
+
+export function parseAppError(e: unknown): AppError | null {
+  const parsed = AppErrorSchema.safeParse(e);
+  return parsed.success ? parsed.data : null;
+}
+
+export function hasErrorCode(e: unknown, code: ErrorCode): boolean {
+  return parseAppError(e)?.code === code;
+}
+
+// Human-readable text for anything a command or event can reject with.
+export function errorMessage(e: unknown): string {
+  const appError = parseAppError(e);
+  if (appError) {
+    return appError.details
+      ? `${appError.message}: ${appError.details}`
+      : appError.message;
+  }
+  return e instanceof Error ? e.message : String(e);
+}
@@ -0,0 +1,28 @@
+import { describe, expect, it } from "vitest";
+
+import { AutoConnectEventSchema, pendingAttempt } from "./autoConnect.ts";
+
+// Fixtures are generated by `cargo test --test serialize_fixtures` in
+// src-tauri from the real serde output of auto_connect::AutoConnectEvent.
+import events from "./fixtures/auto_connect_events.json";
+
+describe("AutoConnectEventSchema fixtures", () => {
+  it("parses every event kind", () => {
+    const kinds = events.map((e) => {
+      const parsed = AutoConnectEventSchema.safeParse(e);
+      expect(parsed.success).toBe(true);
+      return parsed.success ? parsed.data.kind : null;
+    });
+    expect(kinds).toEqual(["Attempt", "Succeeded", "GaveUp", "Cancelled"]);
+  });
+});
+
+describe("pendingAttempt", () => {
+  it("keeps only scheduled attempts", () => {
+    const [attempt, succeeded] = events.map((e) =>
+      AutoConnectEventSchema.parse(e)
+    );
+    expect(pendingAttempt(attempt)?.attempt).toBe(2);
+    expect(pendingAttempt(succeeded)).toBeNull();
+  });
+});
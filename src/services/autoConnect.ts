@@ -0,0 +1,41 @@
+import { z } from "zod";
+
+// Wire type of the `auto-connect` event (src-tauri/src/auto_connect.rs):
+// progress of the startup connect and of always-on reconnects after a drop.
+// Fixtures for this schema are generated by
+// `cargo test --test serialize_fixtures` — see autoConnect.test.ts.
+
+export const AUTO_CONNECT_EVENT = "auto-connect";
+
+export const AutoConnectTriggerSchema = z.enum(["Startup", "Dropped"]);
+export type AutoConnectTrigger = z.infer<typeof AutoConnectTriggerSchema>;
+
+export const AutoConnectEventSchema = z.discriminatedUnion("kind", [
+  z.object({
+    kind: z.literal("Attempt"),
+    destination_id: z.string(),
+    trigger: AutoConnectTriggerSchema,
+    attempt: z.number(),
+    delay_ms: z.number(),
+  }),
+  z.object({
+    kind: z.literal("Succeeded"),
+    destination_id: z.string(),
+    attempts: z.number(),
+  }),
+  z.object({
+    kind: z.literal("GaveUp"),
+    destination_id: z.string(),
+    attempts: z.number(),
+  }),
+  z.object({ kind: z.literal("Cancelled") }),
+]);
+export type AutoConnectEvent = z.infer<typeof AutoConnectEventSchema>;
+export type AutoConnectAttempt = Extract<AutoConnectEvent, { kind: "Attempt" }>;
+
+// Only a scheduled attempt is worth showing; every other event ends it.
+export function pendingAttempt(
+  event: AutoConnectEvent,
+): AutoConnectAttempt | null {
+  return event.kind === "Attempt" ? event : null;
+}
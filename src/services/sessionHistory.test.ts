@@ -0,0 +1,46 @@
+import { describe, expect, it } from "vitest";
+
+import {
+  EndReasonSchema,
+  SessionHistorySchema,
+  sessionDurationMs,
+} from "./sessionHistory.ts";
+
+// Fixtures are generated by `cargo test --test serialize_fixtures` in
+// src-tauri from the real serde output of sessions::Session.
+import sessionHistory from "./fixtures/session_history.json";
+
+describe("SessionHistorySchema fixtures", () => {
+  it("parses a session for every end reason", () => {
+    const parsed = SessionHistorySchema.safeParse(sessionHistory);
+    expect(parsed.success).toBe(true);
+    if (parsed.success) {
+      expect(parsed.data.map((s) => s.endReason)).toEqual(
+        EndReasonSchema.options,
+      );
+      expect(parsed.data[0].meta.location).toBe("EU");
+    }
+  });
+
+  it("keeps never-connected attempts nullable", () => {
+    const parsed = SessionHistorySchema.parse(sessionHistory);
+    const failed = parsed.find((s) => s.endReason === "ConnectFailed");
+    expect(failed?.connectedAt).toBeNull();
+    expect(failed?.timeToConnectMs).toBeNull();
+  });
+
+  it("carries the failover reason", () => {
+    const parsed = SessionHistorySchema.parse(sessionHistory);
+    const failedOver = parsed.find((s) => s.endReason === "FailedOver");
+    expect(failedOver?.endDetail).toContain("failed health checks");
+    expect(parsed[0].endDetail).toBeNull();
+  });
+});
+
+describe("sessionDurationMs", () => {
+  it("counts connected time only", () => {
+    const [connected, failed] = SessionHistorySchema.parse(sessionHistory);
+    expect(sessionDurationMs(connected)).toBe(597_500);
+    expect(sessionDurationMs(failed)).toBe(0);
+  });
+});
@@ -0,0 +1,60 @@
+import { invoke } from "@tauri-apps/api/core";
+import { z } from "zod";
+
+// Wire types for recorded connection sessions (src-tauri/src/sessions.rs).
+// A session is appended when a connection ends; each one is also broadcast
+// as a `session-recorded` event. Timestamps are Unix milliseconds.
+// Fixtures for this schema are generated by
+// `cargo test --test serialize_fixtures` — see sessionHistory.test.ts.
+
+export const SESSION_RECORDED_EVENT = "session-recorded";
+
+export const EndReasonSchema = z.enum([
+  "UserDisconnect",
+  "ConnectFailed",
+  "Dropped",
+  "Switched",
+  "FailedOver",
+  "ServiceStopped",
+  "AppExit",
+]);
+export type EndReason = z.infer<typeof EndReasonSchema>;
+
+export const SessionSchema = z.object({
+  destinationId: z.string(),
+  meta: z.record(z.string(), z.string()),
+  startedAt: z.number(),
+  connectedAt: z.number().nullable(),
+  endedAt: z.number(),
+  timeToConnectMs: z.number().nullable(),
+  reconnectCount: z.number(),
+  endReason: EndReasonSchema,
+  // Why the failover policy switched away; null for other reasons.
+  endDetail: z.string().nullable(),
+});
+export type Session = z.infer<typeof SessionSchema>;
+
+export const SessionHistorySchema = z.array(SessionSchema);
+
+export type SessionExportFormat = "csv" | "json";
+
+// Oldest first, as recorded.
+export async function getSessionHistory(): Promise<Session[]> {
+  const raw = await invoke<unknown>("get_session_history");
+  return SessionHistorySchema.parse(raw);
+}
+
+// Rejects with an AppError coded "Io" if the file cannot be written.
+export function exportSessionHistory(
+  destPath: string,
+  format: SessionExportFormat,
+): Promise<void> {
+  return invoke<void>("export_session_history", { destPath, format });
+}
+
+// Connected time, or 0 for attempts that never connected.
+export function sessionDurationMs(session: Session): number {
+  return session.connectedAt == null
+    ? 0
+    : Math.max(0, session.endedAt - session.connectedAt);
+}
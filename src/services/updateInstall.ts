@@ -22,8 +22,9 @@ export const InstallStatusSchema = z.discriminatedUnion("kind", [
 export type InstallStatus = z.infer<typeof InstallStatusSchema>;
 
 // Starts the updater; resolves once it is spawned. Progress and the outcome
-// arrive via UPDATE_INSTALL_STATUS_EVENT. Rejects with "InstallInProgress",
-// "UnsupportedPlatform", "InvalidChannel" or a spawn error.
+// arrive via UPDATE_INSTALL_STATUS_EVENT. Rejects with an AppError coded
+// "InstallInProgress", "UnsupportedPlatform", "InvalidChannel" or
+// "SpawnFailed".
 export function installUpdate(
   channel: UpdateChannel,
   force: boolean,
@@ -3,6 +3,7 @@ import {
   BalanceResponseSchema,
   ConnectResponseSchema,
   DisconnectResponseSchema,
+  LatencyHistorySchema,
   ServiceInfoSchema,
   StatusResponseSchema,
 } from "./vpnService.ts";
@@ -31,6 +32,7 @@ import balanceResponse from "./fixtures/balance_response.json";
 import balanceResponseWithIssues from "./fixtures/balance_response_with_issues.json";
 import balanceResponseWithCapacity from "./fixtures/balance_response_with_capacity.json";
 import serviceInfo from "./fixtures/service_info.json";
+import latencyHistory from "./fixtures/latency_history.json";
 
 describe("StatusResponseSchema", () => {
   it("parses NotRunning run_mode", () => {
@@ -99,6 +101,23 @@ describe("StatusResponseSchema", () => {
       StatusResponseSchema.safeParse(statusRouteHealthVariants).success,
     ).toBe(true);
   });
+
+  it("parses per-destination latency stats and scores", () => {
+    const result = StatusResponseSchema.parse(statusWithConnections);
+    expect(result.latency["test-exit"].smoothed_ms).toBe(42);
+    expect(result.scores["test-exit"].score).toBe(0.7625);
+  });
+});
+
+describe("LatencyHistorySchema", () => {
+  it("parses samples and stats per destination", () => {
+    const result = LatencyHistorySchema.parse(latencyHistory);
+    expect(result["test-exit"].samples.map((s) => s.ping_rtt_ms)).toEqual([
+      42,
+      58,
+    ]);
+    expect(result["test-exit"].stats.smoothed_ms).toBe(46);
+  });
 });
 
 describe("ConnectResponseSchema", () => {
@@ -1,5 +1,6 @@
 import { invoke } from "@tauri-apps/api/core";
 import { z } from "zod";
+import { errorMessage } from "./appError.ts";
 
 // ==========================================
 // Zod Schemas & Inferred Types
@@ -277,6 +278,43 @@ export const InfoSchema = z.object({
 });
 export type Info = z.infer<typeof InfoSchema>;
 
+// Smoothed over recent health checks (src-tauri/src/latency.rs).
+export const LatencyStatsSchema = z.object({
+  samples: z.number(),
+  last_ms: z.number(),
+  smoothed_ms: z.number(),
+  jitter_ms: z.number(),
+});
+export type LatencyStats = z.infer<typeof LatencyStatsSchema>;
+
+export const LatencySampleSchema = z.object({
+  checked_at_ms: z.number(),
+  ping_rtt_ms: z.number(),
+  slots: SlotsSchema,
+  load_avg: LoadAvgSchema,
+});
+export type LatencySample = z.infer<typeof LatencySampleSchema>;
+
+// Keyed by destination id; samples oldest first.
+export const LatencyHistorySchema = z.record(
+  z.string(),
+  z.object({
+    samples: z.array(LatencySampleSchema),
+    stats: LatencyStatsSchema,
+  }),
+);
+export type LatencyHistory = z.infer<typeof LatencyHistorySchema>;
+
+// Composite exit score, each part 0..1 (src-tauri/src/score.rs).
+export const ExitScoreSchema = z.object({
+  score: z.number(),
+  rtt: z.number(),
+  load: z.number(),
+  slots: z.number(),
+  stability: z.number(),
+});
+export type ExitScore = z.infer<typeof ExitScoreSchema>;
+
 export const StatusResponseSchema = z.object({
   run_mode: RunModeSchema,
   destinations: z.array(DestinationStateSchema),
@@ -285,6 +323,8 @@ export const StatusResponseSchema = z.object({
   connecting: ConnectingInfoSchema.nullable(),
   reconnecting: ReconnectingInfoSchema.nullable(),
   disconnecting: z.array(DisconnectingInfoSchema),
+  latency: z.record(z.string(), LatencyStatsSchema),
+  scores: z.record(z.string(), ExitScoreSchema),
 });
 export type StatusResponse = z.infer<typeof StatusResponseSchema>;
 
@@ -398,7 +438,7 @@ export class VPNService {
       } else {
         console.error("Connect error:", error);
       }
-      throw new Error(`Connect error: ${error}`);
+      throw new Error(`Connect error: ${errorMessage(error)}`);
     }
   }
 
@@ -416,7 +456,7 @@ export class VPNService {
       } else {
         console.error("Disconnect error:", error);
       }
-      throw new Error(`Disconnect error: ${error}`);
+      throw new Error(`Disconnect error: ${errorMessage(error)}`);
     }
   }
 
@@ -425,10 +465,15 @@ export class VPNService {
       await invoke("compress_logs", { logPath, destPath });
     } catch (error) {
       console.error("Failed to compress logs", error);
-      throw new Error(`Compress Logs Error: ${error}`);
+      throw new Error(`Compress Logs Error: ${errorMessage(error)}`);
     }
   }
 
+  static async getLatencyHistory(): Promise<LatencyHistory> {
+    const raw = await invoke<unknown>("get_latency_history");
+    return LatencyHistorySchema.parse(raw);
+  }
+
   static getBestDestination(ds_states: StatusResponse["destinations"]): string {
     // when we have an actual StatusResponse, destinations can never be empty
     // Thats why we do not need to check for that case
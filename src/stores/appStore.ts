@@ -15,10 +15,12 @@ import {
   type Destination,
   type DestinationState,
   type DisconnectingInfo,
+  type ExitScore,
   formatWarmupStatus,
   isDeployingSafeRunMode,
   isPreparingSafeRunMode,
   isWarmupRunMode,
+  type LatencyStats,
   type ReconnectingInfo,
   type RunMode,
   type ServiceInfo,
@@ -27,10 +29,19 @@ import {
   StatusResponseSchema,
   VPNService,
 } from "@src/services/vpnService.ts";
+import { type AppError, errorMessage } from "@src/services/appError.ts";
+import {
+  AUTO_CONNECT_EVENT,
+  type AutoConnectAttempt,
+  AutoConnectEventSchema,
+  pendingAttempt,
+} from "@src/services/autoConnect.ts";
 import { useLogsStore } from "@src/stores/logsStore.ts";
 import {
   destinationLabel,
+  firstPreferredDestination,
   getPreferredAvailabilityChangeMessage,
+  pinnedExitId,
   resolveAutoDestination,
 } from "@src/utils/destinations.ts";
 
@@ -50,6 +61,10 @@ export interface AppState {
   serviceInfo: ServiceInfo | null;
   availableDestinations: Destination[];
   destinations: Record<string, DestinationState>;
+  /** Smoothed health-check latency per destination id. */
+  latency: Record<string, LatencyStats>;
+  /** Composite exit score per ready destination id. */
+  scores: Record<string, ExitScore>;
   connected: ConnectedInfo | null;
   connecting: ConnectingInfo | null;
   reconnecting: ReconnectingInfo | null;
@@ -67,6 +82,8 @@ export interface AppState {
   availableVersion: string | null;
   targetDestination: string | null;
   balance: BalanceResponse | null;
+  /** Scheduled startup connect or always-on reconnect, if any. */
+  autoConnect: AutoConnectAttempt | null;
 }
 
 type AppActions = {
@@ -80,13 +97,13 @@ type AppActions = {
 type AppStoreTuple = readonly [Store<AppState>, AppActions];
 
 type StatusEvent = {
-  payload: { Ok: StatusResponse | null } | { Err: string };
+  payload: { Ok: StatusResponse | null } | { Err: AppError };
   id: number;
   event: string;
 };
 
 type BalanceEvent = {
-  payload: { Ok: BalanceResponse | null } | { Err: string };
+  payload: { Ok: BalanceResponse | null } | { Err: AppError };
   id: number;
   event: string;
 };
@@ -100,6 +117,8 @@ function initialState(): AppState {
     currentScreen: AppScreen.Initialization,
     destination: null,
     destinations: {},
+    latency: {},
+    scores: {},
     disconnecting: [],
     error: undefined,
     isLoading: false,
@@ -114,6 +133,7 @@ function initialState(): AppState {
     availableVersion: null,
     targetDestination: null,
     balance: null,
+    autoConnect: null,
   };
 }
 
@@ -133,6 +153,7 @@ export function createAppStore(): AppStoreTuple {
   let unlistenServiceInfo: (() => void) | undefined;
   let unlistenStatusUpdate: (() => void) | undefined;
   let unlistenBalanceUpdate: (() => void) | undefined;
+  let unlistenAutoConnect: (() => void) | undefined;
   let connectedOnOpenDetected = false;
   let activeSyncPhase: SyncPhaseIndex | null = null;
   let syncPhaseStartTime = 0;
@@ -252,16 +273,17 @@ export function createAppStore(): AppStoreTuple {
       }
     }
 
-    // 3. Preferred location (if available).
+    // 3. Preferred exit (if available).
     // Only sets destination — not selectedId — so the user's "Auto" choice
-    // stays visible. connect() resolves preferredLocation independently via
+    // stays visible. connect() resolves preferredExits independently via
     // resolveAutoDestination.
-    if (settings.preferredLocation) {
-      const dest = state.destinations[settings.preferredLocation];
-      if (dest) {
-        setState("destination", dest.destination);
-        return;
-      }
+    const preferred = firstPreferredDestination(
+      Object.values(state.destinations).map((ds) => ds.destination),
+      settings.preferredExits,
+    );
+    if (preferred) {
+      setState("destination", preferred);
+      return;
     }
 
     setState("destination", null);
@@ -309,6 +331,8 @@ export function createAppStore(): AppStoreTuple {
     );
     setState("runMode", reconcile(response.run_mode));
     setState("destinations", reconcile(destinations));
+    setState("latency", reconcile(response.latency));
+    setState("scores", reconcile(response.scores));
     setState("targetDestination", response.target_destination);
     setState("connected", response.connected);
     setState("connecting", reconcile(response.connecting));
@@ -383,7 +407,7 @@ export function createAppStore(): AppStoreTuple {
     const prefMsg = getPreferredAvailabilityChangeMessage(
       state.availableDestinations,
       availableDestinations,
-      settings.preferredLocation,
+      pinnedExitId(settings.preferredExits),
     );
     if (prefMsg) log(prefMsg);
   };
@@ -405,6 +429,10 @@ export function createAppStore(): AppStoreTuple {
         unlistenBalanceUpdate();
         unlistenBalanceUpdate = undefined;
       }
+      if (unlistenAutoConnect) {
+        unlistenAutoConnect();
+        unlistenAutoConnect = undefined;
+      }
 
       try {
         unlistenServiceInfo = await listen<unknown>("service_info", (event) => {
@@ -487,10 +515,27 @@ export function createAppStore(): AppStoreTuple {
         console.error("Failed to listen for balance updates: " + errorMsg);
       }
 
+      try {
+        unlistenAutoConnect = await listen<unknown>(
+          AUTO_CONNECT_EVENT,
+          ({ payload }) => {
+            const parsed = AutoConnectEventSchema.safeParse(payload);
+            if (parsed.success) {
+              setState("autoConnect", pendingAttempt(parsed.data));
+            } else {
+              console.error("Invalid auto-connect event", payload);
+            }
+          },
+        );
+      } catch (error) {
+        const errorMsg = error instanceof Error ? error.message : String(error);
+        console.error("Failed to listen for auto-connect events: " + errorMsg);
+      }
+
       try {
         const cached = await invoke<{
-          status: { Ok: StatusResponse } | { Err: string };
-          balance: { Ok: BalanceResponse } | { Err: string };
+          status: { Ok: StatusResponse } | { Err: AppError };
+          balance: { Ok: BalanceResponse } | { Err: AppError };
           service_info: unknown;
         }>("get_cached_state");
 
@@ -541,7 +586,12 @@ export function createAppStore(): AppStoreTuple {
           id: resolveAutoDestination(
             state.availableDestinations,
             state.destinations,
-            settings.preferredLocation,
+            settings.preferredExits,
+            {
+              latency: state.latency,
+              scores: state.scores,
+              order: settings.exitNodeSortOrder,
+            },
           )?.id,
           reason: "auto destination",
         };
@@ -733,6 +783,6 @@ function incomingStatusEvent(event: StatusEvent): StatusResponse | void {
     }
   } else {
     console.error("Error processing status update", rawRes.Err);
-    throw new Error(rawRes.Err);
+    throw new Error(errorMessage(rawRes.Err));
   }
 }
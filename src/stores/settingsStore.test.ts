@@ -69,7 +69,10 @@ describe("settingsStore", () => {
 
     await actions.load();
 
-    expect(state.preferredLocation).toBe("exit-1");
+    expect(state.preferredExits).toEqual([
+      { id: "exit-1" },
+      { meta: { location: "EU" } },
+    ]);
     expect(state.exitNodeSortOrder).toBe("alpha");
     expect(state.channel).toBe("snapshot");
     expect(state.updateManifest?.channels.stable?.version).toBe("0.29.0");
@@ -80,9 +83,9 @@ describe("settingsStore", () => {
     const [state, actions] = await freshStore();
     await actions.load();
 
-    fireChange({ ...settingsFull, preferredLocation: null, channel: null });
+    fireChange({ ...settingsFull, preferredExits: [], channel: null });
 
-    expect(state.preferredLocation).toBe(null);
+    expect(state.preferredExits).toEqual([]);
     expect(state.channel).toBe(null);
   });
 
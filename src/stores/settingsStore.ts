@@ -48,12 +48,53 @@ export type UpdateChannel = z.infer<typeof UpdateChannelSchema>;
 export const FlagDisplaySchema = z.enum(["none", "mono", "color"]);
 export type FlagDisplay = z.infer<typeof FlagDisplaySchema>;
 
+// `{ id }` names one exit; `{ meta }` matches every exit whose metadata has
+// all the given values, e.g. `{ meta: { location: "EU" } }`.
+export const ExitPreferenceSchema = z.union([
+  z.object({ id: z.string() }),
+  z.object({ meta: z.record(z.string(), z.string()) }),
+]);
+export type ExitPreference = z.infer<typeof ExitPreferenceSchema>;
+
+// Times are local "HH:MM"; a start after the end spans midnight.
+export const QuietHoursSchema = z.object({
+  start: z.string(),
+  end: z.string(),
+});
+export type QuietHours = z.infer<typeof QuietHoursSchema>;
+
+export const NotificationSettingsSchema = z.object({
+  connected: z.boolean(),
+  disconnected: z.boolean(),
+  fundsLow: z.boolean(),
+  safeFunded: z.boolean(),
+  updateAvailable: z.boolean(),
+  quietHours: QuietHoursSchema.nullable(),
+});
+export type NotificationSettings = z.infer<typeof NotificationSettingsSchema>;
+
+export const TrayFieldSchema = z.enum([
+  "destination",
+  "location",
+  "connectedSince",
+  "latency",
+  "fundsLevel",
+]);
+export type TrayField = z.infer<typeof TrayFieldSchema>;
+
+export const SortOrderSchema = z.enum(["latency", "alpha", "score"]);
+export type SortOrder = z.infer<typeof SortOrderSchema>;
+
 export const SettingsSchema = z.object({
-  preferredLocation: z.string().nullable(),
+  preferredExits: z.array(ExitPreferenceSchema),
   connectOnStartup: z.boolean(),
+  alwaysOn: z.boolean(),
+  exitFailover: z.boolean(),
+  failoverAfterSecs: z.number(),
   startMinimized: z.boolean(),
+  launchAtLogin: z.boolean(),
   updateCheck: z.boolean(),
-  exitNodeSortOrder: z.enum(["latency", "alpha"]),
+  exitNodeSortOrder: SortOrderSchema,
   lastCheckedAt: z.number().nullable(),
   updateManifest: UpdateManifestSchema.nullable(),
   channel: UpdateChannelSchema.nullable(),
@@ -61,14 +102,24 @@ export const SettingsSchema = z.object({
   installedVersion: z.string().nullable(),
   showDetailedMetrics: z.boolean(),
   flagDisplay: FlagDisplaySchema,
+  daemonSocketPath: z.string().nullable(),
+  metricsEndpoint: z.boolean(),
+  metricsPort: z.number().int().min(1).max(65535),
+  notifications: NotificationSettingsSchema,
+  trayTooltipFields: z.array(TrayFieldSchema),
+  trayTitleFields: z.array(TrayFieldSchema),
 });
 export type SettingsState = z.infer<typeof SettingsSchema>;
 
 // Shown until the first snapshot arrives; must match the Rust defaults.
 const DEFAULT_SETTINGS: SettingsState = {
-  preferredLocation: null,
+  preferredExits: [],
   connectOnStartup: false,
+  alwaysOn: false,
+  exitFailover: false,
+  failoverAfterSecs: 60,
   startMinimized: false,
+  launchAtLogin: false,
   updateCheck: true,
   exitNodeSortOrder: "latency",
   lastCheckedAt: null,
@@ -78,17 +129,40 @@ const DEFAULT_SETTINGS: SettingsState = {
   installedVersion: null,
   showDetailedMetrics: false,
   flagDisplay: "color",
+  daemonSocketPath: null,
+  metricsEndpoint: false,
+  metricsPort: 9687,
+  notifications: {
+    connected: true,
+    disconnected: true,
+    fundsLow: true,
+    safeFunded: true,
+    updateAvailable: true,
+    quietHours: null,
+  },
+  trayTooltipFields: [
+    "destination",
+    "location",
+    "connectedSince",
+    "latency",
+    "fundsLevel",
+  ],
+  trayTitleFields: [],
 };
 
 type SettingsActions = {
   load: () => Promise<void>;
   /** Reactive: true once the first real snapshot replaced DEFAULT_SETTINGS. */
   hydrated: () => boolean;
-  setPreferredLocation: (id: string | null) => Promise<void>;
+  setPreferredExits: (preferences: ExitPreference[]) => Promise<void>;
   setConnectOnStartup: (enabled: boolean) => Promise<void>;
+  setAlwaysOn: (enabled: boolean) => Promise<void>;
+  setExitFailover: (enabled: boolean) => Promise<void>;
   setStartMinimized: (enabled: boolean) => Promise<void>;
+  setLaunchAtLogin: (enabled: boolean) => Promise<void>;
+  refreshLaunchAtLogin: () => Promise<void>;
   setUpdateCheck: (enabled: boolean) => Promise<void>;
-  setExitNodeSortOrder: (order: "latency" | "alpha") => Promise<void>;
+  setExitNodeSortOrder: (order: SortOrder) => Promise<void>;
   setUpdateCheckResult: (
     manifest: UpdateManifest,
     checkedAt: number,
@@ -101,6 +175,16 @@ type SettingsActions = {
   setDismissedUpdateVersion: (version: string | null) => Promise<void>;
   setShowDetailedMetrics: (show: boolean) => Promise<void>;
   setFlagDisplay: (display: FlagDisplay) => Promise<void>;
+  setMetricsEndpoint: (enabled: boolean) => Promise<void>;
+  setMetricsPort: (port: number) => Promise<void>;
+  setNotifications: (notifications: NotificationSettings) => Promise<void>;
+  setTrayTooltipFields: (fields: TrayField[]) => Promise<void>;
+  setTrayTitleFields: (fields: TrayField[]) => Promise<void>;
+  // These reject with an AppError for the caller to show; the resulting
+  // snapshot arrives through `settings-changed` like any patch.
+  exportSettings: (destPath: string) => Promise<void>;
+  importSettings: (srcPath: string) => Promise<void>;
+  resetSettings: () => Promise<void>;
 };
 
 type SettingsStoreTuple = readonly [
@@ -171,9 +255,23 @@ export function createSettingsStore(): SettingsStoreTuple {
       return loadPromise;
     },
     hydrated,
-    setPreferredLocation: (id) => patch({ preferredLocation: id }),
+    setPreferredExits: (preferences) => patch({ preferredExits: preferences }),
     setConnectOnStartup: (enabled) => patch({ connectOnStartup: enabled }),
+    setAlwaysOn: (enabled) => patch({ alwaysOn: enabled }),
+    setExitFailover: (enabled) => patch({ exitFailover: enabled }),
     setStartMinimized: (enabled) => patch({ startMinimized: enabled }),
+    // Rust applies the autostart entry and stores what the OS reports, so
+    // the snapshot may come back with the old value if that failed.
+    setLaunchAtLogin: (enabled) => patch({ launchAtLogin: enabled }),
+    // The entry can be removed outside the app; a mismatch is stored and
+    // broadcast like any other change.
+    refreshLaunchAtLogin: async () => {
+      try {
+        await invoke("refresh_launch_at_login");
+      } catch (e) {
+        console.error("Failed to read launch at login state", e);
+      }
+    },
     setUpdateCheck: (enabled) => patch({ updateCheck: enabled }),
     setExitNodeSortOrder: (order) => patch({ exitNodeSortOrder: order }),
     setUpdateCheckResult: (manifest, checkedAt) =>
@@ -190,6 +288,19 @@ export function createSettingsStore(): SettingsStoreTuple {
       patch({ dismissedUpdateVersion: version }),
     setShowDetailedMetrics: (show) => patch({ showDetailedMetrics: show }),
     setFlagDisplay: (display) => patch({ flagDisplay: display }),
+    setMetricsEndpoint: (enabled) => patch({ metricsEndpoint: enabled }),
+    setMetricsPort: (port) => patch({ metricsPort: port }),
+    // The whole object is sent: the Rust patch replaces it as one value.
+    setNotifications: (notifications) => patch({ notifications }),
+    setTrayTooltipFields: (fields) => patch({ trayTooltipFields: fields }),
+    setTrayTitleFields: (fields) => patch({ trayTitleFields: fields }),
+    exportSettings: (destPath) => invoke<void>("export_settings", { destPath }),
+    importSettings: async (srcPath) => {
+      await invoke("import_settings", { srcPath });
+    },
+    resetSettings: async () => {
+      await invoke("reset_settings");
+    },
   } as const;
 
   const dispose = () => {
@@ -4,9 +4,11 @@ import type {
   DestinationState,
 } from "@src/services/vpnService.ts";
 import {
+  pinnedExitId,
   resolveAutoDestination,
   sortAlphaDestinations,
   sortByHealthScore,
+  sortByScore,
 } from "./destinations.ts";
 
 const BASE_DESTINATION: Destination = {
@@ -51,7 +53,7 @@ function makeUnavailable(id: string): DestinationState {
 
 describe("resolveAutoDestination", () => {
   it("returns null when no destinations are available", () => {
-    expect(resolveAutoDestination([], {}, null)).toBeNull();
+    expect(resolveAutoDestination([], {}, [])).toBeNull();
   });
 
   it("returns the best health-sorted node when no preferred location is set", () => {
@@ -63,7 +65,7 @@ describe("resolveAutoDestination", () => {
         slow: makeReadyToConnect("slow", 100_000_000),
         fast: makeReadyToConnect("fast", 20_000_000),
       },
-      null,
+      [],
     );
     expect(result?.id).toBe("fast");
   });
@@ -77,7 +79,7 @@ describe("resolveAutoDestination", () => {
         nodeA: makeReadyToConnect("nodeA", 10_000_000),
         preferred: makeReadyToConnect("preferred", 200_000_000),
       },
-      "preferred",
+      [{ id: "preferred" }],
     );
     expect(result?.id).toBe("preferred");
   });
@@ -87,7 +89,7 @@ describe("resolveAutoDestination", () => {
     const result = resolveAutoDestination(
       [nodeA],
       { nodeA: makeReadyToConnect("nodeA") },
-      "missing-preferred",
+      [{ id: "missing-preferred" }],
     );
     expect(result?.id).toBe("nodeA");
   });
@@ -101,7 +103,7 @@ describe("resolveAutoDestination", () => {
         nodeA: makeReadyToConnect("nodeA", 10_000_000),
         preferred: makeUnavailable("preferred"),
       },
-      "preferred",
+      [{ id: "preferred" }],
     );
     expect(result?.id).toBe("nodeA");
   });
@@ -123,7 +125,7 @@ describe("resolveAutoDestination", () => {
           },
         },
       },
-      "preferred",
+      [{ id: "preferred" }],
     );
     expect(result?.id).toBe("nodeA");
   });
@@ -133,10 +135,43 @@ describe("resolveAutoDestination", () => {
     const result = resolveAutoDestination(
       [nodeA],
       { nodeA: makeUnavailable("nodeA") },
-      null,
+      [],
     );
     expect(result?.id).toBe("nodeA");
   });
+
+  it("walks the preference list and picks the fastest metadata match", () => {
+    const down: Destination = { ...BASE_DESTINATION, id: "down" };
+    const us: Destination = {
+      ...BASE_DESTINATION,
+      id: "us",
+      meta: { location: "US" },
+    };
+    const euSlow: Destination = { ...BASE_DESTINATION, id: "euSlow" };
+    const euFast: Destination = { ...BASE_DESTINATION, id: "euFast" };
+    const result = resolveAutoDestination(
+      [down, us, euSlow, euFast],
+      {
+        down: makeUnavailable("down"),
+        us: { ...makeReadyToConnect("us", 5_000_000), destination: us },
+        euSlow: makeReadyToConnect("euSlow", 80_000_000),
+        euFast: makeReadyToConnect("euFast", 40_000_000),
+      },
+      [{ id: "down" }, { meta: { location: "EU" } }],
+    );
+    expect(result?.id).toBe("euFast");
+  });
+});
+
+describe("pinnedExitId", () => {
+  it("only pins a leading id preference", () => {
+    expect(pinnedExitId([{ id: "a" }, { meta: { location: "EU" } }])).toBe(
+      "a",
+    );
+    expect(pinnedExitId([{ meta: { location: "EU" } }, { id: "a" }]))
+      .toBeNull();
+    expect(pinnedExitId([])).toBeNull();
+  });
 });
 
 describe("sortAlphaDestinations", () => {
@@ -212,6 +247,26 @@ describe("sortByHealthScore", () => {
     expect(sorted[1].id).toBe("slow");
   });
 
+  it("ranks by smoothed latency over the latest ping when available", () => {
+    const steady: Destination = { ...BASE_DESTINATION, id: "steady" };
+    const spiky: Destination = { ...BASE_DESTINATION, id: "spiky" };
+    const stats = (smoothed_ms: number) => ({
+      samples: 10,
+      last_ms: 0,
+      smoothed_ms,
+      jitter_ms: 0,
+    });
+    const sorted = sortByHealthScore(
+      [steady, spiky],
+      {
+        steady: makeReadyToConnect("steady", 60_000_000),
+        spiky: makeReadyToConnect("spiky", 20_000_000),
+      },
+      { steady: stats(55), spiky: stats(140) },
+    );
+    expect(sorted.map((d) => d.id)).toEqual(["steady", "spiky"]);
+  });
+
   it("places ready node before non-ready regardless of alphabetical order", () => {
     const ready: Destination = { ...BASE_DESTINATION, id: "zzzz" };
     const notReady: Destination = { ...BASE_DESTINATION, id: "aaaa" };
@@ -261,3 +316,44 @@ describe("sortByHealthScore", () => {
     expect(sorted[1].id).toBe("unreachable");
   });
 });
+
+describe("sortByScore", () => {
+  const score = (value: number) => ({
+    score: value,
+    rtt: value,
+    load: value,
+    slots: value,
+    stability: value,
+  });
+
+  it("orders scored destinations by score, unscored after A–Z", () => {
+    const busy: Destination = { ...BASE_DESTINATION, id: "busy" };
+    const idle: Destination = { ...BASE_DESTINATION, id: "idle" };
+    const zeta: Destination = { ...BASE_DESTINATION, id: "zeta" };
+    const mu: Destination = { ...BASE_DESTINATION, id: "mu" };
+    const sorted = sortByScore([zeta, busy, mu, idle], {
+      busy: score(0.4),
+      idle: score(0.8),
+    });
+    expect(sorted.map((d) => d.id)).toEqual(["idle", "busy", "mu", "zeta"]);
+  });
+
+  it("drives automatic selection in score order", () => {
+    const close: Destination = { ...BASE_DESTINATION, id: "close" };
+    const far: Destination = { ...BASE_DESTINATION, id: "far" };
+    const destinations = {
+      close: makeReadyToConnect("close", 10_000_000),
+      far: makeReadyToConnect("far", 90_000_000),
+    };
+    const scores = { close: score(0.3), far: score(0.7) };
+    expect(
+      resolveAutoDestination([close, far], destinations, [], {
+        scores,
+        order: "score",
+      })?.id,
+    ).toBe("far");
+    expect(
+      resolveAutoDestination([close, far], destinations, [], { scores })?.id,
+    ).toBe("close");
+  });
+});
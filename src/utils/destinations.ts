@@ -1,20 +1,24 @@
 import type {
   Destination,
   DestinationState,
+  ExitScore,
+  LatencyStats,
 } from "@src/services/vpnService.ts";
+import type { ExitPreference, SortOrder } from "@src/stores/settingsStore.ts";
 import { getSortLatencyMs, isReadyToConnect } from "@src/utils/exitHealth.ts";
 
 /** Sort by latency ascending; no-latency entries go last, then A–Z. */
 export function sortByStartupLatency(
   available: Destination[],
   destinations: Record<string, DestinationState>,
+  latency: Record<string, LatencyStats> = {},
 ): Destination[] {
   return [...available].sort((a, b) => {
     const msA = destinations[a.id]
-      ? getSortLatencyMs(destinations[a.id])
+      ? getSortLatencyMs(destinations[a.id], latency[a.id])
       : null;
     const msB = destinations[b.id]
-      ? getSortLatencyMs(destinations[b.id])
+      ? getSortLatencyMs(destinations[b.id], latency[b.id])
       : null;
     if (msA !== null && msB !== null) return msA - msB;
     if (msA !== null) return -1;
@@ -23,6 +27,34 @@ export function sortByStartupLatency(
   });
 }
 
+export function matchesPreference(
+  d: Destination,
+  preference: ExitPreference,
+): boolean {
+  if ("id" in preference) return d.id === preference.id;
+  return Object.entries(preference.meta).every(([key, value]) =>
+    d.meta?.[key] === value
+  );
+}
+
+/** A leading id preference; failover never switches away from it. */
+export function pinnedExitId(preferences: ExitPreference[]): string | null {
+  const first = preferences[0];
+  return first && "id" in first ? first.id : null;
+}
+
+export function preferenceLabel(
+  preference: ExitPreference,
+  available: Destination[],
+): string {
+  if ("id" in preference) {
+    return destinationLabelById(preference.id, available);
+  }
+  return Object.entries(preference.meta)
+    .map(([key, value]) => `${key}=${value}`)
+    .join(", ");
+}
+
 export function getPreferredAvailabilityChangeMessage(
   previous: Destination[],
   next: Destination[],
@@ -42,6 +74,7 @@ export function getPreferredAvailabilityChangeMessage(
 export function sortByHealthScore(
   available: Destination[],
   destinations: Record<string, DestinationState>,
+  latency: Record<string, LatencyStats> = {},
 ): Destination[] {
   return [...available].sort((a, b) => {
     const aReady = isReadyToConnect(
@@ -52,10 +85,10 @@ export function sortByHealthScore(
     );
     if (aReady !== bReady) return aReady ? -1 : 1;
     const msA = destinations[a.id]
-      ? getSortLatencyMs(destinations[a.id])
+      ? getSortLatencyMs(destinations[a.id], latency[a.id])
       : null;
     const msB = destinations[b.id]
-      ? getSortLatencyMs(destinations[b.id])
+      ? getSortLatencyMs(destinations[b.id], latency[b.id])
       : null;
     if (msA !== null && msB !== null) return msA - msB;
     if (msA !== null) return -1;
@@ -64,6 +97,24 @@ export function sortByHealthScore(
   });
 }
 
+/**
+ * Sort: scored (ReadyToConnect/Connecting) destinations first, highest
+ * score first; all others after (A–Z).
+ */
+export function sortByScore(
+  available: Destination[],
+  scores: Record<string, ExitScore>,
+): Destination[] {
+  return [...available].sort((a, b) => {
+    const scoreA = scores[a.id]?.score;
+    const scoreB = scores[b.id]?.score;
+    if (scoreA !== undefined && scoreB !== undefined) return scoreB - scoreA;
+    if (scoreA !== undefined) return -1;
+    if (scoreB !== undefined) return 1;
+    return destinationLabel(a).localeCompare(destinationLabel(b));
+  });
+}
+
 /** Sort: ReadyToConnect/Connecting first (A–Z within tier), all others after (A–Z). */
 export function sortAlphaDestinations(
   available: Destination[],
@@ -81,27 +132,51 @@ export function sortAlphaDestinations(
   });
 }
 
+/** What automatic selection ranks by; see `pick_startup_target`. */
+export type AutoRanking = {
+  latency?: Record<string, LatencyStats>;
+  scores?: Record<string, ExitScore>;
+  order?: SortOrder;
+};
+
+/**
+ * Mirrors `pick_startup_target`: the first preference with a ReadyToConnect
+ * match wins (the best match for metadata filters), else the best node
+ * overall. "Best" is the highest score in `score` order, the lowest
+ * latency otherwise.
+ */
 export function resolveAutoDestination(
   available: Destination[],
   destinations: Record<string, DestinationState>,
-  preferredLocation: string | null,
+  preferredExits: ExitPreference[],
+  ranking: AutoRanking = {},
 ): Destination | null {
-  const candidates = sortByHealthScore(available, destinations);
+  const candidates = ranking.order === "score"
+    ? sortByScore(available, ranking.scores ?? {})
+    : sortByHealthScore(available, destinations, ranking.latency);
   if (candidates.length === 0) return null;
-  if (preferredLocation) {
-    const preferred = candidates.find((d) => d.id === preferredLocation);
-    if (
-      preferred &&
-      isReadyToConnect(
-        destinations[preferredLocation]?.route_health ?? undefined,
-      )
-    ) {
-      return preferred;
-    }
+  for (const preference of preferredExits) {
+    const preferred = candidates.find((d) =>
+      matchesPreference(d, preference) &&
+      isReadyToConnect(destinations[d.id]?.route_health ?? undefined)
+    );
+    if (preferred) return preferred;
   }
   return candidates[0] ?? null;
 }
 
+/** First destination matching the earliest preference, ready or not. */
+export function firstPreferredDestination(
+  available: Destination[],
+  preferredExits: ExitPreference[],
+): Destination | null {
+  for (const preference of preferredExits) {
+    const match = available.find((d) => matchesPreference(d, preference));
+    if (match) return match;
+  }
+  return null;
+}
+
 export function destinationLabel(d: Destination): string {
   const loc = d.meta?.location;
   return loc ? `${d.id} - ${loc}` : d.id;
@@ -2,6 +2,7 @@ import type {
   Destination,
   DestinationState,
   ExitHealthData,
+  LatencyStats,
   RouteHealthState,
   RouteHealthView,
 } from "@src/services/vpnService.ts";
@@ -162,8 +163,14 @@ export function getMaxHopCount(destinations: Destination[]): number {
   return Math.max(1, ...destinations.map((d) => getHopCount(d.routing)));
 }
 
-/** Latency ms for a ReadyToConnect/Connecting destination; null otherwise. */
-export function getSortLatencyMs(ds: DestinationState): number | null {
+/**
+ * Latency ms for a ReadyToConnect/Connecting destination; null otherwise.
+ * A ready exit ranks by its smoothed latency once it has history.
+ */
+export function getSortLatencyMs(
+  ds: DestinationState,
+  stats?: LatencyStats,
+): number | null {
   const rh = ds.route_health;
   if (!rh) return null;
   const { state } = rh;
@@ -171,7 +178,7 @@ export function getSortLatencyMs(ds: DestinationState): number | null {
     return state.tunnel_ping_rtt ?? state.exit.ping_rtt;
   }
   if (state.state === "ReadyToConnect") {
-    return state.exit.ping_rtt;
+    return stats?.smoothed_ms ?? state.exit.ping_rtt;
   }
   return null;
 }
@@ -1,10 +1,16 @@
 import { describe, expect, it } from "vitest";
-import type {
-  ChannelRelease,
-  UpdateManifest,
+import {
+  type ChannelRelease,
+  UpdateChannelSchema,
+  type UpdateManifest,
+  UpdateManifestSchema,
 } from "@src/stores/settingsStore.ts";
 import { evaluateUpdate, resolveChannelResync } from "./updateAvailability.ts";
 
+// Generated by `cargo test --test serialize_fixtures` in src-tauri, which
+// checks each case against the update notification's `available_update`.
+import updateCases from "../services/fixtures/update_availability.json";
+
 const release = (version: string): ChannelRelease => ({
   version,
   published_at: "2026-01-01T00:00:00Z",
@@ -319,6 +325,23 @@ describe("dismissed version", () => {
   });
 });
 
+describe("update_availability fixture", () => {
+  it.each(updateCases.cases)(
+    "offers $availableVersion to $packageVersion like the notification does",
+    ({ packageVersion, channel, dismissedVersion, availableVersion }) => {
+      const result = evaluateUpdate({
+        packageVersion,
+        manifest: UpdateManifestSchema.parse(updateCases.manifest),
+        channel: UpdateChannelSchema.nullable().parse(channel),
+        dismissedVersion,
+      });
+      expect(result.isUpdateAvailable ? result.availableVersion : null).toBe(
+        availableVersion,
+      );
+    },
+  );
+});
+
 describe("resolveChannelResync", () => {
   it("keeps a preference that already matches the installed package", () => {
     expect(resolveChannelResync({
@@ -4,6 +4,7 @@ import {
   type UpdateManifest,
   useSettingsStore,
 } from "@src/stores/settingsStore.ts";
+import { hasErrorCode } from "@src/services/appError.ts";
 
 export const AUTO_CHECK_INTERVAL_MS = 24 * 60 * 60 * 1000;
 
@@ -22,7 +23,7 @@ export async function runBackgroundCheck(): Promise<void> {
     });
     await settingsActions.setUpdateCheckResult(manifest, Date.now());
   } catch (e) {
-    if (e === "VpnNotConnected") {
+    if (hasErrorCode(e, "VpnNotConnected")) {
       setPendingCheckAfterConnect(true);
     }
   }